@@ -0,0 +1,40 @@
+//! Speed of `PrecisionMode::Low` against `PrecisionMode::Normal` on the cpu, the fp16 kernels of
+//! the `arm82` feature are only used on ARMv8.2 cpus, the two are the same elsewhere
+//!
+//! ```sh
+//! cargo bench --bench arm82
+//! ```
+use divan::Bencher;
+use mnn::*;
+
+fn main() {
+    divan::main();
+}
+
+/// Spatial sizes of the `[1, 3, size, size]` input of realesr
+const SIZES: [i32; 2] = [64, 128];
+
+fn run(bencher: Bencher, precision: PrecisionMode, size: i32) {
+    let net = Interpreter::from_file("tests/assets/realesr.mnn").unwrap();
+    let config = ScheduleConfig::new()
+        .with_type(ForwardType::CPU)
+        .with_num_threads(4)
+        .with_backend_config(BackendConfig::new().with_precision_mode(precision));
+    let mut session = net.create_session(config).unwrap();
+    session
+        .resize(&[("data", vec![1, 3, size, size])].into())
+        .unwrap();
+    session.bind_input::<f32>("data").unwrap();
+    session.bound_input_mut::<f32>("data").unwrap().fill(0.5);
+    bencher.bench_local(|| session.run_with_inputs().unwrap());
+}
+
+#[divan::bench(args = SIZES, sample_size = 1, sample_count = 20)]
+fn normal(bencher: Bencher, size: i32) {
+    run(bencher, PrecisionMode::Normal, size);
+}
+
+#[divan::bench(args = SIZES, sample_size = 1, sample_count = 20)]
+fn low(bencher: Bencher, size: i32) {
+    run(bencher, PrecisionMode::Low, size);
+}
@@ -0,0 +1,139 @@
+//! Throughput of the tensor copies between host tensors and the memory of the backends
+//!
+//! ```sh
+//! cargo bench --bench tensor_copy --features opencl
+//! ```
+use divan::{Bencher, counter::BytesCount};
+use mnn::*;
+
+fn main() {
+    divan::main();
+}
+
+/// Spatial sizes of the `[1, 3, size, size]` f32 tensors
+const SIZES: [i32; 4] = [64, 256, 512, 1024];
+
+fn shape(size: i32) -> [i32; 4] {
+    [1, 3, size, size]
+}
+
+fn bytes(size: i32) -> usize {
+    3 * size as usize * size as usize * core::mem::size_of::<f32>()
+}
+
+#[divan::bench(args = SIZES)]
+fn host_to_host(bencher: Bencher, size: i32) {
+    let src = Tensor::<Host<f32>>::new(shape(size), DimensionType::NCHW);
+    let mut dst = Tensor::<Host<f32>>::new(shape(size), DimensionType::NCHW);
+    bencher
+        .counter(BytesCount::new(bytes(size)))
+        .bench_local(|| dst.copy_from(&src).unwrap());
+}
+
+#[divan::bench(args = SIZES)]
+fn host_nchw_to_nhwc(bencher: Bencher, size: i32) {
+    let src = Tensor::<Host<f32>>::new(shape(size), DimensionType::NCHW);
+    let mut dst = Tensor::<Host<f32>>::new([1, size, size, 3], DimensionType::NHWC);
+    bencher
+        .counter(BytesCount::new(bytes(size)))
+        .bench_local(|| dst.copy_from(&src).unwrap());
+}
+
+/// The input of a session resized to `size`, a device tensor only has memory once it belongs to
+/// a backend
+fn session(forward: ForwardType, size: i32) -> (Interpreter, Session) {
+    let net = Interpreter::from_file("tests/assets/realesr.mnn").unwrap();
+    let config = ScheduleConfig::new()
+        .with_type(forward)
+        .with_backup_type(forward);
+    let mut session = net.create_session(config).unwrap();
+    let mut input = net.input::<f32>(&session, "data").unwrap();
+    net.resize_tensor(&mut input, shape(size));
+    drop(input);
+    net.resize_session(&mut session);
+    (net, session)
+}
+
+fn host_to_device(bencher: Bencher, forward: ForwardType, size: i32) {
+    let (net, session) = session(forward, size);
+    let mut device = net.input::<f32>(&session, "data").unwrap();
+    let host = device.create_host_tensor_from_device(false);
+    bencher
+        .counter(BytesCount::new(bytes(size)))
+        .bench_local(|| {
+            device.copy_from_host_tensor(&host).unwrap();
+            net.wait(&session);
+        });
+}
+
+fn device_to_host(bencher: Bencher, forward: ForwardType, size: i32) {
+    let (net, session) = session(forward, size);
+    let device = net.input::<f32>(&session, "data").unwrap();
+    let mut host = device.create_host_tensor_from_device(false);
+    bencher
+        .counter(BytesCount::new(bytes(size)))
+        .bench_local(|| device.copy_to_host_tensor(&mut host).unwrap());
+}
+
+#[divan::bench_group(sample_size = 10, sample_count = 20)]
+mod cpu {
+    use super::*;
+
+    #[divan::bench(args = SIZES)]
+    fn host_to_device(bencher: Bencher, size: i32) {
+        super::host_to_device(bencher, ForwardType::CPU, size);
+    }
+
+    #[divan::bench(args = SIZES)]
+    fn device_to_host(bencher: Bencher, size: i32) {
+        super::device_to_host(bencher, ForwardType::CPU, size);
+    }
+}
+
+#[cfg(feature = "opencl")]
+#[divan::bench_group(sample_size = 10, sample_count = 20)]
+mod opencl {
+    use super::*;
+
+    #[divan::bench(args = SIZES)]
+    fn host_to_device(bencher: Bencher, size: i32) {
+        super::host_to_device(bencher, ForwardType::OpenCL, size);
+    }
+
+    #[divan::bench(args = SIZES)]
+    fn device_to_host(bencher: Bencher, size: i32) {
+        super::device_to_host(bencher, ForwardType::OpenCL, size);
+    }
+}
+
+#[cfg(feature = "vulkan")]
+#[divan::bench_group(sample_size = 10, sample_count = 20)]
+mod vulkan {
+    use super::*;
+
+    #[divan::bench(args = SIZES)]
+    fn host_to_device(bencher: Bencher, size: i32) {
+        super::host_to_device(bencher, ForwardType::Vulkan, size);
+    }
+
+    #[divan::bench(args = SIZES)]
+    fn device_to_host(bencher: Bencher, size: i32) {
+        super::device_to_host(bencher, ForwardType::Vulkan, size);
+    }
+}
+
+#[cfg(feature = "metal")]
+#[divan::bench_group(sample_size = 10, sample_count = 20)]
+mod metal {
+    use super::*;
+
+    #[divan::bench(args = SIZES)]
+    fn host_to_device(bencher: Bencher, size: i32) {
+        super::host_to_device(bencher, ForwardType::Metal, size);
+    }
+
+    #[divan::bench(args = SIZES)]
+    fn device_to_host(bencher: Bencher, size: i32) {
+        super::device_to_host(bencher, ForwardType::Metal, size);
+    }
+}
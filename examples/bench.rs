@@ -0,0 +1,66 @@
+//! Benchmark a model with [`Benchmark`]
+//!
+//! ```sh
+//! cargo run --release --example bench --features bench -- tests/assets/realesr.mnn --size 64
+//! ```
+use mnn::*;
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Parser, Clone)]
+pub struct Cli {
+    #[clap(default_value = "tests/assets/realesr.mnn")]
+    model: PathBuf,
+    /// Resize the spatial dimensions of the first input to this size
+    #[clap(short, long)]
+    size: Option<i32>,
+    #[clap(short, long, default_value = "cpu")]
+    forward: ForwardType,
+    #[clap(short, long, default_value_t = 4)]
+    threads: i32,
+    #[clap(short, long, default_value_t = 5)]
+    warmup: usize,
+    #[clap(short, long, default_value_t = 20)]
+    iters: usize,
+}
+
+pub fn main() -> anyhow::Result<()> {
+    use clap::Parser;
+    let cli = Cli::parse();
+    let interpreter = Interpreter::from_file(&cli.model)?;
+    let config = ScheduleConfig::new()
+        .with_type(cli.forward)
+        .with_num_threads(cli.threads);
+    let mut session = interpreter.create_session(config)?;
+
+    let name = session
+        .inputs()
+        .into_keys()
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("The model doesn't have any inputs"))?;
+    if let Some(size) = cli.size {
+        let mut input = interpreter.input::<f32>(&session, &name)?;
+        let shape = input.shape();
+        interpreter.resize_tensor(&mut input, [shape[0], shape[1], size, size]);
+        drop(input);
+        interpreter.resize_session(&mut session);
+    }
+    // Set the input once so the runs don't measure the copies
+    session.bind_input::<f32>(&name)?;
+    session.bound_input_mut::<f32>(&name)?.fill(0.5);
+
+    let report = Benchmark::new(&mut session)
+        .warmup(cli.warmup)
+        .iters(cli.iters)
+        .run(|session| {
+            session.run_with_inputs()?;
+            interpreter.wait(session);
+            Ok(())
+        })?;
+    println!("{} runs of {}", report.iters, cli.model.display());
+    println!("min:    {:?}", report.min);
+    println!("median: {:?}", report.median);
+    println!("p99:    {:?}", report.p99);
+    println!("mean:   {:?}", report.mean);
+    println!("throughput: {:.2} runs/s", report.throughput);
+    Ok(())
+}
@@ -47,7 +47,7 @@ macro_rules! time {
 pub fn main() -> anyhow::Result<()> {
     use clap::Parser;
     let cli = Cli::parse();
-    let mut interpreter = Interpreter::from_file(&cli.model)?;
+    let interpreter = Interpreter::from_file(&cli.model)?;
     if !cli.no_cache {
         interpreter.set_cache_file(cli.model.with_extension("cache"), 128)?;
     }
@@ -62,9 +62,9 @@ pub fn main() -> anyhow::Result<()> {
 
     let mut config = ScheduleConfig::new();
     config.set_type(cli.forward);
-    let mut session = time!(interpreter.create_session(config)?; "create session");
+    let session = time!(interpreter.create_session(config)?; "create session");
     if !cli.no_cache {
-        interpreter.update_cache_file(&mut session)?;
+        interpreter.update_cache_file(&session)?;
     }
 
     let mut current = 0;
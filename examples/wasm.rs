@@ -0,0 +1,76 @@
+//! Run a model that javascript passes as an `ArrayBuffer` on `wasm32-unknown-emscripten`
+//!
+//! Build with
+//! ```sh
+//! RUSTFLAGS="-C link-arg=-sEXPORTED_FUNCTIONS=_main,_model_alloc,_model_free,_model_run \
+//!     -C link-arg=-sEXPORTED_RUNTIME_METHODS=HEAPU8" \
+//!     cargo build --release --example wasm --target wasm32-unknown-emscripten
+//! ```
+//! and load the model from javascript with
+//! ```js
+//! const bytes = new Uint8Array(await (await fetch("model.mnn")).arrayBuffer());
+//! const ptr = Module._model_alloc(bytes.length);
+//! Module.HEAPU8.set(bytes, ptr);
+//! const outputs = Module._model_run(ptr, bytes.length);
+//! Module._model_free(ptr, bytes.length);
+//! ```
+//! The model is copied once from javascript into the wasm memory and once by MNN, it never goes
+//! through a `Vec` on the rust side.
+//! Since nothing here touches files emscripten doesn't link its filesystem, using
+//! [`Interpreter::from_file`] or a cache file pulls it back in.
+use mnn::*;
+
+/// Allocate `len` bytes in the wasm memory for javascript to copy the model into
+#[unsafe(no_mangle)]
+pub extern "C" fn model_alloc(len: usize) -> *mut u8 {
+    let mut buffer = std::mem::ManuallyDrop::new(vec![0u8; len]);
+    buffer.as_mut_ptr()
+}
+
+/// Free a buffer returned by [`model_alloc`]
+///
+/// # Safety
+/// `ptr` and `len` must come from a single call to [`model_alloc`]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn model_free(ptr: *mut u8, len: usize) {
+    drop(unsafe { Vec::from_raw_parts(ptr, len, len) });
+}
+
+/// Run the model in the buffer with all inputs set to 1 and return the number of output elements
+/// (or -1 on failure)
+///
+/// # Safety
+/// `ptr` must be valid for reads of `len` bytes
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn model_run(ptr: *const u8, len: usize) -> i64 {
+    match unsafe { run(ptr, len) } {
+        Ok(elements) => elements as i64,
+        Err(e) => {
+            eprintln!("{e:?}");
+            -1
+        }
+    }
+}
+
+unsafe fn run(ptr: *const u8, len: usize) -> Result<usize> {
+    let net = unsafe { Interpreter::from_raw_parts(ptr, len)? };
+    let session = net.create_session(ScheduleConfig::new())?;
+    for input in net.inputs(&session).iter() {
+        input.tensor::<f32>()?.fill(1.0);
+    }
+    net.run_session(&session)?;
+    let outputs = session.collect_outputs::<f32>()?;
+    Ok(outputs.values().map(Vec::len).sum())
+}
+
+pub fn main() -> anyhow::Result<()> {
+    // Natively the model can be passed as the first argument
+    let Some(model) = std::env::args().nth(1) else {
+        println!("Call model_run from javascript or pass a model path");
+        return Ok(());
+    };
+    let bytes = std::fs::read(model)?;
+    let elements = unsafe { model_run(bytes.as_ptr(), bytes.len()) };
+    println!("output elements: {elements}");
+    Ok(())
+}
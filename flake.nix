@@ -132,6 +132,14 @@
                 partitions = 1;
                 partitionType = "count";
               });
+            # Baseline x86 build without the SSE / AVX kernels
+            mnn-portable-cpu-nextest = craneLib.cargoNextest (commonArgs
+              // {
+                inherit cargoArtifacts;
+                partitions = 1;
+                partitionType = "count";
+                cargoExtraArgs = "--features portable-cpu";
+              });
             mnn-sys-clippy = craneLib.cargoClippy (commonArgs
               // {
                 inherit cargoArtifacts;
@@ -211,13 +211,13 @@ impl SessionRunner {
         }
     }
 
-    pub fn create(mut net: Interpreter, config: ScheduleConfig) -> Result<Self> {
+    pub fn create(net: Interpreter, config: ScheduleConfig) -> Result<Self> {
         #[cfg(feature = "tracing")]
         tracing::trace!("Creating session");
         #[cfg(feature = "tracing")]
         let now = std::time::Instant::now();
-        let mut session = net.create_session(config)?;
-        net.update_cache_file(&mut session)?;
+        let session = net.create_session(config)?;
+        net.update_cache_file(&session)?;
         #[cfg(feature = "tracing")]
         tracing::trace!("Session created in {:?}", now.elapsed());
         Ok(Self {
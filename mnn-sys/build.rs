@@ -49,6 +49,223 @@ static MNN_COMPILE: LazyLock<bool> = LazyLock::new(|| {
         .unwrap_or(true)
 });
 
+/// Build from `MNN_SRC` / the vendor directory in place instead of copying it to the out dir
+///
+/// The MNN headers have to be patched already (see [`patch_headers`]) since the source isn't
+/// modified
+static MNN_NO_VENDOR_COPY: LazyLock<bool> = LazyLock::new(|| {
+    std::env::var("MNN_NO_VENDOR_COPY")
+        .ok()
+        .is_some_and(|v| matches!(v.as_str(), "1" | "true" | "yes"))
+});
+
+/// Whether the target advertises a cpu feature (e.g. with `-C target-cpu` / `-C target-feature`)
+fn has_target_feature(feature: &str) -> bool {
+    std::env::var("CARGO_CFG_TARGET_FEATURE")
+        .unwrap_or_default()
+        .split(',')
+        .any(|f| f == feature)
+}
+
+/// Whether to build the SSE / AVX kernels of MNN
+///
+/// MNN picks the kernels with cpuid at runtime so they are safe on baseline x86-64, they are
+/// disabled with the portable-cpu feature or when the target doesn't even have SSE2 (e.g. i586)
+static USE_SSE: LazyLock<bool> = LazyLock::new(|| {
+    !cfg!(feature = "portable-cpu")
+        && (!TARGET_ARCH.starts_with("x86") || has_target_feature("sse2"))
+});
+
+/// `-msse4.1` if the target advertises SSE4.1, used for the global flags on windows
+static SSE41_FLAG: LazyLock<&str> = LazyLock::new(|| {
+    if *USE_SSE && has_target_feature("sse4.1") {
+        " -msse4.1"
+    } else {
+        ""
+    }
+});
+
+/// Build the mnn_c shims and MNN with AddressSanitizer (asan feature)
+///
+/// Only for debug builds with gcc / clang on linux, macos and android, the feature is ignored with
+/// a warning otherwise
+static ASAN: LazyLock<bool> = LazyLock::new(|| {
+    if !cfg!(feature = "asan") {
+        return false;
+    }
+    if std::env::var("PROFILE").as_deref() != Ok("debug") {
+        println!("cargo:warning=The asan feature is ignored for release builds");
+        return false;
+    }
+    let compiler = cc::Build::new().cpp(true).get_compiler();
+    let supported = (compiler.is_like_gnu() || compiler.is_like_clang())
+        && ["linux", "macos", "android"].contains(&TARGET_OS.as_str());
+    if !supported {
+        println!(
+            "cargo:warning=The asan feature is ignored, AddressSanitizer needs gcc or clang on linux, macos or android"
+        );
+    }
+    supported
+});
+
+const ASAN_FLAGS: [&str; 2] = ["-fsanitize=address", "-fno-omit-frame-pointer"];
+
+/// Fail before building anything if the enabled features can't give a usable MNN for the target
+///
+/// MNN always builds its CPU backend, so this catches targets without the C++ standard library
+/// it needs and backend features that only exist on some platforms, which would otherwise fail
+/// deep in the cmake build or at link time. All the conflicts are listed at once.
+fn check_features() -> Result<()> {
+    let target = std::env::var("TARGET").unwrap_or_default();
+    let apple = ["macos", "ios", "tvos", "visionos", "watchos"].contains(&TARGET_OS.as_str());
+    let mut conflicts = Vec::new();
+    if ["unknown", "none"].contains(&TARGET_OS.as_str()) {
+        conflicts.push(format!(
+            "the CPU backend needs a C++ standard library, which {target} doesn't have (use wasm32-unknown-emscripten for wasm)"
+        ));
+    }
+    for (option, feature) in [(CxxOption::METAL, "metal"), (CxxOption::COREML, "coreml")] {
+        if option.enabled() && !apple {
+            conflicts.push(format!(
+                "the {feature} feature is only supported on apple targets, not {target}"
+            ));
+        }
+    }
+    if CxxOption::OPENGL.enabled() && apple {
+        conflicts.push(format!(
+            "the opengl feature needs OpenGL ES 3 and EGL, which {target} doesn't have"
+        ));
+    }
+    if CxxOption::OPENMP.enabled() && CxxOption::THREADPOOL.enabled() {
+        conflicts.push(
+            "the openmp feature can't be used with the mnn-threadpool feature (enabled by default), \
+             use default-features = false"
+                .into(),
+        );
+    }
+    if CxxOption::MINI.enabled() && CxxOption::CONVERTER.enabled() {
+        conflicts.push(
+            "the mini feature can't be used with the converter feature, MNN_BUILD_MINI drops the \
+             shape inference the converter needs"
+                .into(),
+        );
+    }
+    ensure!(
+        conflicts.is_empty(),
+        "The enabled features of mnn-sys can't be built for {target}:\n  - {}",
+        conflicts.join("\n  - ")
+    );
+    Ok(())
+}
+
+/// Link the AddressSanitizer runtime of the compiler the shims were built with
+fn link_asan_runtime() -> Result<()> {
+    let compiler = cc::Build::new().cpp(true).get_compiler();
+    if !compiler.is_like_clang() {
+        println!("cargo:rustc-link-lib=dylib=asan");
+        return Ok(());
+    }
+    let output = compiler
+        .to_command()
+        .arg("-print-runtime-dir")
+        .output()
+        .context("Failed to find the runtime directory of clang")?;
+    let runtime_dir = String::from_utf8(output.stdout)?;
+    println!("cargo:rustc-link-search=native={}", runtime_dir.trim());
+    match TARGET_OS.as_str() {
+        "macos" => println!("cargo:rustc-link-lib=dylib=clang_rt.asan_osx_dynamic"),
+        "android" => println!(
+            "cargo:rustc-link-lib=dylib=clang_rt.asan-{}-android",
+            *TARGET_ARCH
+        ),
+        _ => println!("cargo:rustc-link-lib=dylib=clang_rt.asan-{}", *TARGET_ARCH),
+    }
+    Ok(())
+}
+
+/// Flags that put each function / global in its own section so the linker can drop the unused
+/// ones (mini feature, not for msvc which uses /Gy and /OPT:REF by default in release)
+const SECTION_FLAGS: [&str; 2] = ["-ffunction-sections", "-fdata-sections"];
+
+/// Print the size of the static MNN library built with the mini feature
+///
+/// Compare it with a build without the feature, the reduction depends on the backends and the
+/// target
+fn report_lib_size(lib_dir: &Path) {
+    for name in ["libMNN.a", "MNN.lib"] {
+        if let core::result::Result::Ok(metadata) = std::fs::metadata(lib_dir.join(name)) {
+            println!(
+                "cargo:warning=mini build: {name} is {:.1} MiB",
+                metadata.len() as f64 / 1024.0 / 1024.0
+            );
+        }
+    }
+}
+
+/// The C++ standard MNN is built with, `MNN_CXX_STANDARD` overrides the default of 14
+static MNN_CXX_STANDARD: LazyLock<String> = LazyLock::new(|| {
+    let Some(standard) = std::env::var("MNN_CXX_STANDARD").ok() else {
+        return "14".into();
+    };
+    if ["11", "14", "17", "20", "23"].contains(&standard.trim()) {
+        standard.trim().into()
+    } else {
+        println!("cargo:warning=Ignoring MNN_CXX_STANDARD={standard:?}, expected one of 11, 14, 17, 20 or 23");
+        "14".into()
+    }
+});
+
+/// Extra `-DKEY=VALUE` defines for the MNN cmake build from `MNN_CMAKE_ARGS`
+///
+/// The defines are separated by whitespace or `;`, anything that doesn't look like a cmake define
+/// is skipped with a warning
+fn extra_cmake_defines() -> Vec<(String, String)> {
+    let args = std::env::var("MNN_CMAKE_ARGS").unwrap_or_default();
+    args.split(|c: char| c.is_whitespace() || c == ';')
+        .filter(|arg| !arg.is_empty())
+        .filter_map(|arg| {
+            let define = arg
+                .strip_prefix("-D")
+                .and_then(|define| define.split_once('='))
+                .filter(|(key, _)| {
+                    // KEY or KEY:TYPE
+                    let name = key.split_once(':').map_or(*key, |(name, _)| name);
+                    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                });
+            if define.is_none() {
+                println!("cargo:warning=Ignoring {arg:?} in MNN_CMAKE_ARGS, expected -DKEY=VALUE");
+            }
+            define.map(|(key, value)| (key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// The cmake generator from `MNN_CMAKE_GENERATOR` (e.g. `Ninja`), `None` for cmake's default
+///
+/// A Ninja generator falls back to the default one with a warning if `ninja` isn't on the PATH
+static CMAKE_GENERATOR: LazyLock<Option<String>> = LazyLock::new(|| {
+    let generator = std::env::var("MNN_CMAKE_GENERATOR")
+        .ok()
+        .filter(|generator| !generator.trim().is_empty())?;
+    let ninja = generator.to_ascii_lowercase().starts_with("ninja");
+    if ninja && !has_ninja() {
+        println!(
+            "cargo:warning=Ignoring MNN_CMAKE_GENERATOR={generator:?}, ninja wasn't found on the PATH"
+        );
+        return None;
+    }
+    Some(generator)
+});
+
+fn has_ninja() -> bool {
+    std::process::Command::new("ninja")
+        .arg("--version")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
 const HALIDE_SEARCH: &str =
     r#"HALIDE_ATTRIBUTE_ALIGN(1) halide_type_code_t code; // halide_type_code_t"#;
 const TRACING_SEARCH: &str = "#define MNN_PRINT(format, ...) printf(format, ##__VA_ARGS__)\n#define MNN_ERROR(format, ...) printf(format, ##__VA_ARGS__)";
@@ -93,6 +310,7 @@ fn ensure_vendor_exists(vendor: impl AsRef<Path>) -> Result<()> {
 fn main() -> Result<()> {
     println!("cargo:rerun-if-changed=build.rs");
     println!("cargo:rerun-if-env-changed=MNN_SRC");
+    check_features()?;
     let out_dir = PathBuf::from(std::env::var("OUT_DIR")?);
     let source = PathBuf::from(
         std::env::var("MNN_SRC")
@@ -102,65 +320,72 @@ fn main() -> Result<()> {
 
     ensure_vendor_exists(&source)?;
 
-    let vendor = out_dir.join("vendor");
-    // std::fs::remove_dir_all(&vendor).ok();
-    if !vendor.exists() {
-        fs_extra::dir::copy(
-            &source,
-            &vendor,
-            &fs_extra::dir::CopyOptions::new()
-                .overwrite(true)
-                .copy_inside(true),
-        )
-        .context("Failed to copy vendor")?;
-        let intptr = vendor.join("include").join("MNN").join("HalideRuntime.h");
-        #[cfg(unix)]
-        std::fs::set_permissions(&intptr, std::fs::Permissions::from_mode(0o644))?;
-
-        use itertools::Itertools;
-        let intptr_contents = std::fs::read_to_string(&intptr)?;
-        let patched = intptr_contents.lines().collect::<Vec<_>>();
-        if let Some((idx, _)) = patched
-            .iter()
-            .find_position(|line| line.contains(HALIDE_SEARCH))
-        {
-            // remove the last line and the next 3 lines
-            let patched = patched
-                .into_iter()
-                .enumerate()
-                .filter(|(c_idx, _)| !(*c_idx == idx - 1 || (idx + 1..=idx + 3).contains(c_idx)))
-                .map(|(_, c)| c)
-                .collect::<Vec<_>>();
-
-            std::fs::write(intptr, patched.join("\n"))?;
-        }
-
-        let mnn_define = vendor.join("include").join("MNN").join("MNNDefine.h");
-        let patched =
-            std::fs::read_to_string(&mnn_define)?.replace(TRACING_SEARCH, TRACING_REPLACE);
-        #[cfg(unix)]
-        std::fs::set_permissions(&mnn_define, std::fs::Permissions::from_mode(0o644))?;
-        std::fs::write(mnn_define, patched)?;
-    }
-
+    println!("cargo:rerun-if-env-changed=MNN_NO_VENDOR_COPY");
+    let vendor = if *MNN_NO_VENDOR_COPY {
+        ensure_headers_patched(source.join("include"))?;
+        source.clone()
+    } else {
+        let vendor = out_dir.join("vendor");
+        copy_vendor(&source, &vendor)?;
+        vendor
+    };
+
+    println!("cargo:rerun-if-env-changed=MNN_COMPILE");
+    println!("cargo:rerun-if-env-changed=MNN_CXX_STANDARD");
+    println!("cargo:rerun-if-env-changed=MNN_CMAKE_ARGS");
+    println!("cargo:rerun-if-env-changed=MNN_CMAKE_GENERATOR");
+    println!("cargo:rerun-if-env-changed=MNN_LIB_DIR");
+    println!("cargo:rerun-if-env-changed=MNN_INCLUDE_DIR");
+    let mut include = vendor.join("include");
     if *MNN_COMPILE {
         let install_dir = out_dir.join("mnn-install");
         build_cmake(&vendor, &install_dir)?;
+        if CxxOption::MINI.enabled() {
+            report_lib_size(&install_dir.join("lib"));
+        }
         println!(
             "cargo:rustc-link-search=native={}",
             install_dir.join("lib").display()
         );
     } else if let core::result::Result::Ok(lib_dir) = std::env::var("MNN_LIB_DIR") {
+        if let Some(prebuilt) = std::env::var_os("MNN_INCLUDE_DIR") {
+            let expected = mnn_version(vendor.join("include"))?;
+            let found = mnn_version(&prebuilt)?;
+            ensure!(
+                expected == found,
+                "The headers in MNN_INCLUDE_DIR are for MNN {found} but mnn-sys wraps MNN {expected}, use a prebuilt MNN {expected} or set MNN_COMPILE=1 to build the vendored MNN"
+            );
+            include = prebuilt_headers(prebuilt, "MNN_INCLUDE_DIR", &out_dir)?;
+        }
+        ensure_prebuilt_lib(&lib_dir, &include)?;
         println!("cargo:rustc-link-search=native={}", lib_dir);
+    } else if let Some(system) = pkg_config_mnn() {
+        let expected = mnn_version(vendor.join("include"))?;
+        if system.version != expected {
+            println!(
+                "cargo:warning=pkg-config found MNN {} but mnn-sys wraps MNN {expected}, the mnn_c wrappers and bindings may not match it",
+                system.version
+            );
+        }
+        include = prebuilt_headers(&system.include_dir, "pkg-config includedir", &out_dir)?;
+        ensure_prebuilt_lib(&system.lib_dir, &include)?;
+        println!(
+            "cargo:rustc-link-search=native={}",
+            system.lib_dir.display()
+        );
     } else {
-        panic!("MNN_LIB_DIR not set while MNN_COMPILE is false");
+        panic!("MNN_LIB_DIR not set and no MNN found with pkg-config while MNN_COMPILE is false");
     }
 
-    mnn_c_build(PathBuf::from(MANIFEST_DIR).join("mnn_c"), &vendor)
+    mnn_c_build(PathBuf::from(MANIFEST_DIR).join("mnn_c"), &vendor, &include)
         .with_context(|| "Failed to build mnn_c")?;
-    mnn_c_bindgen(&vendor, &out_dir).with_context(|| "Failed to generate mnn_c bindings")?;
-    mnn_cpp_bindgen(&vendor, &out_dir).with_context(|| "Failed to generate mnn_cpp bindings")?;
-    println!("cargo:include={vendor}/include", vendor = vendor.display());
+    mnn_c_bindgen(&include, &out_dir).with_context(|| "Failed to generate mnn_c bindings")?;
+    mnn_cpp_bindgen(&include, &out_dir).with_context(|| "Failed to generate mnn_cpp bindings")?;
+    println!("cargo:include={}", include.display());
+    let version = mnn_version(&include)?;
+    println!("cargo:version={version}");
+    println!("cargo:rustc-env=MNN_VERSION={version}");
+    write_build_options(&out_dir)?;
     if *TARGET_OS == "macos" {
         #[cfg(feature = "metal")]
         println!("cargo:rustc-link-lib=framework=Foundation");
@@ -176,9 +401,41 @@ fn main() -> Result<()> {
         println!("cargo:rustc-link-lib=framework=OpenCL");
         #[cfg(feature = "opengl")]
         println!("cargo:rustc-link-lib=framework=OpenGL");
-    } else {
-        // #[cfg(feature = "opencl")]
-        // println!("cargo:rustc-link-lib=static=opencl");
+    } else if *TARGET_OS == "android" {
+        #[cfg(feature = "nnapi")]
+        println!("cargo:rustc-link-lib=dylib=neuralnetworks");
+        #[cfg(feature = "nnapi")]
+        println!("cargo:rustc-link-lib=dylib=android");
+        #[cfg(feature = "nnapi")]
+        println!("cargo:rustc-link-lib=dylib=log");
+        #[cfg(feature = "opengl")]
+        println!("cargo:rustc-link-lib=dylib=GLESv3");
+        #[cfg(feature = "opengl")]
+        println!("cargo:rustc-link-lib=dylib=EGL");
+    } else if *TARGET_OS == "linux" {
+        // libGLESv2 also provides the OpenGL ES 3 entry points on desktop linux
+        #[cfg(feature = "opengl")]
+        link_loader("OpenGL ES", &["GLESv2", "GL"], "GL_LIB_DIR", GL_HELP)?;
+        #[cfg(feature = "opengl")]
+        link_loader("EGL", &["EGL"], "GL_LIB_DIR", GL_HELP)?;
+    }
+    // Without the system-loaders feature MNN loads the OpenCL / Vulkan loaders with dlopen at
+    // runtime so there is nothing to link
+    if *TARGET_OS != "macos" && *TARGET_OS != "ios" {
+        #[cfg(all(feature = "system-loaders", feature = "opencl"))]
+        link_loader(
+            "OpenCL",
+            &["OpenCL"],
+            "OPENCL_LIB_DIR",
+            "Install the OpenCL ICD loader (e.g. ocl-icd-opencl-dev on debian / ubuntu, ocl-icd on arch / fedora) or set OPENCL_LIB_DIR to the directory containing it.\nOr disable the system-loaders feature to let MNN load it at runtime.",
+        )?;
+        #[cfg(all(feature = "system-loaders", feature = "vulkan"))]
+        link_loader(
+            "Vulkan",
+            &["vulkan", "vulkan-1"],
+            "VULKAN_LIB_DIR",
+            "Install the Vulkan loader (e.g. libvulkan-dev on debian / ubuntu, vulkan-icd-loader on arch) or the Vulkan SDK, or set VULKAN_LIB_DIR to the directory containing it.\nOr disable the system-loaders feature to let MNN load it at runtime.",
+        )?;
     }
     if is_emscripten() {
         // println!("cargo:rustc-link-lib=static=stdc++");
@@ -194,7 +451,432 @@ fn main() -> Result<()> {
             wasm32_emscripten_libs.display()
         );
     }
+    // The converter library (and the protobuf it's built with) depend on MNN so they have to be
+    // linked before it
+    #[cfg(feature = "converter")]
+    {
+        if *MNN_COMPILE {
+            println!(
+                "cargo:rustc-link-search=native={}",
+                out_dir.join("build").display()
+            );
+        }
+        println!("cargo:rustc-link-lib=static=MNNConvertDeps");
+        println!("cargo:rustc-link-lib=static=protobuf");
+    }
     println!("cargo:rustc-link-lib=static=MNN");
+    // The static MNN library doesn't carry its OpenMP runtime with it
+    #[cfg(feature = "openmp")]
+    link_openmp();
+    if *ASAN {
+        link_asan_runtime()?;
+    }
+    Ok(())
+}
+
+/// Link the OpenMP runtime of the compiler MNN was built with, `OPENMP_LIB` overrides it
+#[cfg(feature = "openmp")]
+fn link_openmp() {
+    println!("cargo:rerun-if-env-changed=OPENMP_LIB");
+    if let core::result::Result::Ok(lib) = std::env::var("OPENMP_LIB") {
+        println!("cargo:rustc-link-lib={lib}");
+        return;
+    }
+    match TARGET_OS.as_str() {
+        // MSVC links vcomp itself from the /openmp objects
+        "windows" => {}
+        // clang (apple / the NDK) uses the llvm runtime
+        "macos" | "ios" | "android" => println!("cargo:rustc-link-lib=omp"),
+        _ => println!("cargo:rustc-link-lib=gomp"),
+    }
+}
+
+/// Write the options MNN is built with to `build_options.rs` (`mnn_sys::BUILD_OPTIONS`)
+///
+/// With a prebuilt MNN (`MNN_LIB_DIR`) these are the options requested by the features, not
+/// necessarily the ones the library was built with
+fn write_build_options(out_dir: &Path) -> Result<()> {
+    use std::fmt::Write as _;
+    let options = [
+        CxxOption::METAL,
+        CxxOption::COREML,
+        CxxOption::OPENCL,
+        CxxOption::OPENGL,
+        CxxOption::VULKAN,
+        CxxOption::NNAPI.android_only(),
+        CxxOption::THREADPOOL,
+        CxxOption::OPENMP,
+        CxxOption::LOW_MEMORY,
+        CxxOption::MINI,
+        CxxOption::ARM82.aarch64_only(),
+        CxxOption::BF16,
+        CxxOption::CONVERTER,
+        CxxOption::SYSTEM_LIB,
+    ]
+    .map(|option| (option.name, option.enabled()));
+    let mut contents = String::from(
+        "/// The cmake options of MNN and whether they are enabled, written by the build script\n\
+         pub const BUILD_OPTIONS: &[(&str, bool)] = &[\n",
+    );
+    for (name, enabled) in options.into_iter().chain([
+        ("MNN_USE_SSE", *USE_SSE && TARGET_ARCH.starts_with("x86")),
+        ("MNN_COMPILE", *MNN_COMPILE),
+        ("ASAN", *ASAN),
+    ]) {
+        writeln!(contents, "    ({name:?}, {enabled}),")?;
+    }
+    contents.push_str("];\n");
+    std::fs::write(out_dir.join("build_options.rs"), contents)?;
+    Ok(())
+}
+
+/// Find and link a gpu library (e.g. the OpenCL ICD loader for the system-loaders feature or the
+/// GL libraries for the opengl feature)
+///
+/// Fails with `help` when the library can't be found instead of failing at link time
+#[cfg(any(feature = "system-loaders", feature = "opengl"))]
+fn link_loader(api: &str, names: &[&str], env: &str, help: &str) -> Result<()> {
+    println!("cargo:rerun-if-env-changed={env}");
+    let env_dir = std::env::var_os(env).map(PathBuf::from);
+    // The android NDK / cross compilation sysroots are searched by the linker itself
+    if *TARGET_OS == "android" && env_dir.is_none() {
+        println!("cargo:rustc-link-lib=dylib={}", names[0]);
+        return Ok(());
+    }
+    let sdk_dirs = std::env::var_os("VULKAN_SDK")
+        .filter(|_| api == "Vulkan")
+        .map(PathBuf::from)
+        .map(|sdk| vec![sdk.join("lib"), sdk.join("Lib")])
+        .unwrap_or_default();
+    let system_dirs = [
+        PathBuf::from("/usr/lib"),
+        PathBuf::from("/usr/lib64"),
+        PathBuf::from("/usr/local/lib"),
+        PathBuf::from(format!("/usr/lib/{}-linux-gnu", *TARGET_ARCH)),
+    ];
+    let search = env_dir.iter().cloned().chain(sdk_dirs).chain(system_dirs);
+    let found = search
+        .flat_map(|dir| names.iter().map(move |name| (dir.clone(), *name)))
+        .find(|(dir, name)| {
+            [
+                format!("lib{name}.so"),
+                format!("lib{name}.a"),
+                format!("lib{name}.dylib"),
+                format!("{name}.lib"),
+            ]
+            .iter()
+            .any(|file| dir.join(file).exists())
+        });
+    let Some((dir, name)) = found else {
+        anyhow::bail!("The {api} library wasn't found but it needs to be linked.\n{help}");
+    };
+    println!("cargo:rustc-link-search=native={}", dir.display());
+    println!("cargo:rustc-link-lib=dylib={name}");
+    Ok(())
+}
+
+#[cfg(feature = "opengl")]
+const GL_HELP: &str = "The opengl feature needs the OpenGL ES 3 and EGL development packages (e.g. libgles-dev and libegl-dev on debian / ubuntu, mesa on arch, mesa-libGLES-devel and mesa-libEGL-devel on fedora).\nOr set GL_INCLUDE_DIR / GL_LIB_DIR to the directories containing the headers / libraries.";
+
+/// Find the OpenGL ES 3 / EGL headers for the MNN OpenGL backend on linux
+///
+/// Android takes them from the NDK sysroot so this returns `None` there
+#[cfg(feature = "opengl")]
+fn gl_include_dir() -> Result<Option<PathBuf>> {
+    println!("cargo:rerun-if-env-changed=GL_INCLUDE_DIR");
+    if *TARGET_OS != "linux" {
+        return Ok(None);
+    }
+    let env_dir = std::env::var_os("GL_INCLUDE_DIR").map(PathBuf::from);
+    let has_headers = |dir: &PathBuf| {
+        dir.join("GLES3").join("gl3.h").exists() && dir.join("EGL").join("egl.h").exists()
+    };
+    if let Some(dir) = env_dir {
+        ensure!(
+            has_headers(&dir),
+            "GL_INCLUDE_DIR ({}) doesn't contain GLES3/gl3.h and EGL/egl.h",
+            dir.display()
+        );
+        return Ok(Some(dir));
+    }
+    let system_dirs = [
+        PathBuf::from("/usr/include"),
+        PathBuf::from("/usr/local/include"),
+    ];
+    let Some(dir) = system_dirs.into_iter().find(has_headers) else {
+        anyhow::bail!("The OpenGL ES 3 / EGL headers weren't found.\n{GL_HELP}");
+    };
+    Ok(Some(dir))
+}
+
+/// Copy the MNN source to `vendor` and patch it
+///
+/// A hash of the source (and of the patches) is stored next to the copy so it's only copied
+/// again when the source changes, e.g. after updating the submodule.
+fn copy_vendor(source: impl AsRef<Path>, vendor: impl AsRef<Path>) -> Result<()> {
+    let (source, vendor) = (source.as_ref(), vendor.as_ref());
+    let hash = format!("{:016x}", source_hash(source)?);
+    let stamp = vendor.with_extension("hash");
+    if vendor.exists() && std::fs::read_to_string(&stamp).is_ok_and(|stamp| stamp == hash) {
+        return Ok(());
+    }
+    if vendor.exists() {
+        make_writable(vendor)?;
+        std::fs::remove_dir_all(vendor).context("Failed to remove the outdated vendor copy")?;
+    }
+    fs_extra::dir::copy(
+        source,
+        vendor,
+        &fs_extra::dir::CopyOptions::new()
+            .overwrite(true)
+            .copy_inside(true),
+    )
+    .context("Failed to copy vendor")?;
+    patch_headers(vendor.join("include"))?;
+    std::fs::write(stamp, hash)?;
+    Ok(())
+}
+
+/// Hash the paths and contents of all the files of the MNN source (except `.git`)
+fn source_hash(source: &Path) -> Result<u64> {
+    use std::hash::{Hash, Hasher};
+    fn files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.file_name().is_some_and(|name| name == ".git") {
+                continue;
+            }
+            if path.is_dir() {
+                files(&path, out)?;
+            } else {
+                out.push(path);
+            }
+        }
+        Ok(())
+    }
+    let mut paths = Vec::new();
+    files(source, &mut paths).context("Failed to read the MNN source")?;
+    paths.sort();
+    let mut hasher = std::hash::DefaultHasher::new();
+    // Patch the copy again when the patches change
+    (HALIDE_SEARCH, TRACING_SEARCH, TRACING_REPLACE).hash(&mut hasher);
+    for path in paths {
+        path.strip_prefix(source)?.hash(&mut hasher);
+        std::fs::read(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+            .hash(&mut hasher);
+    }
+    Ok(hasher.finish())
+}
+
+/// Make the directories of a copy writable so it can be removed (e.g. copies of the nix store)
+fn make_writable(dir: &Path) -> Result<()> {
+    #[cfg(unix)]
+    std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o755))?;
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            make_writable(&entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+/// Remove the `#if __cplusplus` around the `code` of `halide_type_t` so bindgen sees the enum
+///
+/// Already patched headers are returned unchanged.
+fn patch_halide_runtime(contents: &str) -> String {
+    use itertools::Itertools;
+    let lines = contents.lines().collect::<Vec<_>>();
+    let Some((idx, _)) = lines
+        .iter()
+        .find_position(|line| line.contains(HALIDE_SEARCH))
+    else {
+        return contents.to_string();
+    };
+    if idx == 0 || !lines[idx - 1].trim_start().starts_with("#if") {
+        return contents.to_string();
+    }
+    // remove the last line and the next 3 lines
+    lines
+        .into_iter()
+        .enumerate()
+        .filter(|(c_idx, _)| !(*c_idx == idx - 1 || (idx + 1..=idx + 3).contains(c_idx)))
+        .map(|(_, c)| c)
+        .join("\n")
+}
+
+/// Forward `MNN_PRINT` / `MNN_ERROR` to rust
+///
+/// Already patched headers are returned unchanged.
+fn patch_mnn_define(contents: &str) -> String {
+    contents.replace(TRACING_SEARCH, TRACING_REPLACE)
+}
+
+/// Apply `patch` to a header, checking that patching it again doesn't change it
+fn patch_header(path: &Path, patch: fn(&str) -> String) -> Result<()> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let patched = patch(&contents);
+    ensure!(
+        patch(&patched) == patched,
+        "Patching {} isn't idempotent",
+        path.display()
+    );
+    if patched != contents {
+        #[cfg(unix)]
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o644))?;
+        std::fs::write(path, patched)?;
+    }
+    Ok(())
+}
+
+/// Patch the MNN headers for bindgen and to forward the MNN logs to rust
+fn patch_headers(include: impl AsRef<Path>) -> Result<()> {
+    let include = include.as_ref().join("MNN");
+    patch_header(&include.join("HalideRuntime.h"), patch_halide_runtime)?;
+    patch_header(&include.join("MNNDefine.h"), patch_mnn_define)?;
+    Ok(())
+}
+
+/// Check that the MNN headers are patched for [`MNN_NO_VENDOR_COPY`] builds
+fn ensure_headers_patched(include: impl AsRef<Path>) -> Result<()> {
+    let include = include.as_ref().join("MNN");
+    let patches: [(&str, fn(&str) -> String); 2] = [
+        ("HalideRuntime.h", patch_halide_runtime),
+        ("MNNDefine.h", patch_mnn_define),
+    ];
+    for (header, patch) in patches {
+        let path = include.join(header);
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        ensure!(
+            patch(&contents) == contents,
+            "{} isn't patched, MNN_NO_VENDOR_COPY needs the patches of mnn-sys/patches applied to the MNN source",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Read the MNN version (`major.minor.patch`) from `MNN/MNNDefine.h` in an include directory
+fn mnn_version(include: impl AsRef<Path>) -> Result<String> {
+    let mnn_define = include.as_ref().join("MNN").join("MNNDefine.h");
+    let contents = std::fs::read_to_string(&mnn_define)
+        .with_context(|| format!("Failed to read {}", mnn_define.display()))?;
+    use itertools::Itertools;
+    let component = |name: &str| {
+        contents
+            .lines()
+            .filter_map(|line| {
+                let define = line.trim().strip_prefix("#define")?;
+                define.split_whitespace().collect_tuple()
+            })
+            .find_map(|(define, value): (&str, &str)| (define == name).then_some(value))
+            .with_context(|| format!("{name} not found in {}", mnn_define.display()))
+    };
+    Ok(format!(
+        "{}.{}.{}",
+        component("MNN_VERSION_MAJOR")?,
+        component("MNN_VERSION_MINOR")?,
+        component("MNN_VERSION_PATCH")?
+    ))
+}
+
+/// Copy the headers of a prebuilt MNN to the out dir and patch them like the vendored ones
+///
+/// The mnn_c wrappers are written against the vendored headers so the versions should match,
+/// `source` names where the headers came from for the errors
+fn prebuilt_headers(
+    prebuilt: impl AsRef<Path>,
+    source: &str,
+    out_dir: impl AsRef<Path>,
+) -> Result<PathBuf> {
+    let prebuilt = prebuilt.as_ref();
+    ensure!(
+        prebuilt.join("MNN").join("Interpreter.hpp").exists(),
+        "{source} ({}) doesn't contain MNN/Interpreter.hpp, it should point to the include directory of the MNN install",
+        prebuilt.display()
+    );
+    let include = out_dir.as_ref().join("mnn-include");
+    if include.exists() {
+        std::fs::remove_dir_all(&include)?;
+    }
+    fs_extra::dir::copy(
+        prebuilt,
+        &include,
+        &fs_extra::dir::CopyOptions::new()
+            .overwrite(true)
+            .copy_inside(true),
+    )
+    .with_context(|| format!("Failed to copy the headers from {source}"))?;
+    patch_headers(&include)?;
+    Ok(include)
+}
+
+/// An installed MNN found with [`pkg_config_mnn`]
+struct SystemMnn {
+    version: String,
+    lib_dir: PathBuf,
+    include_dir: PathBuf,
+}
+
+/// Look for an installed MNN with pkg-config (`MNN.pc` or `mnn.pc`)
+///
+/// Like the pkg-config crate this is skipped when cross compiling unless
+/// `PKG_CONFIG_ALLOW_CROSS=1`, the binary can be overridden with `PKG_CONFIG`
+fn pkg_config_mnn() -> Option<SystemMnn> {
+    println!("cargo:rerun-if-env-changed=PKG_CONFIG");
+    println!("cargo:rerun-if-env-changed=PKG_CONFIG_PATH");
+    println!("cargo:rerun-if-env-changed=PKG_CONFIG_ALLOW_CROSS");
+    let cross = std::env::var("TARGET").ok() != std::env::var("HOST").ok();
+    if cross && std::env::var("PKG_CONFIG_ALLOW_CROSS").as_deref() != Ok("1") {
+        return None;
+    }
+    let pkg_config = std::env::var("PKG_CONFIG").unwrap_or_else(|_| "pkg-config".into());
+    let query = |module: &str, arg: &str| -> Option<String> {
+        let output = std::process::Command::new(&pkg_config)
+            .args([arg, module])
+            .stderr(std::process::Stdio::null())
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let module = ["MNN", "mnn"]
+        .into_iter()
+        .find(|module| query(module, "--exists").is_some())?;
+    Some(SystemMnn {
+        version: query(module, "--modversion")?,
+        lib_dir: query(module, "--variable=libdir")?.into(),
+        include_dir: query(module, "--variable=includedir")?.into(),
+    })
+}
+
+/// Check that MNN_LIB_DIR contains a static MNN library built from the same version as the headers
+fn ensure_prebuilt_lib(lib_dir: impl AsRef<Path>, include: impl AsRef<Path>) -> Result<()> {
+    let lib_dir = lib_dir.as_ref();
+    let lib = ["libMNN.a", "MNN.lib"]
+        .iter()
+        .map(|name| lib_dir.join(name))
+        .find(|lib| lib.exists())
+        .with_context(|| {
+            format!(
+                "MNN_LIB_DIR ({}) doesn't contain a static MNN library (libMNN.a / MNN.lib), build MNN with -DMNN_BUILD_SHARED_LIBS=OFF or set MNN_COMPILE=1",
+                lib_dir.display()
+            )
+        })?;
+    let version = mnn_version(include)?;
+    // MNN::getVersion returns the MNN_VERSION string literal so it's embedded in the library
+    let needle = format!("{version}\0");
+    let bytes = std::fs::read(&lib).with_context(|| format!("Failed to read {}", lib.display()))?;
+    ensure!(
+        bytes.windows(needle.len()).any(|w| w == needle.as_bytes()),
+        "{} wasn't built from MNN {version} (the version of the headers), rebuild it from the same MNN version or set MNN_COMPILE=1",
+        lib.display()
+    );
     Ok(())
 }
 
@@ -206,8 +888,8 @@ static IS_MSVC_TARGET: LazyLock<bool> = LazyLock::new(|| {
 
 // ... (other functions)
 
-pub fn mnn_c_bindgen(vendor: impl AsRef<Path>, out: impl AsRef<Path>) -> Result<()> {
-    let vendor = vendor.as_ref();
+pub fn mnn_c_bindgen(include: impl AsRef<Path>, out: impl AsRef<Path>) -> Result<()> {
+    let include = include.as_ref();
     let mnn_c = PathBuf::from(MANIFEST_DIR).join("mnn_c");
     mnn_c.read_dir()?.flatten().for_each(|e| {
         rerun_if_changed(e.path());
@@ -218,6 +900,8 @@ pub fn mnn_c_bindgen(vendor: impl AsRef<Path>, out: impl AsRef<Path>) -> Result<
         "tensor_c.h",
         "backend_c.h",
         "schedule_c.h",
+        "module_c.h",
+        "converter_c.h",
     ];
 
     let mut builder = bindgen::Builder::default()
@@ -254,7 +938,7 @@ pub fn mnn_c_bindgen(vendor: impl AsRef<Path>, out: impl AsRef<Path>) -> Result<
     }
         
     let bindings = builder
-        .clang_arg(format!("-I{}", vendor.join("include").to_string_lossy()))
+        .clang_arg(format!("-I{}", include.to_string_lossy()))
         .pipe(|generator| {
             HEADERS.iter().fold(generator, |gen, header| {
                 gen.header(mnn_c.join(header).to_string_lossy())
@@ -288,8 +972,8 @@ pub fn mnn_c_bindgen(vendor: impl AsRef<Path>, out: impl AsRef<Path>) -> Result<
     Ok(())
 }
 
-pub fn mnn_cpp_bindgen(vendor: impl AsRef<Path>, out: impl AsRef<Path>) -> Result<()> {
-    let vendor = vendor.as_ref();
+pub fn mnn_cpp_bindgen(include: impl AsRef<Path>, out: impl AsRef<Path>) -> Result<()> {
+    let include = include.as_ref();
     let mut builder = bindgen::Builder::default()
         .clang_args(["-x", "c++"])
         .clang_args(["-std=c++14"])
@@ -298,20 +982,20 @@ pub fn mnn_cpp_bindgen(vendor: impl AsRef<Path>, out: impl AsRef<Path>) -> Resul
         .clang_arg(CxxOption::COREML.cxx())
         .clang_arg(CxxOption::OPENCL.cxx())
         .clang_arg("-D__STDC_LIMIT_MACROS")
-        .clang_arg(format!("-I{}", vendor.join("include").to_string_lossy()))
+        .clang_arg(format!("-I{}", include.to_string_lossy()))
         .generate_cstr(true)
         .generate_inline_functions(false)
         .size_t_is_usize(true)
         .emit_diagnostics()
         .ctypes_prefix("core::ffi")
         .header(
-            vendor
-                .join("include")
+            include
                 .join("MNN")
                 .join("Interpreter.hpp")
                 .to_string_lossy(),
         )
-        .allowlist_item(".*SessionInfoCode.*");
+        .allowlist_item(".*SessionInfoCode.*")
+        .allowlist_item(".*HintMode.*");
 
     // Only add macOS-specific flags when targeting macOS
     if *TARGET_OS == "macos" {
@@ -338,7 +1022,11 @@ pub fn mnn_cpp_bindgen(vendor: impl AsRef<Path>, out: impl AsRef<Path>) -> Resul
     Ok(())
 }
 
-pub fn mnn_c_build(path: impl AsRef<Path>, vendor: impl AsRef<Path>) -> Result<()> {
+pub fn mnn_c_build(
+    path: impl AsRef<Path>,
+    vendor: impl AsRef<Path>,
+    include: impl AsRef<Path>,
+) -> Result<()> {
     let mnn_c = path.as_ref();
     let files = mnn_c.read_dir()?.flatten().map(|e| e.path()).filter(|e| {
         e.extension() == Some(std::ffi::OsStr::new("cpp"))
@@ -359,7 +1047,11 @@ pub fn mnn_c_build(path: impl AsRef<Path>, vendor: impl AsRef<Path>) -> Result<(
     }
 
     cc::Build::new()
-        .include(vendor.join("include"))
+        .include(include.as_ref())
+        // Internal headers needed for the tensor quantization parameters
+        .include(vendor.join("source"))
+        .include(vendor.join("schema").join("current"))
+        .include(vendor.join("3rd_party").join("flatbuffers").join("include"))
         // .includes(vulkan_includes(vendor))
         .pipe(|config| {
             #[cfg(feature = "vulkan")]
@@ -372,6 +1064,15 @@ pub fn mnn_c_build(path: impl AsRef<Path>, vendor: impl AsRef<Path>) -> Result<(
             config.define("MNN_COREML", "1");
             #[cfg(feature = "opencl")]
             config.define("MNN_OPENCL", "ON");
+            #[cfg(feature = "nnapi")]
+            if *TARGET_OS == "android" {
+                config.define("MNN_NNAPI", "1");
+            }
+            // cli.hpp / config.hpp of the converter library for converter_c.cpp
+            #[cfg(feature = "converter")]
+            config
+                .define("MNN_BUILD_CONVERTER", "1")
+                .include(vendor.join("tools").join("converter").join("include"));
             if is_emscripten() {
                 config.compiler("emcc");
                 // We can't compile wasm32-unknown-unknown with emscripten
@@ -380,6 +1081,20 @@ pub fn mnn_c_build(path: impl AsRef<Path>, vendor: impl AsRef<Path>) -> Result<(
             }
             #[cfg(feature = "crt_static")]
             config.static_crt(true);
+            #[cfg(feature = "mini")]
+            {
+                config.define("MNN_BUILD_MINI", "1").opt_level_str("s");
+                if !*IS_MSVC_TARGET {
+                    for flag in SECTION_FLAGS {
+                        config.flag(flag);
+                    }
+                }
+            }
+            if *ASAN {
+                for flag in ASAN_FLAGS {
+                    config.flag(flag);
+                }
+            }
 
             // No bail logic here now, just configure config
             config
@@ -403,6 +1118,71 @@ pub fn mnn_c_build(path: impl AsRef<Path>, vendor: impl AsRef<Path>) -> Result<(
     Ok(())
 }
 
+/// The android NDK settings passed to cmake when building for android
+struct AndroidNdk {
+    toolchain: PathBuf,
+    abi: String,
+    platform: String,
+}
+
+impl AndroidNdk {
+    /// Find the NDK from `ANDROID_NDK_HOME` (or `ANDROID_NDK_ROOT`, both are set by `cargo ndk`)
+    ///
+    /// `ANDROID_ABI` and `ANDROID_PLATFORM` override the abi derived from the target and the
+    /// platform (`CARGO_NDK_ANDROID_PLATFORM` or android-21)
+    fn from_env() -> Result<Self> {
+        for env in [
+            "ANDROID_NDK_HOME",
+            "ANDROID_NDK_ROOT",
+            "ANDROID_ABI",
+            "ANDROID_PLATFORM",
+            "CARGO_NDK_ANDROID_PLATFORM",
+        ] {
+            println!("cargo:rerun-if-env-changed={env}");
+        }
+        let ndk = std::env::var_os("ANDROID_NDK_HOME")
+            .or_else(|| std::env::var_os("ANDROID_NDK_ROOT"))
+            .map(PathBuf::from)
+            .context(
+                "Building for android requires the android NDK, set ANDROID_NDK_HOME to its path \
+                 (e.g. $ANDROID_HOME/ndk/<version>) or build with `cargo ndk`",
+            )?;
+        let toolchain = ndk.join("build/cmake/android.toolchain.cmake");
+        ensure!(
+            toolchain.exists(),
+            "{} doesn't look like an android NDK ({} is missing), point ANDROID_NDK_HOME to the \
+             root of the NDK",
+            ndk.display(),
+            toolchain.display()
+        );
+        let abi = match std::env::var("ANDROID_ABI") {
+            core::result::Result::Ok(abi) => abi,
+            Err(_) => match TARGET_ARCH.as_str() {
+                "aarch64" => "arm64-v8a",
+                "arm" => "armeabi-v7a",
+                "x86" => "x86",
+                "x86_64" => "x86_64",
+                arch => bail!("Unsupported android architecture {arch}, set ANDROID_ABI manually"),
+            }
+            .to_string(),
+        };
+        let platform = std::env::var("ANDROID_PLATFORM")
+            .or_else(|_| std::env::var("CARGO_NDK_ANDROID_PLATFORM"))
+            .unwrap_or_else(|_| "21".into());
+        // cargo ndk only passes the api level
+        let platform = if platform.starts_with("android-") {
+            platform
+        } else {
+            format!("android-{platform}")
+        };
+        Ok(Self {
+            toolchain,
+            abi,
+            platform,
+        })
+    }
+}
+
 pub fn build_cmake(path: impl AsRef<Path>, install: impl AsRef<Path>) -> Result<()> {
     let threads = std::thread::available_parallelism()?;
 
@@ -433,14 +1213,14 @@ pub fn build_cmake(path: impl AsRef<Path>, install: impl AsRef<Path>) -> Result<
         let mut cmd = std::process::Command::new("cmake");
         cmd.current_dir(&build_dir)
            .arg(&*path_str)
-           .arg("-G").arg("Unix Makefiles")
-           .arg("-DCMAKE_CXX_STANDARD=14")
+           .arg("-G").arg(CMAKE_GENERATOR.as_deref().unwrap_or("Unix Makefiles"))
+           .arg(format!("-DCMAKE_CXX_STANDARD={}", *MNN_CXX_STANDARD))
            .arg("-DMNN_BUILD_SHARED_LIBS=OFF")
            .arg("-DMNN_SEP_BUILD=OFF")
            .arg("-DMNN_PORTABLE_BUILD=ON")
-           .arg("-DMNN_USE_SYSTEM_LIB=OFF")
-           .arg("-DMNN_BUILD_CONVERTER=OFF")
-           .arg("-DMNN_BUILD_TOOLS=OFF")
+           .arg(format!("-DMNN_USE_SYSTEM_LIB={}", CxxOption::SYSTEM_LIB.cmake_value()))
+           .arg(format!("-DMNN_BUILD_CONVERTER={}", CxxOption::CONVERTER.cmake_value()))
+           .arg(format!("-DMNN_BUILD_TOOLS={}", CxxOption::CONVERTER.cmake_value()))
            .arg(format!("-DCMAKE_INSTALL_PREFIX={}", install_str))
            .arg("-DCMAKE_SYSTEM_NAME=Windows")
            .arg("-DCMAKE_BUILD_TYPE=Release");
@@ -501,8 +1281,8 @@ pub fn build_cmake(path: impl AsRef<Path>, install: impl AsRef<Path>) -> Result<
         }
 
 
-        let c_flags = format!("{} {} {} -DWIN32=1 /EHsc -msse4.1", env_c_flags, extra_c_includes, target_flag);
-        let cxx_flags = format!("{} {} {} -DWIN32=1 /EHsc -msse4.1", env_cxx_flags, extra_cxx_includes, target_flag);
+        let c_flags = format!("{} {} {} -DWIN32=1 /EHsc{}", env_c_flags, extra_c_includes, target_flag, *SSE41_FLAG);
+        let cxx_flags = format!("{} {} {} -DWIN32=1 /EHsc{}", env_cxx_flags, extra_cxx_includes, target_flag, *SSE41_FLAG);
 
         cmd.arg(format!("-DCMAKE_C_COMPILER={}", cc))
             .arg(format!("-DCMAKE_CXX_COMPILER={}", cxx))
@@ -528,7 +1308,17 @@ pub fn build_cmake(path: impl AsRef<Path>, install: impl AsRef<Path>) -> Result<
            .arg(format!("-DMNN_COREML={}", CxxOption::COREML.cmake_value()))
            .arg(format!("-DMNN_OPENCL={}", CxxOption::OPENCL.cmake_value()))
            .arg(format!("-DMNN_OPENGL={}", CxxOption::OPENGL.cmake_value()))
+           .arg(format!("-DMNN_NNAPI={}", CxxOption::NNAPI.android_only().cmake_value()))
+           .arg(format!("-DMNN_LOW_MEMORY={}", CxxOption::LOW_MEMORY.cmake_value()))
+           .arg(format!("-DMNN_BUILD_MINI={}", CxxOption::MINI.cmake_value()))
+           .arg(format!("-DMNN_REDUCE_SIZE={}", CxxOption::REDUCE_SIZE.cmake_value()))
+           .arg(format!("-DMNN_SUPPORT_DEPRECATED_OP={}", CxxOption::DEPRECATED_OP.cmake_value()))
+           .arg(format!("-DMNN_ARM82={}", CxxOption::ARM82.aarch64_only().cmake_value()))
+           .arg(format!("-DMNN_SUPPORT_BF16={}", CxxOption::BF16.cmake_value()))
            .arg("-DMNN_USE_SSE=OFF");
+        for (key, value) in extra_cmake_defines() {
+            cmd.arg(format!("-D{key}={value}"));
+        }
            
         // if *TARGET_OS == "windows" {
         //    cmd.arg("-DCMAKE_CXX_FLAGS=-DWIN32=1");
@@ -565,17 +1355,30 @@ pub fn build_cmake(path: impl AsRef<Path>, install: impl AsRef<Path>) -> Result<
         return Ok(());
     }
 
+    let android = if *TARGET_OS == "android" {
+        Some(AndroidNdk::from_env()?)
+    } else {
+        None
+    };
+    #[cfg(feature = "opengl")]
+    let gl_include = gl_include_dir()?;
+    #[cfg(not(feature = "opengl"))]
+    let gl_include: Option<PathBuf> = None;
+
     let mut config = cmake::Config::new(path);
     
-    config.define("CMAKE_CXX_STANDARD", "14")
+    config.define("CMAKE_CXX_STANDARD", &*MNN_CXX_STANDARD)
         .parallel(threads.get() as u8)
         .define("MNN_BUILD_SHARED_LIBS", "OFF")
         .define("MNN_SEP_BUILD", "OFF")
         .define("MNN_PORTABLE_BUILD", "ON")
-        .define("MNN_USE_SYSTEM_LIB", "OFF")
-        .define("MNN_BUILD_CONVERTER", "OFF")
-        .define("MNN_BUILD_TOOLS", "OFF")
+        .define("MNN_USE_SYSTEM_LIB", CxxOption::SYSTEM_LIB.cmake_value())
+        .define("MNN_BUILD_CONVERTER", CxxOption::CONVERTER.cmake_value())
+        .define("MNN_BUILD_TOOLS", CxxOption::CONVERTER.cmake_value())
         .define("CMAKE_INSTALL_PREFIX", install.as_ref());
+    if let Some(generator) = &*CMAKE_GENERATOR {
+        config.generator(generator);
+    }
 
 
 
@@ -592,12 +1395,48 @@ pub fn build_cmake(path: impl AsRef<Path>, install: impl AsRef<Path>) -> Result<
             config.define("MNN_COREML", CxxOption::COREML.cmake_value());
             config.define("MNN_OPENCL", CxxOption::OPENCL.cmake_value());
             config.define("MNN_OPENGL", CxxOption::OPENGL.cmake_value());
-            config.define("MNN_USE_SSE", "ON");
+            config.define("MNN_NNAPI", CxxOption::NNAPI.android_only().cmake_value());
+            config.define("MNN_LOW_MEMORY", CxxOption::LOW_MEMORY.cmake_value());
+            config.define("MNN_BUILD_MINI", CxxOption::MINI.cmake_value());
+            config.define("MNN_REDUCE_SIZE", CxxOption::REDUCE_SIZE.cmake_value());
+            config.define("MNN_SUPPORT_DEPRECATED_OP", CxxOption::DEPRECATED_OP.cmake_value());
+            config.define("MNN_ARM82", CxxOption::ARM82.aarch64_only().cmake_value());
+            config.define("MNN_SUPPORT_BF16", CxxOption::BF16.cmake_value());
+            config.define("MNN_USE_SSE", if *USE_SSE { "ON" } else { "OFF" });
             // config.define("CMAKE_CXX_FLAGS", "-O0");
             // #[cfg(windows)]
             if *TARGET_OS == "windows" {
-                config.define("CMAKE_CXX_FLAGS", "-DWIN32=1 -msse4.1");
-                config.define("CMAKE_C_FLAGS", "-DWIN32=1 -msse4.1");
+                let flags = format!("-DWIN32=1{}", *SSE41_FLAG);
+                config.define("CMAKE_CXX_FLAGS", &flags);
+                config.define("CMAKE_C_FLAGS", &flags);
+            }
+
+            if let Some(ndk) = &android {
+                config
+                    .define("CMAKE_SYSTEM_NAME", "Android")
+                    .define("CMAKE_TOOLCHAIN_FILE", &ndk.toolchain)
+                    .define("ANDROID_ABI", &ndk.abi)
+                    .define("ANDROID_PLATFORM", &ndk.platform)
+                    // Same c++ runtime as the one cc links for the mnn_c shims
+                    .define("ANDROID_STL", "c++_shared");
+            }
+
+            if let Some(dir) = &gl_include {
+                let flag = format!("-I{}", dir.display());
+                config.cflag(&flag).cxxflag(&flag);
+            }
+            if *ASAN {
+                for flag in ASAN_FLAGS {
+                    config.cflag(flag).cxxflag(flag);
+                }
+            }
+            if CxxOption::MINI.enabled() {
+                config.profile("MinSizeRel");
+                if !*IS_MSVC_TARGET {
+                    for flag in SECTION_FLAGS {
+                        config.cflag(flag).cxxflag(flag);
+                    }
+                }
             }
 
             if is_emscripten() {
@@ -606,6 +1445,10 @@ pub fn build_cmake(path: impl AsRef<Path>, install: impl AsRef<Path>) -> Result<
                     .define("CMAKE_CXX_COMPILER", "em++")
                     .target("wasm32-unknown-emscripten");
             }
+            // Last so they can override any of the defines above
+            for (key, value) in extra_cmake_defines() {
+                config.define(key, value);
+            }
             config
         })
         .build();
@@ -723,9 +1566,26 @@ impl CxxOption {
     pub const OPENCL: CxxOption = cxx_option_from_feature!("opencl", "MNN_OPENCL");
     pub const OPENMP: CxxOption = cxx_option_from_feature!("openmp", "MNN_OPENMP");
     pub const OPENGL: CxxOption = cxx_option_from_feature!("opengl", "MNN_OPENGL");
+    pub const NNAPI: CxxOption = cxx_option_from_feature!("nnapi", "MNN_NNAPI");
+    pub const SYSTEM_LIB: CxxOption =
+        cxx_option_from_feature!("system-loaders", "MNN_USE_SYSTEM_LIB");
     pub const CRT_STATIC: CxxOption = cxx_option_from_feature!("crt_static", "MNN_WIN_RUNTIME_MT");
     pub const THREADPOOL: CxxOption =
         cxx_option_from_feature!("mnn-threadpool", "MNN_USE_THREAD_POOL");
+    pub const CONVERTER: CxxOption =
+        cxx_option_from_feature!("converter", "MNN_BUILD_CONVERTER");
+    pub const LOW_MEMORY: CxxOption = cxx_option_from_feature!("low-memory", "MNN_LOW_MEMORY");
+    /// Only the shape inference of fixed shape models
+    pub const MINI: CxxOption = cxx_option_from_feature!("mini", "MNN_BUILD_MINI");
+    /// Drop the uncommon ops and the size-costly optimized kernels
+    pub const REDUCE_SIZE: CxxOption = cxx_option_from_feature!("mini", "MNN_REDUCE_SIZE");
+    /// The quantized tflite ops kept for old models, dropped by the mini feature
+    pub const DEPRECATED_OP: CxxOption =
+        CxxOption::from_bool("MNN_SUPPORT_DEPRECATED_OP", !cfg!(feature = "mini"));
+    /// The fp16 kernels of ARMv8.2 cpus, picked at runtime with `Precision_Low`
+    pub const ARM82: CxxOption = cxx_option_from_feature!("arm82", "MNN_ARM82");
+    /// The bf16 cpu kernels, picked with `Precision_Low_BF16`
+    pub const BF16: CxxOption = cxx_option_from_feature!("bf16", "MNN_SUPPORT_BF16");
 
     pub fn new(name: &'static str, value: impl Into<CxxOptionValue>) -> Self {
         Self {
@@ -744,6 +1604,24 @@ impl CxxOption {
         self
     }
 
+    /// Turn the option off when not building for android (e.g. NNAPI)
+    pub fn android_only(self) -> Self {
+        if *TARGET_OS == "android" {
+            self
+        } else {
+            self.off()
+        }
+    }
+
+    /// Turn the option off when not building for aarch64 (e.g. the ARMv8.2 kernels)
+    pub fn aarch64_only(self) -> Self {
+        if *TARGET_ARCH == "aarch64" {
+            self
+        } else {
+            self.off()
+        }
+    }
+
     pub fn with_value(mut self, value: &'static str) -> Self {
         self.value = CxxOptionValue::Value(value);
         self
@@ -0,0 +1,25 @@
+#include "converter_c.h"
+#ifdef MNN_BUILD_CONVERTER
+#include <cli.hpp>
+#include <config.hpp>
+#include <string>
+#include <vector>
+
+bool MNN_convertModel(const char *from, const char *to, const char *format) {
+  // Go through the argument parser of MNNConvert so the defaults (bizCode,
+  // optimization level, ...) match the command line tool
+  std::vector<std::string> args = {"MNNConvert", "-f",         format,
+                                   "--modelFile", from,        "--MNNModel",
+                                   to,            "--bizCode", "MNN"};
+  std::vector<char *> argv;
+  for (auto &arg : args) {
+    argv.push_back(&arg[0]);
+  }
+  modelConfig config;
+  if (!MNN::Cli::initializeMNNConvertArgs(config, (int)argv.size(),
+                                          argv.data())) {
+    return false;
+  }
+  return MNN::Cli::convertModel(config);
+}
+#endif
@@ -0,0 +1,24 @@
+#ifndef CONVERTER_C_H
+#define CONVERTER_C_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/**
+ * @brief convert a model to the MNN format with the MNN converter library.
+ * Only available when MNN is built with MNN_BUILD_CONVERTER (converter
+ * feature).
+ * @param from      path of the source model.
+ * @param to        path of the converted .mnn model.
+ * @param format    source format as accepted by MNNConvert -f (ONNX, TF,
+ * TFLITE, TORCH, MNN, JSON).
+ * @return true if the conversion succeeded.
+ */
+bool MNN_convertModel(const char *from, const char *to, const char *format);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif // CONVERTER_C_H
@@ -1,5 +1,8 @@
 #include "interpreter_c.h"
 #include "MNN/Interpreter.hpp"
+#include "MNN_generated.h"
+#include "core/Backend.hpp"
+#include "core/TensorUtils.hpp"
 #include <MNN/MNNForwardType.h>
 #include <cstdlib>
 #include <cstring>
@@ -28,7 +31,7 @@ void modelPrintIO(const char *model) {
   }
 }
 
-// const char *getVersion() { return MNN::getVersion(); }
+const char *getVersion() { return MNN::getVersion(); }
 Interpreter *Interpreter_createFromFile(const char *file) {
   return reinterpret_cast<Interpreter *>(
       MNN::Interpreter::createFromFile(file));
@@ -67,28 +70,65 @@ void Interpreter_setSessionHint(Interpreter *interpreter, int mode, int value) {
   mnn_interpreter->setSessionHint(static_cast<MNN::Interpreter::HintMode>(mode),
                                   value);
 }
-// RuntimeInfo* Interpreter_createRuntime(const ScheduleConfig* configs, size_t
-// configSize) {
-//     std::vector<MNN::ScheduleConfig> cppConfigs(configSize);
-//     for (size_t i = 0; i < configSize; ++i) {
-//         cppConfigs[i].saveTensors.assign(configs[i].saveTensors,
-//         configs[i].saveTensors + configs[i].saveTensorsSize);
-//         cppConfigs[i].type = configs[i].type;
-//         cppConfigs[i].numThread = configs[i].numThread;
-//         cppConfigs[i].path.inputs.assign(configs[i].path.inputs,
-//         configs[i].path.inputs + configs[i].path.inputsSize);
-//         cppConfigs[i].path.outputs.assign(configs[i].path.outputs,
-//         configs[i].path.outputs + configs[i].path.outputsSize);
-//         cppConfigs[i].path.mode =
-//         static_cast<MNN::ScheduleConfig::Path::Mode>(configs[i].path.mode);
-//         cppConfigs[i].backupType = configs[i].backupType;
-//         cppConfigs[i].backendConfig = configs[i].backendConfig;
-//     }
-//     auto runtimeInfo = MNN::Interpreter::createRuntime(cppConfigs);
-//     return new RuntimeInfo{new std::map<MNNForwardType,
-//     std::shared_ptr<MNN::Runtime>>(runtimeInfo.first), new
-//     std::shared_ptr<MNN::Runtime>(runtimeInfo.second)};
-// }
+RuntimeInfo *Interpreter_createRuntime(const MNNScheduleConfig *const *configs,
+                                       size_t configSize) {
+  auto mnn_configs =
+      reinterpret_cast<const MNN::ScheduleConfig *const *>(configs);
+  std::vector<MNN::ScheduleConfig> s_configs;
+  for (size_t i = 0; i < configSize; ++i) {
+    s_configs.push_back(*mnn_configs[i]);
+  }
+  auto runtime =
+      new MNN::RuntimeInfo(MNN::Interpreter::createRuntime(s_configs));
+  return reinterpret_cast<RuntimeInfo *>(runtime);
+}
+void RuntimeInfo_destroy(RuntimeInfo *runtime) {
+  delete reinterpret_cast<MNN::RuntimeInfo *>(runtime);
+}
+int RuntimeInfo_hasBackend(const RuntimeInfo *runtime, MNNForwardType type) {
+  auto mnn_runtime = reinterpret_cast<const MNN::RuntimeInfo *>(runtime);
+  // createRuntime falls back to the backup type when a backend can't be
+  // created, so only the backends that were actually created are in the map
+  auto iter = mnn_runtime->first.find(type);
+  return iter != mnn_runtime->first.end() && iter->second != nullptr;
+}
+int RuntimeInfo_setCache(RuntimeInfo *runtime, const void *buffer,
+                         size_t size) {
+  auto mnn_runtime = reinterpret_cast<MNN::RuntimeInfo *>(runtime);
+  bool valid = true;
+  for (auto &iter : mnn_runtime->first) {
+    if (iter.second != nullptr) {
+      valid = iter.second->onSetCache(buffer, size) && valid;
+    }
+  }
+  return valid;
+}
+size_t RuntimeInfo_getCache(RuntimeInfo *runtime, const void **buffer) {
+  auto mnn_runtime = reinterpret_cast<MNN::RuntimeInfo *>(runtime);
+  for (auto &iter : mnn_runtime->first) {
+    if (iter.second == nullptr) {
+      continue;
+    }
+    auto cache = iter.second->onGetCache();
+    if (cache.first != nullptr && cache.second > 0) {
+      *buffer = cache.first;
+      return cache.second;
+    }
+  }
+  *buffer = nullptr;
+  return 0;
+}
+Session *Interpreter_createSessionWithRuntime(Interpreter *interpreter,
+                                              const MNNScheduleConfig *config,
+                                              const RuntimeInfo *runtime) {
+  auto mnn_interpreter = reinterpret_cast<MNN::Interpreter *>(interpreter);
+  auto mnn_schedule_config =
+      reinterpret_cast<const MNN::ScheduleConfig *>(config);
+  auto mnn_runtime = reinterpret_cast<const MNN::RuntimeInfo *>(runtime);
+  MNN::Session *session =
+      mnn_interpreter->createSession(*mnn_schedule_config, *mnn_runtime);
+  return reinterpret_cast<Session *>(session);
+}
 Session *Interpreter_createSession(Interpreter *interpreter,
                                    const MNNScheduleConfig *config) {
   printf("DEBUG[mnn_c]: Interpreter_createSession called.\n");
@@ -114,21 +154,6 @@ Session *Interpreter_createSession(Interpreter *interpreter,
 
   return reinterpret_cast<Session *>(session);
 }
-// Session* Interpreter_createSessionWithRuntime(Interpreter* interpreter, const
-// ScheduleConfig* config, const RuntimeInfo* runtime) {
-//     MNN::ScheduleConfig cppConfig;
-//     cppConfig.saveTensors.assign(config->saveTensors, config->saveTensors +
-//     config->saveTensorsSize); cppConfig.type = config->type;
-//     cppConfig.numThread = config->numThread;
-//     cppConfig.path.inputs.assign(config->path.inputs, config->path.inputs +
-//     config->path.inputsSize);
-//     cppConfig.path.outputs.assign(config->path.outputs, config->path.outputs
-//     + config->path.outputsSize); cppConfig.path.mode =
-//     static_cast<MNN::ScheduleConfig::Path::Mode>(config->path.mode);
-//     cppConfig.backupType = config->backupType;
-//     cppConfig.backendConfig = config->backendConfig;
-//     return interpreter->createSession(cppConfig, *runtime);
-// }
 // Session *Interpreter_createMultiPathSession(Interpreter *interpreter,
 //                                             const MNNScheduleConfig *configs,
 //                                             size_t configSize) {
@@ -185,11 +210,14 @@ void Interpreter_releaseModel(Interpreter *interpreter) {
   auto mnn_interpreter = reinterpret_cast<MNN::Interpreter *>(interpreter);
   mnn_interpreter->releaseModel();
 }
-// std::pair<const void*, size_t> Interpreter_getModelBuffer(const Interpreter*
-// interpreter) {
-//     auto mnn_interpreter = reinterpret_cast<MNN::Interpreter
-//     const*>(interpreter); return mnn_interpreter->getModelBuffer();
-// }
+const void *Interpreter_getModelBuffer(const Interpreter *interpreter,
+                                       size_t *size) {
+  auto mnn_interpreter =
+      reinterpret_cast<MNN::Interpreter const *>(interpreter);
+  auto buffer = mnn_interpreter->getModelBuffer();
+  *size = buffer.second;
+  return buffer.first;
+}
 const char *Interpreter_getModelVersion(const Interpreter *interpreter) {
   auto mnn_interpreter =
       reinterpret_cast<MNN::Interpreter const *>(interpreter);
@@ -353,6 +381,32 @@ const Backend *Interpreter_getBackend(const Interpreter *interpreter,
   return reinterpret_cast<const Backend *>(
       mnn_interpreter->getBackend(mnn_session, mnn_tensor));
 }
+Tensor *Interpreter_createTensorOnBackendOf(const Interpreter *interpreter,
+                                            const Session *session,
+                                            const Tensor *like) {
+  auto mnn_interpreter =
+      reinterpret_cast<MNN::Interpreter const *>(interpreter);
+  auto mnn_session = reinterpret_cast<MNN::Session const *>(session);
+  auto mnn_like = reinterpret_cast<MNN::Tensor const *>(like);
+  auto backend = const_cast<MNN::Backend *>(
+      mnn_interpreter->getBackend(mnn_session, mnn_like));
+  if (backend == nullptr) {
+    return nullptr;
+  }
+  auto tensor = MNN::Tensor::createDevice(
+      mnn_like->shape(), mnn_like->getType(), mnn_like->getDimensionType());
+  // The internal layout of the backend (e.g. NC4HW4 on gpus), not the one
+  // exposed by getDimensionType
+  MNN::TensorUtils::getDescribe(tensor)->dimensionFormat =
+      MNN::TensorUtils::getDescribe(mnn_like)->dimensionFormat;
+  // The memory is released with the tensor
+  if (!backend->onAcquireBuffer(tensor, MNN::Backend::STATIC)) {
+    delete tensor;
+    return nullptr;
+  }
+  MNN::TensorUtils::getDescribeOrigin(tensor)->setBackend(backend);
+  return reinterpret_cast<Tensor *>(tensor);
+}
 const char *Interpreter_bizCode(const Interpreter *interpreter) {
   auto mnn_interpreter =
       reinterpret_cast<MNN::Interpreter const *>(interpreter);
@@ -372,4 +426,41 @@ const char *OperatorInfo_type(const void *op) {
 float OperatorInfo_flops(const void *op) {
   return reinterpret_cast<const MNN::OperatorInfo *>(op)->flops();
 }
+const char *Net_version(const void *buffer, size_t size) {
+  flatbuffers::Verifier verifier(static_cast<const uint8_t *>(buffer), size);
+  if (!MNN::VerifyNetBuffer(verifier)) {
+    return nullptr;
+  }
+  auto extraInfo = MNN::GetNet(buffer)->extraInfo();
+  if (nullptr == extraInfo || nullptr == extraInfo->version()) {
+    return nullptr;
+  }
+  return extraInfo->version()->c_str();
+}
+int Net_forEachOpOutput(const void *buffer, size_t size,
+                        void (*callback)(void *context, const char *opName,
+                                         const char *tensorName),
+                        void *context) {
+  flatbuffers::Verifier verifier(static_cast<const uint8_t *>(buffer), size);
+  if (!MNN::VerifyNetBuffer(verifier)) {
+    return 0;
+  }
+  auto net = MNN::GetNet(buffer);
+  auto tensorNames = net->tensorName();
+  if (nullptr == net->oplists() || nullptr == tensorNames) {
+    return 1;
+  }
+  for (auto op : *net->oplists()) {
+    if (nullptr == op->name() || nullptr == op->outputIndexes()) {
+      continue;
+    }
+    for (auto index : *op->outputIndexes()) {
+      if (index >= 0 && index < static_cast<int>(tensorNames->size())) {
+        callback(context, op->name()->c_str(),
+                 tensorNames->GetAsString(index)->c_str());
+      }
+    }
+  }
+  return 1;
+}
 } // extern "C"
@@ -13,6 +13,7 @@ extern "C" {
 #endif
 typedef struct Interpreter Interpreter;
 typedef struct Backend Backend;
+typedef struct RuntimeInfo RuntimeInfo;
 
 /** acquire runtime status by Runtime::getCurrentStatus with following keys,
  */
@@ -136,13 +137,26 @@ void Interpreter_setExternalFile(Interpreter *interpreter, const char *file,
 ErrorCode Interpreter_updateCacheFile(Interpreter *interpreter,
                                       Session *session);
 void Interpreter_setSessionHint(Interpreter *interpreter, int mode, int value);
-// RuntimeInfo *Interpreter_createRuntime(const ScheduleConfig *configs,
-//                                        size_t configSize);
+RuntimeInfo *Interpreter_createRuntime(const MNNScheduleConfig *const *configs,
+                                       size_t configSize);
+void RuntimeInfo_destroy(RuntimeInfo *runtime);
+int RuntimeInfo_hasBackend(const RuntimeInfo *runtime, MNNForwardType type);
+/**
+ * @brief load the cache (e.g. tuned gpu kernels) into every backend of the
+ * runtime, the buffer has to outlive the runtime.
+ * @return 0 if a backend rejected the cache.
+ */
+int RuntimeInfo_setCache(RuntimeInfo *runtime, const void *buffer, size_t size);
+/**
+ * @brief get the cache of the first backend of the runtime that has one.
+ * @return the size of the cache, 0 if no backend has a cache.
+ */
+size_t RuntimeInfo_getCache(RuntimeInfo *runtime, const void **buffer);
 Session *Interpreter_createSession(Interpreter *interpreter,
                                    const MNNScheduleConfig *config);
-// Session *Interpreter_createSessionWithRuntime(Interpreter *interpreter,
-//                                               const ScheduleConfig *config,
-//                                               const RuntimeInfo *runtime);
+Session *Interpreter_createSessionWithRuntime(Interpreter *interpreter,
+                                              const MNNScheduleConfig *config,
+                                              const RuntimeInfo *runtime);
 Session *
 Interpreter_createMultiPathSession(Interpreter *interpreter,
                                    const MNNScheduleConfig *const *configs,
@@ -155,8 +169,8 @@ void Interpreter_resizeSession(Interpreter *interpreter, Session *session);
 void Interpreter_resizeSessionWithFlag(Interpreter *interpreter,
                                        Session *session, int needRelloc);
 void Interpreter_releaseModel(Interpreter *interpreter);
-// std::pair<const void *, size_t>
-// Interpreter_getModelBuffer(const Interpreter *interpreter);
+const void *Interpreter_getModelBuffer(const Interpreter *interpreter,
+                                       size_t *size);
 const char *Interpreter_getModelVersion(const Interpreter *interpreter);
 ErrorCode Interpreter_updateSessionToModel(Interpreter *interpreter,
                                            Session *session);
@@ -191,6 +205,11 @@ void Interpreter_resizeTensorByNCHW(Interpreter *interpreter, Tensor *tensor,
 const Backend *Interpreter_getBackend(const Interpreter *interpreter,
                                       const Session *session,
                                       const Tensor *tensor);
+/// A new tensor with the shape, type and layout of `like` whose memory is allocated by the
+/// backend of `like` in `session`, null if the backend can't allocate it
+Tensor *Interpreter_createTensorOnBackendOf(const Interpreter *interpreter,
+                                            const Session *session,
+                                            const Tensor *like);
 const char *Interpreter_bizCode(const Interpreter *interpreter);
 const char *Interpreter_uuid(const Interpreter *interpreter);
 
@@ -198,6 +217,16 @@ const char *OperatorInfo_name(const void *op);
 const char *OperatorInfo_type(const void *op);
 float OperatorInfo_flops(const void *op);
 
+/// The version of MNN a model buffer was converted with, null if the buffer isn't a valid model
+/// or doesn't record it (models converted before MNN 2.0)
+const char *Net_version(const void *buffer, size_t size);
+/// Calls `callback(context, opName, tensorName)` for each output of every op of a model buffer,
+/// returns 0 if the buffer isn't a valid model
+int Net_forEachOpOutput(const void *buffer, size_t size,
+                        void (*callback)(void *context, const char *opName,
+                                         const char *tensorName),
+                        void *context);
+
 #ifdef __cplusplus
 }
 #endif
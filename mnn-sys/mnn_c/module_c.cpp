@@ -0,0 +1,125 @@
+#include "module_c.h"
+#include <MNN/Interpreter.hpp>
+#include <MNN/expr/Executor.hpp>
+#include <MNN/expr/ExprCreator.hpp>
+#include <MNN/expr/Module.hpp>
+#include <cstdlib>
+#include <cstring>
+#include <memory>
+
+extern "C" {
+Module *Module_load(const char *const *inputs, size_t inputsSize,
+                    const char *const *outputs, size_t outputsSize,
+                    const char *file, const MNNScheduleConfig *config) {
+  std::vector<std::string> cppInputs(inputs, inputs + inputsSize);
+  std::vector<std::string> cppOutputs(outputs, outputs + outputsSize);
+  auto mnn_config = reinterpret_cast<const MNN::ScheduleConfig *>(config);
+  std::shared_ptr<MNN::Express::Executor::RuntimeManager> rtmgr(
+      MNN::Express::Executor::RuntimeManager::createRuntimeManager(
+          *mnn_config));
+  if (rtmgr == nullptr) {
+    return nullptr;
+  }
+  auto module =
+      MNN::Express::Module::load(cppInputs, cppOutputs, file, rtmgr);
+  return reinterpret_cast<Module *>(module);
+}
+
+void Module_destroy(Module *module) {
+  MNN::Express::Module::destroy(
+      reinterpret_cast<MNN::Express::Module *>(module));
+}
+
+VARPArray *Module_forward(Module *module, VARP *const *inputs,
+                          size_t inputsSize) {
+  auto mnn_module = reinterpret_cast<MNN::Express::Module *>(module);
+  std::vector<MNN::Express::VARP> cppInputs;
+  for (size_t i = 0; i < inputsSize; ++i) {
+    cppInputs.push_back(*reinterpret_cast<MNN::Express::VARP *>(inputs[i]));
+  }
+  auto cppOutputs = mnn_module->onForward(cppInputs);
+  auto out = (VARPArray *)malloc(sizeof(VARPArray));
+  out->size = cppOutputs.size();
+  out->vars = (VARP **)malloc(out->size * sizeof(VARP *));
+  for (size_t i = 0; i < out->size; ++i) {
+    out->vars[i] =
+        reinterpret_cast<VARP *>(new MNN::Express::VARP(cppOutputs[i]));
+  }
+  return out;
+}
+
+size_t Module_outputCount(const Module *module) {
+  auto info =
+      reinterpret_cast<const MNN::Express::Module *>(module)->getInfo();
+  if (info == nullptr) {
+    return 0;
+  }
+  return info->outputNames.size();
+}
+
+void destroyVARPArray(VARPArray *array) {
+  free(array->vars);
+  array->vars = NULL;
+  array->size = 0;
+  free(array);
+}
+
+VARP *VARP_createWith(const int *shape, size_t shapeSize,
+                      halide_type_t typeCode, const void *data,
+                      DimensionType dimType) {
+  std::vector<int> shapeVec(shape, shape + shapeSize);
+  MNN::Express::Dimensionformat format;
+  switch (dimType) {
+  case TENSORFLOW:
+    format = MNN::Express::NHWC;
+    break;
+  case CAFFE_C4:
+    format = MNN::Express::NC4HW4;
+    break;
+  default:
+    format = MNN::Express::NCHW;
+    break;
+  }
+  auto var = MNN::Express::_Input(shapeVec, format, typeCode);
+  auto info = var->getInfo();
+  if (data != nullptr && info != nullptr) {
+    ::memcpy(var->writeMap<void>(), data, info->size * typeCode.bytes());
+  }
+  return reinterpret_cast<VARP *>(new MNN::Express::VARP(var));
+}
+
+void VARP_destroy(VARP *var) {
+  delete reinterpret_cast<MNN::Express::VARP *>(var);
+}
+
+size_t VARP_dims(const VARP *var, int *dims, size_t dimsSize) {
+  auto info = (*reinterpret_cast<const MNN::Express::VARP *>(var))->getInfo();
+  if (info == nullptr) {
+    return 0;
+  }
+  for (size_t i = 0; i < info->dim.size() && i < dimsSize; i++) {
+    dims[i] = info->dim[i];
+  }
+  return info->dim.size();
+}
+
+size_t VARP_elementSize(const VARP *var) {
+  auto info = (*reinterpret_cast<const MNN::Express::VARP *>(var))->getInfo();
+  if (info == nullptr) {
+    return 0;
+  }
+  return info->size;
+}
+
+halide_type_t VARP_getType(const VARP *var) {
+  auto info = (*reinterpret_cast<const MNN::Express::VARP *>(var))->getInfo();
+  if (info == nullptr) {
+    return halide_type_t(halide_type_float, 0, 0);
+  }
+  return info->type;
+}
+
+const void *VARP_readMap(VARP *var) {
+  return (*reinterpret_cast<MNN::Express::VARP *>(var))->readMap<void>();
+}
+} // extern "C"
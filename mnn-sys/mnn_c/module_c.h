@@ -0,0 +1,61 @@
+#ifndef MODULE_C_H
+#define MODULE_C_H
+#include "schedule_c.h"
+#include "tensor_c.h"
+#include <MNN/HalideRuntime.h>
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct Module Module;
+typedef struct VARP VARP;
+typedef struct {
+  VARP **vars;
+  size_t size;
+} VARPArray;
+
+/**
+ * @brief load a module from file.
+ * @param inputs    names of the input variables (all model inputs if empty).
+ * @param outputs   names of the output variables (all model outputs if empty).
+ * @param file      given model file.
+ * @param config    schedule config used to create the runtime.
+ * @return loaded module if success, NULL otherwise.
+ */
+Module *Module_load(const char *const *inputs, size_t inputsSize,
+                    const char *const *outputs, size_t outputsSize,
+                    const char *file, const MNNScheduleConfig *config);
+void Module_destroy(Module *module);
+/**
+ * @brief run the module.
+ * @return array of output variables, the variables are owned by the caller
+ * and the array must be released with destroyVARPArray.
+ */
+VARPArray *Module_forward(Module *module, VARP *const *inputs,
+                          size_t inputsSize);
+/**
+ * @brief number of outputs the module was loaded with (0 if unknown).
+ */
+size_t Module_outputCount(const Module *module);
+void destroyVARPArray(VARPArray *array);
+
+VARP *VARP_createWith(const int *shape, size_t shapeSize,
+                      struct halide_type_t typeCode, const void *data,
+                      DimensionType dimType);
+void VARP_destroy(VARP *var);
+/**
+ * @brief copy at most dimsSize dimensions of the variable to dims.
+ * @return the number of dimensions of the variable (which can be more than
+ * dimsSize).
+ */
+size_t VARP_dims(const VARP *var, int *dims, size_t dimsSize);
+size_t VARP_elementSize(const VARP *var);
+struct halide_type_t VARP_getType(const VARP *var);
+const void *VARP_readMap(VARP *var);
+
+#ifdef __cplusplus
+}
+#endif
+#endif // MODULE_C_H
@@ -61,3 +61,6 @@ MNNForwardType mnnsc_get_type(MNNScheduleConfig *config) {
 MNNForwardType mnnsc_get_backup_type(MNNScheduleConfig *config) {
   return reinterpret_cast<MNN::ScheduleConfig *>(config)->backupType;
 }
+int mnnsc_get_num_threads(MNNScheduleConfig *config) {
+  return reinterpret_cast<MNN::ScheduleConfig *>(config)->numThread;
+}
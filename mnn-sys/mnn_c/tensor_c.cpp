@@ -1,7 +1,10 @@
 #include "tensor_c.h"
 #include "MNN/Tensor.hpp"
+#include "core/Backend.hpp"
+#include "core/TensorUtils.hpp"
 #include "utils.h"
 #include <cstdio>
+#include <memory>
 #ifdef __DEBUG
 #include <iostream>
 void code_bits_lanes(const char *name, halide_type_t *type) {
@@ -53,10 +56,26 @@ int Tensor_copyToHostTensor(const Tensor *deviceTensor, Tensor *hostTensor) {
   return reinterpret_cast<const MNN::Tensor *>(deviceTensor)
       ->copyToHostTensor(reinterpret_cast<MNN::Tensor *>(hostTensor));
 }
+int Tensor_copyOnSameBackend(const Tensor *src, Tensor *dst) {
+  auto mnn_src = reinterpret_cast<const MNN::Tensor *>(src);
+  auto mnn_dst = reinterpret_cast<MNN::Tensor *>(dst);
+  auto backend = MNN::TensorUtils::getDescribeOrigin(mnn_dst)->getBackend();
+  if (backend == nullptr ||
+      backend != MNN::TensorUtils::getDescribeOrigin(mnn_src)->getBackend()) {
+    return 0;
+  }
+  backend->onCopyBuffer(mnn_src, mnn_dst);
+  return 1;
+}
 Tensor *Tensor_createHostTensorFromDevice(const Tensor *deviceTensor,
                                           int copyData) {
-  return reinterpret_cast<Tensor *>(MNN::Tensor::createHostTensorFromDevice(
-      reinterpret_cast<const MNN::Tensor *>(deviceTensor), copyData));
+  auto device = reinterpret_cast<const MNN::Tensor *>(deviceTensor);
+  auto host = MNN::Tensor::createHostTensorFromDevice(device, copyData);
+  if (host != nullptr) {
+    MNN::TensorUtils::getDescribe(host)->quantAttr =
+        MNN::TensorUtils::getDescribe(device)->quantAttr;
+  }
+  return reinterpret_cast<Tensor *>(host);
 }
 const void *Tensor_host(const Tensor *tensor) {
   return reinterpret_cast<const MNN::Tensor *>(tensor)->host<void>();
@@ -180,4 +199,26 @@ Tensor *Tensor_clone(const Tensor *tensor) {
   return reinterpret_cast<Tensor *>(ret);
 }
 
+int Tensor_getQuantParams(const Tensor *tensor, QuantParams *params) {
+  auto mnn_tensor = reinterpret_cast<const MNN::Tensor *>(tensor);
+  auto quant = MNN::TensorUtils::getDescribe(mnn_tensor)->quantAttr;
+  if (quant == nullptr) {
+    return 0;
+  }
+  params->scale = quant->scale;
+  params->zero = quant->zero;
+  params->min = quant->min;
+  params->max = quant->max;
+  return 1;
+}
+
+void Tensor_setQuantParams(Tensor *tensor, const QuantParams *params) {
+  auto mnn_tensor = reinterpret_cast<MNN::Tensor *>(tensor);
+  auto quant = std::make_shared<MNN::QuantAttr>();
+  quant->scale = params->scale;
+  quant->zero = params->zero;
+  quant->min = params->min;
+  quant->max = params->max;
+  MNN::TensorUtils::getDescribe(mnn_tensor)->quantAttr = quant;
+}
 } // extern "C"
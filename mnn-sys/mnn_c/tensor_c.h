@@ -16,6 +16,12 @@ typedef struct {
 typedef enum { TENSORFLOW, CAFFE, CAFFE_C4 } DimensionType;
 typedef enum { HANDLE_NONE = 0, HANDLE_STRING = 1 } HandleDataType;
 typedef enum { MAP_TENSOR_WRITE = 0, MAP_TENSOR_READ = 1 } MapType;
+typedef struct {
+  float scale;
+  float zero;
+  float min;
+  float max;
+} QuantParams;
 Tensor *Tensor_create(int dimSize, DimensionType type);
 Tensor *Tensor_createFromTensor(const Tensor *tensor, DimensionType type,
                                 int allocMemory);
@@ -28,6 +34,9 @@ Tensor *Tensor_createWith(const int *shape, size_t shapeSize,
                           DimensionType dimType);
 int Tensor_copyFromHostTensor(Tensor *deviceTensor, const Tensor *hostTensor);
 int Tensor_copyToHostTensor(const Tensor *deviceTensor, Tensor *hostTensor);
+/// Copies between two device tensors of the same backend without going through
+/// the host, returns 0 if they don't belong to the same backend
+int Tensor_copyOnSameBackend(const Tensor *src, Tensor *dst);
 Tensor *Tensor_createHostTensorFromDevice(const Tensor *deviceTensor,
                                           int copyData);
 DimensionType Tensor_getDimensionType(const Tensor *tensor);
@@ -60,6 +69,9 @@ int Tensor_wait(Tensor *tensor, MapType mtype, int finish);
 int Tensor_setDevicePtr(Tensor *tensor, const void *devicePtr, int memoryType);
 struct halide_type_t Tensor_getType(const Tensor *tensor);
 bool Tensor_isTypeOf(const Tensor *tensor, struct halide_type_t type);
+/** returns 1 and fills params if the tensor has quantization parameters */
+int Tensor_getQuantParams(const Tensor *tensor, QuantParams *params);
+void Tensor_setQuantParams(Tensor *tensor, const QuantParams *params);
 #ifdef __cplusplus
 }
 #endif
@@ -1,5 +1,8 @@
 use std::ffi::CStr;
+mod logging;
+#[cfg(feature = "tracing")]
 mod tracing;
+pub use logging::{clear_log_handler, emit, set_log_handler, take_error_logs, Level, LogRecord};
 
 pub mod cpp {
     #![allow(non_upper_case_globals)]
@@ -16,11 +19,45 @@ mod sys {
     include!(concat!(env!("OUT_DIR"), "/mnn_c.rs"));
 }
 pub use sys::*;
+
+/// The version (`major.minor.patch`) of the MNN headers mnn-sys was built against
+pub const MNN_VERSION: &str = env!("MNN_VERSION");
+
+include!(concat!(env!("OUT_DIR"), "/build_options.rs"));
+
+/// The OpenMP runtime MNN runs cpu sessions on with the `openmp` feature
+#[cfg(feature = "openmp")]
+pub mod omp {
+    extern "C" {
+        pub fn omp_set_num_threads(num_threads: libc::c_int);
+        pub fn omp_get_max_threads() -> libc::c_int;
+    }
+}
+
+/// The version of the linked MNN library as reported by `MNN::getVersion`
+pub fn mnn_version() -> &'static str {
+    // SAFETY: getVersion returns the MNN_VERSION string literal
+    unsafe { CStr::from_ptr(getVersion()) }
+        .to_str()
+        .unwrap_or(MNN_VERSION)
+}
 impl DimensionType {
     pub const NHWC: Self = Self::TENSORFLOW;
     pub const NCHW: Self = Self::CAFFE;
     pub const NC4HW4: Self = Self::CAFFE_C4;
 }
+impl core::ops::BitOr for MNNGpuMode {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+impl core::ops::BitOrAssign for MNNGpuMode {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
 impl halide_type_t {
     unsafe fn new(code: halide_type_code_t, bits: u8, lanes: u16) -> Self {
         Self { code, bits, lanes }
@@ -67,6 +104,12 @@ halide_types! {
     i64 =>  halide_type_t::new(halide_type_code_t::halide_type_int, 64,1)
 }
 
+#[cfg(feature = "half")]
+halide_types! {
+    half::f16 => halide_type_t::new(halide_type_code_t::halide_type_float, 16, 1),
+    half::bf16 => halide_type_t::new(halide_type_code_t::halide_type_bfloat, 16, 1)
+}
+
 impl Drop for CString {
     fn drop(&mut self) {
         unsafe { destroyCString(self.as_ptr_mut()) }
@@ -101,4 +144,4 @@ impl halide_type_code_t {
     pub unsafe fn from_u32(code: u32) -> Self {
         unsafe { std::mem::transmute(code) }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,159 @@
+//! Routing of the messages MNN prints with `MNN_PRINT` / `MNN_ERROR`
+//!
+//! The build script patches those macros to call [`mnn_ffi_emit`] which forwards the messages
+//! to a user provided handler (see [`set_log_handler`]) or else to `tracing` / `log` depending
+//! on the enabled features.
+use std::ffi::{c_char, CStr};
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// Maximum number of error messages kept per thread before the oldest ones are dropped
+const MAX_ERROR_LOGS: usize = 32;
+
+std::thread_local! {
+    static ERROR_LOGS: std::cell::RefCell<Vec<String>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+type LogHandler = Arc<dyn Fn(LogRecord) + Send + Sync + 'static>;
+
+static LOG_HANDLER: RwLock<Option<LogHandler>> = RwLock::new(None);
+
+/// Log level of a message emitted by MNN
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd)]
+pub enum Level {
+    Info = 0,
+    Error = 1,
+}
+
+/// A message emitted by MNN
+#[derive(Debug, Clone, Copy)]
+pub struct LogRecord<'a> {
+    /// Level of the message
+    pub level: Level,
+    /// C++ source file the message originates from
+    pub file: &'a str,
+    /// Line in the source file the message originates from
+    pub line: usize,
+    /// The message itself (without the trailing newline)
+    pub message: &'a str,
+}
+
+/// Route all the messages emitted by MNN to `handler` instead of `tracing` / `log`
+///
+/// The handler may be called from any thread that calls into MNN, it can itself replace or
+/// clear the handler.
+/// If the handler panics the panic is caught (it never unwinds into MNN) and the message goes to
+/// `tracing` / `log` instead
+pub fn set_log_handler(handler: impl Fn(LogRecord) + Send + Sync + 'static) {
+    *LOG_HANDLER.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(handler));
+}
+
+/// Remove the handler set with [`set_log_handler`]
+pub fn clear_log_handler() {
+    *LOG_HANDLER.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Take all the error messages logged by MNN on the current thread since the last call
+pub fn take_error_logs() -> Vec<String> {
+    ERROR_LOGS.with(|logs| core::mem::take(&mut *logs.borrow_mut()))
+}
+
+fn push_error_log(message: String) {
+    ERROR_LOGS.with(|logs| {
+        let mut logs = logs.borrow_mut();
+        if logs.len() >= MAX_ERROR_LOGS {
+            logs.remove(0);
+        }
+        logs.push(message);
+    })
+}
+
+/// Emit a message the same way as the messages of MNN (to the handler set with
+/// [`set_log_handler`] or else to `tracing` / `log`)
+///
+/// Used by the wrapper to report things that MNN doesn't log itself
+pub fn emit(level: Level, file: &'static str, line: usize, message: &str) {
+    let record = LogRecord {
+        level,
+        file,
+        line,
+        message,
+    };
+    // The handler is called without holding the lock so it can call `set_log_handler`
+    let handler = LOG_HANDLER
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    if let Some(handler) = handler {
+        let handled = std::panic::catch_unwind(AssertUnwindSafe(|| handler(record)));
+        if handled.is_ok() {
+            return;
+        }
+    }
+    #[cfg(feature = "tracing")]
+    crate::tracing::emit(level, file, line, message);
+    #[cfg(feature = "log")]
+    emit_log(&record);
+}
+
+#[cfg(feature = "log")]
+fn emit_log(record: &LogRecord) {
+    let level = match record.level {
+        Level::Info => log::Level::Info,
+        Level::Error => log::Level::Error,
+    };
+    if level > log::max_level() {
+        return;
+    }
+    log::logger().log(
+        &log::Record::builder()
+            .level(level)
+            .target("mnn_ffi_emit")
+            .file(Some(record.file))
+            .line(Some(record.line as u32))
+            .args(format_args!("{}", record.message))
+            .build(),
+    );
+}
+
+/// MNN prints the operators a backend can't create with `MNN_PRINT`, they are kept with the errors
+/// since they explain why creating a session failed
+fn is_unsupported_op(message: &str) -> bool {
+    message.starts_with("Don't support type")
+}
+
+#[no_mangle]
+extern "C" fn mnn_ffi_emit(
+    file: *const c_char,
+    line: libc::size_t,
+    level: Level,
+    message: *const c_char,
+) {
+    let emitted = std::panic::catch_unwind(|| {
+        // __FILE__ is a string literal so it lives for the whole program
+        let file: &'static str = if file.is_null() {
+            "<unknown>"
+        } else {
+            unsafe { CStr::from_ptr(file) }
+                .to_str()
+                .unwrap_or("<non utf-8 path>")
+        };
+        if message.is_null() {
+            return;
+        }
+        let message = unsafe { CStr::from_ptr(message) }.to_string_lossy();
+        let message = message.trim_end();
+
+        if level == Level::Error || is_unsupported_op(message) {
+            push_error_log(message.to_string());
+        }
+
+        emit(level, file, line, message);
+    });
+    // Cannot let a panic (e.g. from a tracing subscriber) unwind into C++, the message is lost
+    // but MNN keeps running
+    if emitted.is_err() {
+        eprintln!("Panic in mnn_ffi_emit, dropping the message");
+    }
+}
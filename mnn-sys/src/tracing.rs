@@ -1,21 +1,15 @@
 // This is mostly adapted from tracing-gstreamer crate's implementation
+use crate::logging::Level;
 use once_cell::sync::OnceCell;
 use std::sync::atomic::AtomicUsize;
+use std::collections::BTreeMap;
 use std::sync::{PoisonError, RwLock};
-use std::{collections::BTreeMap, ffi::c_char};
 use tracing_core::{field::FieldSet, identify_callsite, Callsite, Interest, Kind, Metadata};
 
 pub const CALLSITE_INTEREST_NEVER: usize = 1;
 pub const CALLSITE_INTEREST_SOMETIMES: usize = 2;
 pub const CALLSITE_INTEREST_ALWAYS: usize = 3;
 
-#[repr(C)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd)]
-pub enum Level {
-    Info = 0,
-    Error = 1,
-}
-
 impl From<Level> for tracing_core::Level {
     fn from(value: Level) -> Self {
         match value {
@@ -130,54 +124,29 @@ impl MnnCallsite {
     }
 }
 
-#[no_mangle]
-extern "C" fn mnn_ffi_emit(
-    file: *const c_char,
-    line: libc::size_t,
-    level: Level,
-    message: *const c_char,
-) {
-    std::panic::catch_unwind(|| {
-        let file: &'static str = unsafe {
-            core::ffi::CStr::from_ptr(file)
-                .to_str()
-                .expect("Invalid filename for C file")
-        };
-
-        let callsite = DynamicCallsites::get().callsite_for(level, Some(line as u32), Some(file));
-        // let interest = callsite.interest
-        let interest = callsite.interest();
-        if interest.is_never() {
+/// Emit a message from MNN as a tracing event
+pub(crate) fn emit(level: Level, file: &'static str, line: usize, message: &str) {
+    let callsite = DynamicCallsites::get().callsite_for(level, Some(line as u32), Some(file));
+    let interest = callsite.interest();
+    if interest.is_never() {
+        return;
+    }
+    let meta = callsite.metadata();
+    tracing_core::dispatcher::get_default(move |dispatcher| {
+        if !dispatcher.enabled(meta) {
             return;
         }
-        let meta = callsite.metadata();
-        tracing_core::dispatcher::get_default(move |dispatcher| {
-            if !dispatcher.enabled(meta) {
-                return;
-            }
-            let fields = meta.fields();
-            let message = unsafe {
-                std::ffi::CStr::from_ptr(message)
-                    .to_str()
-                    .expect("Invalid message for C message")
-            };
-
-            let message_value = &message as &dyn tracing_core::field::Value;
-            let message_field = fields
-                .into_iter()
-                .next()
-                .expect("Failed to get message field");
-            let values = &[(&message_field, Some(message_value))];
-            let valueset = fields.value_set(values);
-
-            let event = tracing_core::Event::new(meta, &valueset);
-
-            dispatcher.event(&event);
-        });
-    })
-    .unwrap_or_else(|_e| {
-        eprintln!("Panic in mnn_ffi_emit aborting");
-        // Cannot let the panic escape the ffi boundary
-        std::process::abort();
-    })
+        let fields = meta.fields();
+        let message_value = &message as &dyn tracing_core::field::Value;
+        let message_field = fields
+            .into_iter()
+            .next()
+            .expect("Failed to get message field");
+        let values = &[(&message_field, Some(message_value))];
+        let valueset = fields.value_set(values);
+
+        let event = tracing_core::Event::new(meta, &valueset);
+
+        dispatcher.event(&event);
+    });
 }
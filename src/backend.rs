@@ -1,10 +1,15 @@
 //! The backend module contains the data types for the backend configuration
 
-use crate::prelude::*;
+use crate::internal_prelude::*;
 use std::str::FromStr;
 
 use mnn_sys::*;
 
+#[cfg(feature = "vulkan")]
+mod vulkan;
+#[cfg(feature = "vulkan")]
+pub use self::vulkan::vulkan_devices;
+
 /// BackendConfig is a struct that holds the configuration for the backend
 /// memory: [MemoryMode]
 /// power: [PowerMode]
@@ -40,6 +45,35 @@ impl serde::Serialize for BackendConfig {
     }
 }
 
+/// Missing fields keep the defaults of [`BackendConfig::new`]
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BackendConfig {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "BackendConfig")]
+        struct Fields {
+            memory: Option<MemoryMode>,
+            power: Option<PowerMode>,
+            precision: Option<PrecisionMode>,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        let mut config = BackendConfig::new();
+        if let Some(memory) = fields.memory {
+            config.set_memory_mode(memory);
+        }
+        if let Some(power) = fields.power {
+            config.set_power_mode(power);
+        }
+        if let Some(precision) = fields.precision {
+            config.set_precision_mode(precision);
+        }
+        Ok(config)
+    }
+}
+
 impl Clone for BackendConfig {
     fn clone(&self) -> Self {
         unsafe {
@@ -106,6 +140,42 @@ impl PowerMode {
     }
 }
 
+impl From<PowerMode> for mnn_sys::PowerMode {
+    fn from(mode: PowerMode) -> Self {
+        mode.to_mnn_sys()
+    }
+}
+
+impl From<mnn_sys::PowerMode> for PowerMode {
+    fn from(mode: mnn_sys::PowerMode) -> Self {
+        Self::from_mnn_sys(mode)
+    }
+}
+
+impl From<MemoryMode> for mnn_sys::MemoryMode {
+    fn from(mode: MemoryMode) -> Self {
+        mode.to_mnn_sys()
+    }
+}
+
+impl From<mnn_sys::MemoryMode> for MemoryMode {
+    fn from(mode: mnn_sys::MemoryMode) -> Self {
+        Self::from_mnn_sys(mode)
+    }
+}
+
+impl From<PrecisionMode> for mnn_sys::PrecisionMode {
+    fn from(mode: PrecisionMode) -> Self {
+        mode.to_mnn_sys()
+    }
+}
+
+impl From<mnn_sys::PrecisionMode> for PrecisionMode {
+    fn from(mode: mnn_sys::PrecisionMode) -> Self {
+        Self::from_mnn_sys(mode)
+    }
+}
+
 impl FromStr for PowerMode {
     type Err = MNNError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -143,7 +213,8 @@ impl FromStr for PrecisionMode {
             "low" => Ok(Self::Low),
             "normal" => Ok(Self::Normal),
             "high" => Ok(Self::High),
-            "low_bf16" => Ok(Self::LowBf16),
+            "low_bf16" | "bf16" => Ok(Self::LowBf16),
+            "fp16" => Ok(Self::FP16),
             _ => Err(error!(ErrorKind::ParseError)
                 .attach_printable(format!("invalid precision mode: {s}"))),
         }
@@ -155,6 +226,11 @@ impl FromStr for PrecisionMode {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MemoryMode {
     /// Low memory mode
+    ///
+    /// With the `low-memory` feature the CPU backend keeps the weights of quantized models
+    /// quantized and dequantizes them on the fly. This lowers the peak memory (roughly by the
+    /// size of the float weights) at the cost of a slower inference, use it on memory constrained
+    /// devices where the model would otherwise not fit.
     Low,
     /// Normal memory mode
     Normal,
@@ -199,11 +275,22 @@ pub enum PrecisionMode {
     /// High precision mode
     High,
     /// Low precision mode
+    ///
+    /// On the cpu this computes in fp16 on ARMv8.2 cpus when built with the `arm82` feature
+    /// (enabled by default) and is the same as [`PrecisionMode::Normal`] otherwise
     Low,
     /// Low precision mode with BF16
+    ///
+    /// On the cpu this computes in bf16 when built with the `bf16` feature and is the same as
+    /// [`PrecisionMode::Low`] otherwise
     LowBf16,
 }
 impl PrecisionMode {
+    /// Low precision mode, backends that support it will compute in float16
+    pub const FP16: Self = Self::Low;
+    /// Low precision mode with BF16
+    pub const BF16: Self = Self::LowBf16;
+
     pub(crate) fn to_mnn_sys(self) -> mnn_sys::PrecisionMode {
         match self {
             Self::LowBf16 => mnn_sys::PrecisionMode::Precision_Low_BF16,
@@ -332,6 +419,111 @@ impl BackendConfig {
         }
         self
     }
+
+    /// Run the Vulkan backend on the physical device at `index` of [`vulkan_devices`]
+    /// (requires the `vulkan` feature)
+    ///
+    /// This sets the shared context of the config. The logical device is created on the first
+    /// call for an index and is kept for the lifetime of the process.
+    ///
+    /// Interpreters reuse the runtime of their first session of a given [`ForwardType`](crate::ForwardType),
+    /// so the device should be picked before creating the first vulkan session of an interpreter.
+    #[cfg(feature = "vulkan")]
+    pub fn set_vulkan_device(&mut self, index: u32) -> Result<()> {
+        let context = self::vulkan::context(index)?;
+        // SAFETY: The context is never freed and MNN only reads it
+        unsafe {
+            self.set_shared_context(
+                context as *const self::vulkan::MNNVulkanContext as *mut libc::c_void,
+            );
+        }
+        Ok(())
+    }
+
+    /// Run the Vulkan backend on the physical device at `index` of [`vulkan_devices`]
+    /// (requires the `vulkan` feature)
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// println!("{:?}", vulkan_devices());
+    /// let config = ScheduleConfig::new()
+    ///     .with_type(ForwardType::Vulkan)
+    ///     .with_backend_config(BackendConfig::new().with_vulkan_device(1).unwrap());
+    /// ```
+    #[cfg(feature = "vulkan")]
+    pub fn with_vulkan_device(mut self, index: u32) -> Result<Self> {
+        self.set_vulkan_device(index)?;
+        Ok(self)
+    }
+
+    /// Sets the [CoreMLConfig] for the CoreML backend
+    ///
+    /// This overwrites the precision and power modes, see [ComputeUnits]
+    #[cfg(feature = "coreml")]
+    pub fn set_coreml_config(&mut self, config: CoreMLConfig) {
+        let (precision, power) = config.compute_units.modes();
+        self.set_precision_mode(precision);
+        self.set_power_mode(power);
+    }
+
+    /// Sets the [CoreMLConfig] for the CoreML backend
+    #[cfg(feature = "coreml")]
+    pub fn with_coreml_config(mut self, config: CoreMLConfig) -> Self {
+        self.set_coreml_config(config);
+        self
+    }
+}
+
+/// Configuration of the CoreML backend (requires the `coreml` feature)
+///
+/// ```rust,no_run
+/// # use mnn::*;
+/// let config = ScheduleConfig::new()
+///     .with_type(ForwardType::CoreML)
+///     .with_backend_config(BackendConfig::new().with_coreml_config(CoreMLConfig {
+///         compute_units: ComputeUnits::CpuAndNeuralEngine,
+///     }));
+/// ```
+#[cfg(feature = "coreml")]
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CoreMLConfig {
+    /// The compute units CoreML may run the model on
+    pub compute_units: ComputeUnits,
+}
+
+/// The compute units of the CoreML backend
+///
+/// MNN has no dedicated option for them, its CoreML backend derives the `MLComputeUnits` from the
+/// precision and power modes of the [BackendConfig]:
+/// - a high precision runs on the cpu only (the gpu and the Neural Engine compute in float16)
+/// - otherwise a low power mode prefers the Neural Engine and a high power mode the gpu
+#[cfg(feature = "coreml")]
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ComputeUnits {
+    /// Let CoreML pick between the cpu, the gpu and the Neural Engine
+    #[default]
+    All,
+    /// Only run on the cpu
+    CpuOnly,
+    /// Run on the cpu and the gpu
+    CpuAndGpu,
+    /// Run on the cpu and the Neural Engine (much faster for some models on Apple Silicon)
+    CpuAndNeuralEngine,
+}
+
+#[cfg(feature = "coreml")]
+impl ComputeUnits {
+    /// The precision and power modes selecting the compute units in MNN
+    fn modes(self) -> (PrecisionMode, PowerMode) {
+        match self {
+            Self::All => (PrecisionMode::Low, PowerMode::Normal),
+            Self::CpuOnly => (PrecisionMode::High, PowerMode::Normal),
+            Self::CpuAndGpu => (PrecisionMode::Low, PowerMode::High),
+            Self::CpuAndNeuralEngine => (PrecisionMode::Low, PowerMode::Low),
+        }
+    }
 }
 
 #[test]
@@ -352,3 +544,24 @@ fn test_backend_config() {
     assert_eq!(config.get_power_mode(), PowerMode::Normal);
     assert_eq!(config.get_precision_mode(), PrecisionMode::Normal);
 }
+
+#[test]
+fn test_backend_modes_round_trip() {
+    for mode in [PowerMode::Low, PowerMode::Normal, PowerMode::High] {
+        assert_eq!(PowerMode::from(mnn_sys::PowerMode::from(mode)), mode);
+    }
+    for mode in [MemoryMode::Low, MemoryMode::Normal, MemoryMode::High] {
+        assert_eq!(MemoryMode::from(mnn_sys::MemoryMode::from(mode)), mode);
+    }
+    for mode in [
+        PrecisionMode::Low,
+        PrecisionMode::Normal,
+        PrecisionMode::High,
+        PrecisionMode::LowBf16,
+    ] {
+        assert_eq!(PrecisionMode::from(mnn_sys::PrecisionMode::from(mode)), mode);
+    }
+    assert_eq!("fp16".parse::<PrecisionMode>().unwrap(), PrecisionMode::Low);
+    let config = BackendConfig::default().with_precision_mode(PrecisionMode::FP16);
+    assert_eq!(config.get_precision_mode(), PrecisionMode::Low);
+}
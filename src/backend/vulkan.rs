@@ -0,0 +1,143 @@
+//! Selection of the device used by the Vulkan backend (requires the `vulkan` feature)
+//!
+//! MNN always runs on the first physical device it enumerates unless it's given a
+//! `MNNVulkanContext` as the shared context of the [`BackendConfig`](crate::BackendConfig).
+//! The contexts are created here on the requested device with [`ash`].
+use crate::{ForwardType, internal_prelude::*};
+use ash::vk;
+use std::{
+    collections::HashMap,
+    ffi::CStr,
+    sync::{Mutex, OnceLock},
+};
+
+/// Same layout as `MNNVulkanContext` in `MNN/MNNSharedContext.h`
+#[repr(C)]
+pub(crate) struct MNNVulkanContext {
+    instance: vk::Instance,
+    physical_device: vk::PhysicalDevice,
+    device: vk::Device,
+    queue: vk::Queue,
+    queue_family_index: u32,
+}
+
+// SAFETY: The handles are only read by MNN and vulkan handles aren't tied to a thread
+unsafe impl Send for MNNVulkanContext {}
+unsafe impl Sync for MNNVulkanContext {}
+
+struct Vulkan {
+    // Keeps the loader loaded for the instance
+    _entry: ash::Entry,
+    instance: ash::Instance,
+}
+
+/// The instance shared by all the contexts
+///
+/// It is created on first use and never destroyed since the runtimes cached by the interpreters
+/// can outlive any session using it.
+fn vulkan() -> Result<&'static Vulkan> {
+    static VULKAN: OnceLock<Result<Vulkan, String>> = OnceLock::new();
+    VULKAN
+        .get_or_init(|| unsafe {
+            let entry = ash::Entry::load().map_err(|e| e.to_string())?;
+            let app_info = vk::ApplicationInfo::default().api_version(vk::API_VERSION_1_1);
+            let instance = entry
+                .create_instance(
+                    &vk::InstanceCreateInfo::default().application_info(&app_info),
+                    None,
+                )
+                .map_err(|e| e.to_string())?;
+            Ok(Vulkan {
+                _entry: entry,
+                instance,
+            })
+        })
+        .as_ref()
+        .map_err(|e| {
+            error!(ErrorKind::UnsupportedBackend(ForwardType::Vulkan)).attach_printable(e.clone())
+        })
+}
+
+/// Names of the vulkan physical devices, in the order used by
+/// [`BackendConfig::set_vulkan_device`](crate::BackendConfig::set_vulkan_device)
+///
+/// Returns an empty list if the vulkan loader or driver isn't available.
+pub fn vulkan_devices() -> Vec<String> {
+    let Ok(vulkan) = vulkan() else {
+        return Vec::new();
+    };
+    let Ok(devices) = (unsafe { vulkan.instance.enumerate_physical_devices() }) else {
+        return Vec::new();
+    };
+    devices
+        .into_iter()
+        .map(|device| {
+            let properties = unsafe { vulkan.instance.get_physical_device_properties(device) };
+            unsafe { CStr::from_ptr(properties.device_name.as_ptr()) }
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect()
+}
+
+/// The context for the physical device at `index`, the logical devices are created once and
+/// reused for the lifetime of the process
+pub(crate) fn context(index: u32) -> Result<&'static MNNVulkanContext> {
+    static CONTEXTS: OnceLock<Mutex<HashMap<u32, &'static MNNVulkanContext>>> = OnceLock::new();
+    let mut contexts = CONTEXTS
+        .get_or_init(Default::default)
+        .lock()
+        .map_err(|e| error!(ErrorKind::SyncError).attach_printable(e.to_string()))?;
+    if let Some(context) = contexts.get(&index) {
+        return Ok(context);
+    }
+
+    let vulkan = vulkan()?;
+    let devices = unsafe { vulkan.instance.enumerate_physical_devices() }.map_err(|e| {
+        error!(ErrorKind::UnsupportedBackend(ForwardType::Vulkan)).attach_printable(e.to_string())
+    })?;
+    let physical_device = *devices.get(index as usize).ok_or_else(|| {
+        error!(ErrorKind::UnsupportedBackend(ForwardType::Vulkan)).attach_printable(format!(
+            "Vulkan device {index} doesn't exist, {} devices found",
+            devices.len()
+        ))
+    })?;
+    let queue_family_index = unsafe {
+        vulkan
+            .instance
+            .get_physical_device_queue_family_properties(physical_device)
+    }
+    .iter()
+    .position(|family| family.queue_flags.contains(vk::QueueFlags::COMPUTE))
+    .ok_or_else(|| {
+        error!(ErrorKind::UnsupportedBackend(ForwardType::Vulkan)).attach_printable(format!(
+            "Vulkan device {index} doesn't have a compute queue"
+        ))
+    })? as u32;
+
+    let priorities = [1.0];
+    let queue_info = vk::DeviceQueueCreateInfo::default()
+        .queue_family_index(queue_family_index)
+        .queue_priorities(&priorities);
+    let device = unsafe {
+        vulkan.instance.create_device(
+            physical_device,
+            &vk::DeviceCreateInfo::default().queue_create_infos(core::slice::from_ref(&queue_info)),
+            None,
+        )
+    }
+    .map_err(|e| {
+        error!(ErrorKind::UnsupportedBackend(ForwardType::Vulkan)).attach_printable(e.to_string())
+    })?;
+    let queue = unsafe { device.get_device_queue(queue_family_index, 0) };
+
+    let context: &'static MNNVulkanContext = Box::leak(Box::new(MNNVulkanContext {
+        instance: vulkan.instance.handle(),
+        physical_device,
+        device: device.handle(),
+        queue,
+        queue_family_index,
+    }));
+    contexts.insert(index, context);
+    Ok(context)
+}
@@ -0,0 +1,120 @@
+//! Warmup + timed runs of a session (requires the `bench` feature)
+use crate::{Session, internal_prelude::*};
+use std::time::{Duration, Instant};
+
+/// Runs a session a number of times and reports the latencies
+///
+/// The inputs are not touched between the runs, set them once before (e.g. with
+/// [`Session::bind_input`]) so the runs don't measure allocations or copies.
+///
+/// ```rust,no_run
+/// # use mnn::*;
+/// let interpreter = Interpreter::from_file("model.mnn").unwrap();
+/// let mut session = interpreter.create_session(ScheduleConfig::new()).unwrap();
+/// session.bind_input::<f32>("input").unwrap();
+/// session.bound_input_mut::<f32>("input").unwrap().fill(0.5);
+/// let report = Benchmark::new(&mut session)
+///     .warmup(10)
+///     .iters(100)
+///     .run(|s| s.run_with_inputs())
+///     .unwrap();
+/// println!("median {:?}, p99 {:?}", report.median, report.p99);
+/// ```
+pub struct Benchmark<'s> {
+    session: &'s mut Session,
+    warmup: usize,
+    iters: usize,
+}
+
+/// Latencies measured by [`Benchmark::run`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchmarkReport {
+    /// Number of timed runs
+    pub iters: usize,
+    /// Fastest run
+    pub min: Duration,
+    /// Median run
+    pub median: Duration,
+    /// 99th percentile run
+    pub p99: Duration,
+    /// Mean of all runs
+    pub mean: Duration,
+    /// Runs per second
+    pub throughput: f64,
+}
+
+impl<'s> Benchmark<'s> {
+    /// Benchmark `session` with 10 warmup runs and 100 timed runs
+    pub fn new(session: &'s mut Session) -> Self {
+        Self {
+            session,
+            warmup: 10,
+            iters: 100,
+        }
+    }
+
+    /// Set the number of untimed runs before the timed runs
+    pub fn warmup(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// Set the number of timed runs (at least 1)
+    pub fn iters(mut self, iters: usize) -> Self {
+        self.iters = iters.max(1);
+        self
+    }
+
+    /// Run `f` on the session for the warmup and the timed runs
+    ///
+    /// `f` should wait for the outputs on gpu backends (e.g. with [`crate::Interpreter::wait`]),
+    /// otherwise only the time to queue the run is measured.
+    pub fn run(self, mut f: impl FnMut(&mut Session) -> Result<()>) -> Result<BenchmarkReport> {
+        for _ in 0..self.warmup {
+            f(self.session)?;
+        }
+        let mut times = Vec::with_capacity(self.iters);
+        for _ in 0..self.iters {
+            let start = Instant::now();
+            f(self.session)?;
+            times.push(start.elapsed());
+        }
+        Ok(BenchmarkReport::from_times(times))
+    }
+}
+
+impl BenchmarkReport {
+    fn from_times(mut times: Vec<Duration>) -> Self {
+        times.sort_unstable();
+        let iters = times.len();
+        let total: Duration = times.iter().sum();
+        let mean = total / iters as u32;
+        // Nearest rank percentile
+        let percentile = |p: usize| times[(iters * p).div_ceil(100).clamp(1, iters) - 1];
+        Self {
+            iters,
+            min: times[0],
+            median: percentile(50),
+            p99: percentile(99),
+            mean,
+            throughput: iters as f64 / total.as_secs_f64().max(f64::MIN_POSITIVE),
+        }
+    }
+}
+
+#[cfg(test)]
+mod bench_tests {
+    use super::*;
+
+    #[test]
+    fn report_from_times() {
+        let times = (1..=100).rev().map(Duration::from_millis).collect();
+        let report = BenchmarkReport::from_times(times);
+        assert_eq!(report.iters, 100);
+        assert_eq!(report.min, Duration::from_millis(1));
+        assert_eq!(report.median, Duration::from_millis(50));
+        assert_eq!(report.p99, Duration::from_millis(99));
+        assert_eq!(report.mean, Duration::from_micros(50500));
+        assert!((report.throughput - 100.0 / 5.05).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,134 @@
+use crate::ForwardType;
+
+/// How the crate and MNN were built, see [`capabilities`]
+///
+/// The [`Display`](core::fmt::Display) implementation is meant to be pasted in bug reports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    /// The MNN version the crate was built against ([`crate::version`])
+    pub version: &'static str,
+    /// The version of the linked MNN library ([`crate::linked_version`])
+    pub linked_version: &'static str,
+    /// The backends compiled in, they might still be unavailable on this device
+    /// (see [`ForwardType::all_available`])
+    pub backends: Vec<ForwardType>,
+    /// MNN's own thread pool (`mnn-threadpool` feature)
+    pub thread_pool: bool,
+    /// OpenMP instead of the thread pool (`openmp` feature)
+    pub openmp: bool,
+    /// The SSE / AVX kernels on x86 (disabled by the `portable-cpu` feature)
+    pub sse: bool,
+    /// `MNN_LOW_MEMORY` (`low-memory` feature)
+    pub low_memory: bool,
+    /// The size optimized build (`mini` feature)
+    pub mini: bool,
+    /// The ARMv8.2 fp16 kernels (`arm82` feature on aarch64)
+    pub arm82: bool,
+    /// MNN was compiled by the build script instead of linked from `MNN_LIB_DIR`, the options
+    /// above are only the requested ones for a prebuilt library
+    pub compiled: bool,
+    /// All the cmake options recorded by the build script
+    pub options: &'static [(&'static str, bool)],
+}
+
+/// Report how the crate and MNN were built
+///
+/// The options are written by the build script of mnn-sys from the enabled cargo features.
+///
+/// ```rust
+/// let capabilities = mnn::capabilities();
+/// assert!(capabilities.backends.contains(&mnn::ForwardType::CPU));
+/// println!("{capabilities}");
+/// ```
+pub fn capabilities() -> Capabilities {
+    let options = mnn_sys::BUILD_OPTIONS;
+    let enabled = |name: &str| options.iter().any(|&(option, on)| option == name && on);
+    let backends = core::iter::once(ForwardType::CPU)
+        .chain(
+            options
+                .iter()
+                .filter(|(_, on)| *on)
+                .filter_map(|(name, _)| name.strip_prefix("MNN_")?.parse::<ForwardType>().ok()),
+        )
+        .collect();
+    Capabilities {
+        version: crate::version(),
+        linked_version: crate::linked_version(),
+        backends,
+        thread_pool: enabled("MNN_USE_THREAD_POOL"),
+        openmp: enabled("MNN_OPENMP"),
+        sse: enabled("MNN_USE_SSE"),
+        low_memory: enabled("MNN_LOW_MEMORY"),
+        mini: enabled("MNN_BUILD_MINI"),
+        arm82: enabled("MNN_ARM82"),
+        compiled: enabled("MNN_COMPILE"),
+        options,
+    }
+}
+
+impl core::fmt::Display for Capabilities {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let yes_no = |value: bool| if value { "yes" } else { "no" };
+        let backends: Vec<_> = self.backends.iter().map(|b| b.name()).collect();
+        writeln!(f, "MNN version:    {}", self.version)?;
+        if self.linked_version != self.version {
+            writeln!(f, "Linked version: {}", self.linked_version)?;
+        }
+        let build = if self.compiled {
+            "compiled"
+        } else {
+            "prebuilt"
+        };
+        writeln!(f, "MNN build:      {build}")?;
+        writeln!(f, "Backends:       {}", backends.join(", "))?;
+        writeln!(
+            f,
+            "Threading:      {}",
+            match (self.openmp, self.thread_pool) {
+                (true, _) => "openmp",
+                (false, true) => "mnn thread pool",
+                (false, false) => "none",
+            }
+        )?;
+        writeln!(f, "SSE / AVX:      {}", yes_no(self.sse))?;
+        writeln!(f, "Low memory:     {}", yes_no(self.low_memory))?;
+        writeln!(f, "ARMv8.2 fp16:   {}", yes_no(self.arm82))?;
+        write!(f, "Mini:           {}", yes_no(self.mini))
+    }
+}
+
+#[cfg(test)]
+mod capabilities_tests {
+    use super::*;
+
+    #[test]
+    fn capabilities_match_features() {
+        let capabilities = capabilities();
+        let has = |forward: &str| capabilities.backends.iter().any(|b| b.name() == forward);
+        assert!(has("cpu"));
+        assert_eq!(has("metal"), cfg!(feature = "metal"));
+        assert_eq!(has("opencl"), cfg!(feature = "opencl"));
+        assert_eq!(has("opengl"), cfg!(feature = "opengl"));
+        assert_eq!(has("vulkan"), cfg!(feature = "vulkan"));
+        assert_eq!(has("coreml"), cfg!(feature = "coreml"));
+        assert_eq!(
+            has("nnapi"),
+            cfg!(all(feature = "nnapi", target_os = "android"))
+        );
+        assert_eq!(capabilities.thread_pool, cfg!(feature = "mnn-threadpool"));
+        assert_eq!(capabilities.openmp, cfg!(feature = "openmp"));
+        assert_eq!(capabilities.low_memory, cfg!(feature = "low-memory"));
+        assert_eq!(capabilities.mini, cfg!(feature = "mini"));
+        assert_eq!(
+            capabilities.arm82,
+            cfg!(all(feature = "arm82", target_arch = "aarch64"))
+        );
+        if cfg!(feature = "portable-cpu") {
+            assert!(!capabilities.sse);
+        }
+
+        let report = capabilities.to_string();
+        assert!(report.contains(capabilities.version));
+        assert!(report.contains("cpu"));
+    }
+}
@@ -0,0 +1,72 @@
+//! Convert models to the MNN format (requires the `converter` feature)
+//!
+//! This builds MNN's converter library together with MNN which makes the build considerably
+//! longer (it also builds protobuf and the ONNX / TensorFlow / TFLite / TorchScript frontends).
+use crate::internal_prelude::*;
+use std::path::Path;
+
+/// The format of a model passed to [`convert_model`]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ModelFormat {
+    /// ONNX model (`.onnx`)
+    Onnx,
+    /// TensorFlow frozen graph (`.pb`)
+    Tensorflow,
+    /// TensorFlow Lite model (`.tflite`)
+    TfLite,
+    /// TorchScript model (`.pt`)
+    Torchscript,
+    /// MNN model, re-runs the optimization passes of the converter
+    Mnn,
+    /// MNN model dumped to json by MNNDump2Json
+    Json,
+}
+
+impl ModelFormat {
+    /// The name of the format as accepted by `MNNConvert -f`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Onnx => "ONNX",
+            Self::Tensorflow => "TF",
+            Self::TfLite => "TFLITE",
+            Self::Torchscript => "TORCH",
+            Self::Mnn => "MNN",
+            Self::Json => "JSON",
+        }
+    }
+}
+
+/// Convert the model at `from` to a `.mnn` model at `to`
+///
+/// This calls the converter library in process, the converter logs are attached to the error
+/// when the conversion fails.
+///
+/// ```rust,no_run
+/// # use mnn::*;
+/// convert_model("model.onnx", "model.mnn", ModelFormat::Onnx).unwrap();
+/// let interpreter = Interpreter::from_file("model.mnn").unwrap();
+/// ```
+pub fn convert_model(
+    from: impl AsRef<Path>,
+    to: impl AsRef<Path>,
+    format: ModelFormat,
+) -> Result<()> {
+    let from = from.as_ref();
+    let to = to.as_ref();
+    ensure!(from.exists(), ErrorKind::IOError; from.to_string_lossy().to_string(), "File not found");
+    let c_path = |path: &Path| -> Result<std::ffi::CString> {
+        let path = path.to_str().ok_or_else(|| error!(ErrorKind::AsciiError))?;
+        Ok(std::ffi::CString::new(path).change_context(ErrorKind::AsciiError)?)
+    };
+    let c_from = c_path(from)?;
+    let c_to = c_path(to)?;
+    let c_format = std::ffi::CString::new(format.as_str()).change_context(ErrorKind::AsciiError)?;
+    let converted =
+        unsafe { mnn_sys::MNN_convertModel(c_from.as_ptr(), c_to.as_ptr(), c_format.as_ptr()) };
+    ensure!(
+        converted,
+        ErrorKind::ConvertError;
+        format!("Failed to convert {} from {}", from.display(), format.as_str())
+    );
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use crate::ForwardType;
 use mnn_sys::ErrorCode;
 
 #[doc(hidden)]
@@ -20,7 +21,7 @@ impl core::fmt::Debug for MNNError {
     }
 }
 
-impl std::error::Error for MNNError {}
+impl core::error::Error for MNNError {}
 // pub type MNNError = error_stack::Report<ErrorKind>;
 
 /// Error types for MNN
@@ -67,13 +68,88 @@ pub enum ErrorKind {
     /// Tried to run a dynamic tensor without resizing it first
     #[error("Dynamic Tensor Error: Tensor needs to be resized before using")]
     DynamicTensorError,
+    /// Failed to load the model (invalid / corrupted model file or buffer)
+    #[error("Failed to load model")]
+    ModelLoad,
+    /// The requested backend is not available on this device / build
+    #[error("Unsupported backend: {0:?}")]
+    UnsupportedBackend(ForwardType),
+    /// A MNN call returned a null pointer without reporting an error
+    #[error("{op} returned null")]
+    NullResult {
+        /// The MNN call that returned null
+        op: &'static str,
+    },
+    /// Failed to convert a model to the MNN format
+    #[error("Failed to convert model")]
+    ConvertError,
+    /// Mismatching shapes between two tensors
+    #[error("Shape mismatch: expected {expected:?}, got {got:?}")]
+    ShapeMismatch {
+        /// Expected shape
+        expected: Vec<usize>,
+        /// Provided shape
+        got: Vec<usize>,
+    },
+    /// The run was stopped because it didn't finish before its deadline
+    #[error("Timed out")]
+    Timeout,
+    /// The model has an operator that none of the backends of the session can run
+    #[error("Unsupported operator {op_type} on {backend:?}")]
+    UnsupportedOp {
+        /// The type of the operator (e.g. `Convolution3D`)
+        op_type: String,
+        /// The backend the session was created for
+        backend: ForwardType,
+    },
+    /// The model was converted by a newer MNN than the one linked
+    #[error("The model was converted by MNN {model} but MNN {supported} is linked")]
+    SchemaVersion {
+        /// The MNN version the model was converted with
+        model: String,
+        /// The version of the linked MNN
+        supported: String,
+    },
+}
+
+impl ErrorKind {
+    /// Whether the error can only be raised by a failing MNN call (and may have error logs
+    /// attached)
+    ///
+    /// The other kinds are also used to validate arguments on the rust side, they only get the
+    /// logs when they're created with [`MNNError::from_mnn`].
+    fn is_from_mnn(&self) -> bool {
+        matches!(
+            self,
+            Self::InternalError(_)
+                | Self::TensorCopyFailed(_)
+                | Self::NullResult { .. }
+                | Self::ConvertError
+                | Self::UnsupportedOp { .. }
+        )
+    }
 }
 
 impl MNNError {
     #[track_caller]
     #[doc(hidden)]
     pub fn new(kind: ErrorKind) -> Self {
-        let kind = error_stack::Report::new(kind);
+        if kind.is_from_mnn() {
+            return Self::from_mnn(kind);
+        }
+        Self {
+            kind: error_stack::Report::new(kind),
+        }
+    }
+
+    /// Create an error right after a MNN call failed, with the error logs MNN printed attached
+    #[track_caller]
+    pub(crate) fn from_mnn(kind: ErrorKind) -> Self {
+        let mut kind = error_stack::Report::new(kind);
+        for log in mnn_sys::take_error_logs() {
+            kind = kind.attach_printable(log);
+        }
+        kind = kind.attach_printable(format!("MNN version {}", crate::linked_version()));
         Self { kind }
     }
 
@@ -82,6 +158,19 @@ impl MNNError {
         Self::new(ErrorKind::InternalError(code))
     }
 
+    /// Get the kind of the error, useful to `match` on the failure mode
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// match Interpreter::from_bytes([0; 16]) {
+    ///     Err(e) if matches!(e.kind(), ErrorKind::ModelLoad) => {}
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn kind(&self) -> &ErrorKind {
+        self.kind.current_context()
+    }
+
     /// Return the inner [error_stack::Report] containing the error
     #[inline(always)]
     pub fn into_inner(self) -> error_stack::Report<ErrorKind> {
@@ -89,6 +178,13 @@ impl MNNError {
     }
 }
 
+impl From<ErrorCode> for MNNError {
+    #[track_caller]
+    fn from(code: ErrorCode) -> Self {
+        Self::from_error_code(code)
+    }
+}
+
 impl From<ErrorKind> for MNNError {
     #[track_caller]
     fn from(kind: ErrorKind) -> Self {
@@ -1,10 +1,19 @@
 //! The interpreter module provides the `Interpreter` struct which is used to load and run models.
 use crate::tensor::list::TensorList;
-use std::{ffi::CStr, path::Path, sync::Arc};
+use std::{
+    collections::HashMap,
+    ffi::CStr,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex, PoisonError, RwLock, RwLockReadGuard,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+};
 use std::env;
 
 use crate::{
-    AsTensorShape, Device, RawTensor, Ref, RefMut, ScheduleConfig, Tensor, TensorType, prelude::*,
+    AsTensorShape, Device, ForwardType, RawTensor, Ref, RefMut, ScheduleConfig, Tensor, TensorType,
+    internal_prelude::*,
 };
 use mnn_sys::HalideType;
 
@@ -86,10 +95,32 @@ impl core::ops::Deref for TensorCallback {
 
 /// The session mode to be used
 /// The items are mostly untested and are only documented 1:1 to the C++ codebase
-/// The only two items tested are
+/// The only items tested are
 /// - `Debug`
 /// - `Release`
-#[derive(Debug, Copy, Clone)]
+/// - `InputUser`
+///
+/// The mode is set on the interpreter with [`Interpreter::set_session_mode`] and applies to the
+/// sessions created after it, each call sets one of the pairs (e.g. `InputInside` / `InputUser`).
+///
+/// # Tensor ownership
+/// `InputUser` / `OutputUser` change who owns the memory of the input / output tensors.
+/// With `InputUser` the session doesn't keep the input data around, so the input has to be
+/// filled (e.g. with [`Tensor::copy_from_host_tensor`]) before **each** run of the session.
+/// ```rust,no_run
+/// # use mnn::*;
+/// let mut net = Interpreter::from_file("model.mnn").unwrap();
+/// net.set_session_mode(SessionMode::InputUser);
+/// let session = net.create_session(ScheduleConfig::new()).unwrap();
+/// let mut input = net.input::<f32>(&session, "input").unwrap();
+/// let mut host = input.create_host_tensor_from_device(false);
+/// for value in [0.0, 1.0] {
+///     host.fill(value);
+///     input.copy_from_host_tensor(&host).unwrap();
+///     net.run_session(&session).unwrap();
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[cfg_attr(windows, repr(i32))]
 #[cfg_attr(not(windows), repr(u32))]
 pub enum SessionMode {
@@ -108,6 +139,10 @@ pub enum SessionMode {
     #[doc = "Try Resize Session when create Session or not, default direct:"]
     ResizeDirect = mnn_sys::SessionMode::Session_Resize_Direct,
     #[doc = "Try Resize Session when create Session or not, default direct:"]
+    ///
+    /// The sessions aren't resized to the default input shapes when they are created, resize them
+    /// once to the real shapes with [`Session::resize_deferred`](crate::Session::resize_deferred)
+    /// or [`Session::resize`](crate::Session::resize) before running them.
     ResizeDefer = mnn_sys::SessionMode::Session_Resize_Defer,
     #[doc = "Determine the Execution's forward type is determine by user or auto\ndetermine"]
     BackendFix = mnn_sys::SessionMode::Session_Backend_Fix,
@@ -150,19 +185,180 @@ impl From<SessionMode> for mnn_sys::SessionMode::Type {
     }
 }
 
+impl SessionMode {
+    /// The mode of the pair of `self` MNN uses until another one is set
+    pub(crate) fn default_of_pair(self) -> Self {
+        match self {
+            Self::Debug | Self::Release => Self::Debug,
+            Self::InputInside | Self::InputUser => Self::InputInside,
+            Self::OutputInside | Self::OutputUser => Self::OutputInside,
+            Self::ResizeDirect | Self::ResizeDefer => Self::ResizeDirect,
+            Self::BackendFix | Self::BackendAuto => Self::BackendFix,
+            Self::MemoryCollect | Self::MemoryCache => Self::MemoryCollect,
+            Self::CodegenDisable | Self::CodegenEnable => Self::CodegenDisable,
+            Self::ResizeCheck | Self::ResizeFix => Self::ResizeCheck,
+        }
+    }
+}
+
+/// Hints used to tune the sessions created by an interpreter with
+/// [`Interpreter::set_session_hint`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SessionHint {
+    /// Max number of tuning iterations of the gpu backends (0 disables the autotuning)
+    MaxTuningNumber,
+    /// Stride used by the conv algorithm selection
+    StrideAlgorithm,
+    /// Memory allocator type (0: default, 1: defer)
+    MemAllocatorType,
+    /// Winograd memory level (0: lowest memory, higher values use more memory)
+    WinogradMemoryLevel,
+    /// Mask of the geometry compute optimizations
+    GeometryComputeMask,
+    /// A raw `MNN::Interpreter::HintMode` not known by this crate
+    Raw(i32),
+}
+
+impl SessionHint {
+    fn to_mnn_sys(self) -> libc::c_int {
+        use mnn_sys::cpp::*;
+        match self {
+            Self::MaxTuningNumber => MNN_Interpreter_HintMode_MAX_TUNING_NUMBER as libc::c_int,
+            Self::StrideAlgorithm => MNN_Interpreter_HintMode_STRIDE_ALGORITHM as libc::c_int,
+            Self::MemAllocatorType => MNN_Interpreter_HintMode_MEM_ALLOCATOR_TYPE as libc::c_int,
+            Self::WinogradMemoryLevel => {
+                MNN_Interpreter_HintMode_WINOGRAD_MEMORY_LEVEL as libc::c_int
+            }
+            Self::GeometryComputeMask => {
+                MNN_Interpreter_HintMode_GEOMETRY_COMPUTE_MASK as libc::c_int
+            }
+            Self::Raw(mode) => mode,
+        }
+    }
+}
+
+/// Metadata stored in a model by the converter, see [`Interpreter::metadata`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ModelMetadata {
+    /// Version of the converter that wrote the model (`"<2.0.0"` for models older than 2.0.0)
+    pub version: String,
+    /// Business code of the model (`--bizCode` of the converter), empty if absent
+    pub biz_code: String,
+    /// UUID of the model, empty if absent
+    pub uuid: String,
+}
+
 /// net data holder. multiple sessions could share same net.
-#[repr(transparent)]
-#[derive(Debug)]
+///
+/// # Thread safety
+/// MNN guards the net with an internal lock so the interpreter is [Send] and [Sync] and can be
+/// shared between threads (e.g. with an [Arc]) to run different sessions concurrently.
+/// ```rust,no_run
+/// let net = std::sync::Arc::new(mnn::Interpreter::from_file("model.mnn").unwrap());
+/// let handle = std::thread::spawn({
+///     let net = net.clone();
+///     move || {
+///         let session = net.create_session(mnn::ScheduleConfig::new()).unwrap();
+///         net.run_session(&session).unwrap();
+///     }
+/// });
+/// handle.join().unwrap();
+/// ```
+///
+/// # Cloning
+/// Cloning an interpreter returns another handle to the same loaded model, the model is freed
+/// when the last clone and the last session created from any of the clones are dropped.
+#[derive(Debug, Clone)]
 pub struct Interpreter {
     pub(crate) inner: *mut mnn_sys::Interpreter,
+    /// Owns `inner`, shared by all the clones of the interpreter
+    pub(crate) __shared: Arc<InterpreterShared>,
     pub(crate) __marker: PhantomData<()>,
 }
 
+/// The state of an [`Interpreter`] shared by all of its clones
+#[derive(Debug)]
+pub(crate) struct InterpreterShared {
+    inner: *mut mnn_sys::Interpreter,
+    /// Whether the model buffer was released with [`Interpreter::release_model`]
+    ///
+    /// Held for reading while the model buffer is used (creating sessions, reading the buffer)
+    /// and for writing to release or update it, since any clone can do that.
+    model_released: RwLock<bool>,
+    /// Whether a session was created, [`Interpreter::set_external_file`] has to be called before
+    session_created: AtomicBool,
+    /// The mapped model file of [`Interpreter::create_from_mmap`]
+    #[cfg(feature = "mmap")]
+    mmap: Option<memmap2::Mmap>,
+    /// The cache file of [`Interpreter::enable_auto_cache`]
+    auto_cache: Mutex<Option<AutoCache>>,
+    /// The modes set with [`Interpreter::set_session_mode`], at most one per pair
+    ///
+    /// MNN can't be asked for the current mode, so this is what a temporary mode is reset to.
+    /// The lock is held while creating sessions so no session picks up a temporary mode of
+    /// another thread.
+    session_modes: Mutex<Vec<SessionMode>>,
+}
+
+/// The cache file of [`Interpreter::enable_auto_cache`]
+#[derive(Debug)]
+struct AutoCache {
+    /// The file MNN reads and writes, private to this interpreter
+    scratch: PathBuf,
+    /// The shared cache file the scratch file is renamed to
+    path: PathBuf,
+}
+
+// SAFETY: The underlying MNN::Interpreter doesn't have any thread affinity
 unsafe impl Send for Interpreter {}
+// SAFETY: MNN::Interpreter locks the shared net internally when creating, resizing, running and
+// releasing sessions (and when loading / updating the cache file), so it can be used to drive
+// different sessions from multiple threads
+unsafe impl Sync for Interpreter {}
+// SAFETY: Same as above
+unsafe impl Send for InterpreterShared {}
+// SAFETY: Same as above
+unsafe impl Sync for InterpreterShared {}
 
-impl Drop for Interpreter {
+impl Drop for InterpreterShared {
     fn drop(&mut self) {
         unsafe { mnn_sys::Interpreter_destroy(self.inner) }
+        let auto_cache = self
+            .auto_cache
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(cache) = auto_cache.take() {
+            // Only left over if the cache was never updated
+            let _ = std::fs::remove_file(cache.scratch);
+        }
+    }
+}
+
+impl Interpreter {
+    /// Take ownership of a newly created MNN::Interpreter
+    fn from_owned_ptr(inner: *mut mnn_sys::Interpreter) -> Self {
+        Self {
+            inner,
+            __shared: Arc::new(InterpreterShared {
+                inner,
+                model_released: RwLock::new(false),
+                session_created: AtomicBool::new(false),
+                #[cfg(feature = "mmap")]
+                mmap: None,
+                auto_cache: Mutex::new(None),
+                session_modes: Mutex::new(Vec::new()),
+            }),
+            __marker: PhantomData,
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for Interpreter {
+    type Error = MNNError;
+
+    /// Same as [`Interpreter::from_bytes`]
+    fn try_from(bytes: &[u8]) -> Result<Self> {
+        Self::from_bytes(bytes)
     }
 }
 
@@ -178,28 +374,130 @@ impl Interpreter {
         let path = path.to_str().ok_or_else(|| error!(ErrorKind::AsciiError))?;
         let c_path = std::ffi::CString::new(path).change_context(ErrorKind::AsciiError)?;
         let interpreter = unsafe { mnn_sys::Interpreter_createFromFile(c_path.as_ptr()) };
-        ensure!(!interpreter.is_null(), ErrorKind::InterpreterError; "Failed to create interpreter", "Interpreter_createFromFile returned null");
-        Ok(Self {
-            inner: interpreter,
-            __marker: PhantomData,
-        })
+        if interpreter.is_null() {
+            // Explain the failure if the model is too new
+            if let Ok(bytes) = std::fs::read(path) {
+                ensure_schema_version(&bytes)?;
+            }
+        }
+        if interpreter.is_null() {
+            return Err(MNNError::from_mnn(ErrorKind::ModelLoad)
+                .attach_printable("Failed to create interpreter")
+                .attach_printable("Interpreter_createFromFile returned null"));
+        }
+        Ok(Self::from_owned_ptr(interpreter))
     }
 
     /// Create an net/interpreter from a buffer.
     ///
-    /// `bytes`: the buffer of the model
+    /// `bytes`: the buffer of the model, anything that derefs to bytes (`Vec<u8>`, `&[u8]`,
+    /// `Box<[u8]>`, `bytes::Bytes`, ...)
     ///
     /// return: the created net/interpreter
+    ///
+    /// MNN copies the model into its own storage so the buffer doesn't need to outlive the
+    /// interpreter.
     pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Result<Self> {
         let bytes = bytes.as_ref();
-        let size = bytes.len();
-        let interpreter =
-            unsafe { mnn_sys::Interpreter_createFromBuffer(bytes.as_ptr().cast(), size) };
-        ensure!(!interpreter.is_null(), ErrorKind::InterpreterError; "Failed to create interpreter", "Interpreter_createFromBuffer returned null");
-        Ok(Self {
-            inner: interpreter,
-            __marker: PhantomData,
-        })
+        // SAFETY: the pointer and length come from a valid slice
+        unsafe { Self::from_raw_parts(bytes.as_ptr(), bytes.len()) }
+    }
+
+    /// Create an net/interpreter from a model read from `reader` (e.g. a streamed download)
+    ///
+    /// `limit`: the maximum size of the model in bytes, reading stops with an error once the
+    /// reader returns more than that (use it for untrusted sources)
+    ///
+    /// return: the created net/interpreter
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let file = std::fs::File::open("model.mnn").unwrap();
+    /// let interpreter = Interpreter::create_from_reader(file, Some(512 * 1024 * 1024)).unwrap();
+    /// ```
+    pub fn create_from_reader<R: std::io::Read>(
+        mut reader: R,
+        limit: Option<usize>,
+    ) -> Result<Self> {
+        let mut bytes = Vec::new();
+        match limit {
+            Some(limit) => {
+                // Read one byte past the limit to know if the model is too large
+                reader
+                    .take((limit as u64).saturating_add(1))
+                    .read_to_end(&mut bytes)
+                    .change_context(ErrorKind::IOError)?;
+                ensure!(
+                    bytes.len() <= limit,
+                    ErrorKind::IOError;
+                    format!("The model is larger than the limit of {limit} bytes")
+                );
+            }
+            None => {
+                reader
+                    .read_to_end(&mut bytes)
+                    .change_context(ErrorKind::IOError)?;
+            }
+        }
+        Self::from_bytes(bytes)
+    }
+
+    /// Create an net/interpreter from a raw buffer.
+    ///
+    /// This is meant for buffers that don't come from rust, e.g. a model that javascript wrote
+    /// into the wasm memory on `wasm32-unknown-emscripten` (see `examples/wasm.rs`).
+    /// MNN copies the model into its own storage so the buffer can be freed once this returns.
+    ///
+    /// Returns an [`ErrorKind::SchemaVersion`] if MNN can't load a model that was converted by a
+    /// newer MNN than the linked one, which MNN itself only reports as a failed load.
+    ///
+    /// # Safety
+    /// `ptr` must be valid for reads of `len` bytes for the duration of the call
+    pub unsafe fn from_raw_parts(ptr: *const u8, len: usize) -> Result<Self> {
+        ensure!(!ptr.is_null() && len > 0, ErrorKind::ModelLoad; "Failed to create interpreter", "The model buffer is empty");
+        let interpreter = unsafe { mnn_sys::Interpreter_createFromBuffer(ptr.cast(), len) };
+        if interpreter.is_null() {
+            // Explain the failure if the model is too new
+            ensure_schema_version(unsafe { core::slice::from_raw_parts(ptr, len) })?;
+        }
+        if interpreter.is_null() {
+            return Err(MNNError::from_mnn(ErrorKind::ModelLoad)
+                .attach_printable("Failed to create interpreter")
+                .attach_printable("Interpreter_createFromBuffer returned null"));
+        }
+        Ok(Self::from_owned_ptr(interpreter))
+    }
+
+    /// Create an net/interpreter from a memory mapped model file (requires the `mmap` feature).
+    ///
+    /// The file is mapped instead of read into a `Vec` so loading a large model only needs
+    /// memory for the copy MNN keeps, the mapped pages are backed by the page cache.
+    /// The mapping lives as long as the interpreter (and is dropped after it is destroyed).
+    ///
+    /// The file must not be modified or truncated while the interpreter is created from it.
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let interpreter = Interpreter::create_from_mmap("model.mnn".as_ref()).unwrap();
+    /// let session = interpreter.create_session(ScheduleConfig::new()).unwrap();
+    /// ```
+    #[cfg(feature = "mmap")]
+    pub fn create_from_mmap(path: &Path) -> Result<Self> {
+        ensure!(path.exists(), ErrorKind::IOError; path.to_string_lossy().to_string(), "File not found");
+        let file = std::fs::File::open(path)
+            .change_context(ErrorKind::IOError)
+            .attach_printable_lazy(|| path.to_string_lossy().to_string())?;
+        // SAFETY: The mapping is only read while creating the interpreter, see the note above
+        // about modifying the file
+        let mmap = unsafe { memmap2::Mmap::map(&file) }
+            .change_context(ErrorKind::IOError)
+            .attach_printable("Failed to map the model file")?;
+        // SAFETY: the mapping is valid for reads of its whole length
+        let mut interpreter = unsafe { Self::from_raw_parts(mmap.as_ptr(), mmap.len())? };
+        Arc::get_mut(&mut interpreter.__shared)
+            .expect("The interpreter was just created")
+            .mmap = Some(mmap);
+        Ok(interpreter)
     }
 
     /// Set session mode
@@ -208,8 +506,25 @@ impl Interpreter {
     ///
     /// **Warning:**
     /// It should be called before create session!
+    ///
+    /// See [`SessionMode`] for the ownership rules of the `InputUser` / `OutputUser` modes
     pub fn set_session_mode(&mut self, mode: SessionMode) {
+        let mut modes = self.session_modes();
         unsafe { mnn_sys::Interpreter_setSessionMode(self.inner, mode as mnn_sys::SessionMode::Type) }
+        modes.retain(|set| set.default_of_pair() != mode.default_of_pair());
+        modes.push(mode);
+    }
+
+    /// Set a session hint
+    ///
+    /// `hint`: the hint to set
+    ///
+    /// `value`: the value of the hint
+    ///
+    /// **Warning:**
+    /// It should be called before create session!
+    pub fn set_session_hint(&mut self, hint: SessionHint, value: i32) {
+        unsafe { mnn_sys::Interpreter_setSessionHint(self.inner, hint.to_mnn_sys(), value) }
     }
 
     ///call this function to get tensors ready.
@@ -218,6 +533,7 @@ impl Interpreter {
     ///
     ///`session`: the session to be prepared
     pub fn resize_session(&self, session: &mut crate::Session) {
+        let _guard = session.__runtime.as_ref().map(crate::Runtime::lock);
         unsafe { mnn_sys::Interpreter_resizeSession(self.inner, session.inner) }
     }
 
@@ -228,10 +544,16 @@ impl Interpreter {
     /// # Note
     /// NeedRelloc is default to 1, 1 means need realloc!
     pub fn resize_session_reallocate(&self, session: &mut crate::Session) {
+        let _guard = session.__runtime.as_ref().map(crate::Runtime::lock);
         unsafe { mnn_sys::Interpreter_resizeSessionWithFlag(self.inner, session.inner, 1i32) }
     }
 
     /// Resize the tensor using the given shape
+    ///
+    /// The session the tensor belongs to needs to be resized with [`Interpreter::resize_session`]
+    /// before it is run again. The order is checked when the session is run rather than in the
+    /// types, [`Interpreter::run_session`] fails with [`ErrorKind::DynamicTensorError`] until the
+    /// session is resized.
     pub fn resize_tensor<T: TensorType>(&self, tensor: &mut Tensor<T>, dims: impl AsTensorShape) {
         let dims = dims.as_tensor_shape();
         let dims_len = dims.size;
@@ -270,38 +592,355 @@ impl Interpreter {
         }
     }
 
+    /// Resize the 4D input `name` of the session to `[batch, channel, height, width]`
+    ///
+    /// The dims are reordered for inputs in another layout (e.g. NHWC models). MNN flags the
+    /// session as needing a resize, running it before [`Interpreter::resize_session`] fails.
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// let net = Interpreter::from_file("tests/assets/realesr.mnn").unwrap();
+    /// let mut session = net.create_session(ScheduleConfig::new()).unwrap();
+    /// net.resize_input_nchw(&session, "data", 1, 3, 64, 64).unwrap();
+    /// net.resize_session(&mut session);
+    /// let input = net.input::<f32>(&session, "data").unwrap();
+    /// assert_eq!(input.shape().dims(), [1, 3, 64, 64]);
+    /// ```
+    pub fn resize_input_nchw(
+        &self,
+        session: &crate::Session,
+        name: impl AsRef<str>,
+        batch: i32,
+        channel: i32,
+        height: i32,
+        width: i32,
+    ) -> Result<()> {
+        let name = name.as_ref();
+        let input = self.raw_input(session, name)?;
+        let shape = input.shape();
+        ensure!(
+            shape.as_ref().len() == 4,
+            ErrorKind::TensorError;
+            format!("Input \"{name}\" with shape {shape:?} isn't 4D")
+        );
+        let dims = [batch, channel, height, width];
+        ensure!(
+            dims.iter().all(|&dim| dim > 0),
+            ErrorKind::TensorError;
+            format!("Invalid shape {dims:?} for input \"{name}\"")
+        );
+        let _guard = session.__runtime.as_ref().map(crate::Runtime::lock);
+        unsafe {
+            mnn_sys::Interpreter_resizeTensorByNCHW(
+                self.inner,
+                input.inner,
+                batch,
+                channel,
+                height,
+                width,
+            )
+        }
+        Ok(())
+    }
+
     /// Create a session with session config. Session will be managed in net/interpreter.
     ///
     /// `schedule` : the config of the session
     ///
     /// return: the created session
+    ///
+    /// If the requested backend can't be created MNN falls back to the backup type of the config
+    /// ([`ForwardType::CPU`] by default), an [`ErrorKind::UnsupportedBackend`] is only returned if
+    /// the session ends up on another backend. Set the backup type to the requested type to fail
+    /// instead of falling back, or use [`Interpreter::create_session_with_fallback`] to try a list
+    /// of backends.
     pub fn create_session(
-        &mut self,
+        &self,
         schedule: crate::ScheduleConfig,
+    ) -> Result<crate::session::Session> {
+        self.create_session_with_modes(schedule, &[])
+    }
+
+    /// [`Interpreter::create_session`] with the temporary session `modes`
+    fn create_session_with_modes(
+        &self,
+        mut schedule: crate::ScheduleConfig,
+        modes: &[SessionMode],
     ) -> Result<crate::session::Session> {
         // Force print to stdout/stderr for debugging crash
         println!("DEBUG[mnn-rs]: Interpreter::create_session called.");
+        let _model = self.ensure_model()?;
+        schedule.cap_threads();
         profile!("Creating session"; {
             println!("DEBUG[mnn-rs]: Calling unsafe mnn_sys::Interpreter_createSession...");
-            let session = unsafe { mnn_sys::Interpreter_createSession(self.inner, schedule.inner) };
+            // Only the messages logged while creating this session explain a failure
+            mnn_sys::take_error_logs();
+            let session = self.with_backend_mode(schedule.backend_auto, modes, || unsafe {
+                mnn_sys::Interpreter_createSession(self.inner, schedule.inner)
+            });
             println!("DEBUG[mnn-rs]: mnn_sys::Interpreter_createSession returned. Checking null...");
-            assert!(!session.is_null());
+            if session.is_null() {
+                return Err(session_error("Interpreter_createSession", schedule.get_type()));
+            }
+            self.__shared.session_created.store(true, Ordering::Relaxed);
             println!("DEBUG[mnn-rs]: Session is not null. Wrapping in crate::session::Session...");
+            let requested = schedule.get_type();
+            let backup = schedule.get_backup_type();
+            let session = crate::session::Session {
+                inner: session,
+                net: self.inner,
+                __interpreter: self.__shared.clone(),
+                __session_internals: crate::SessionInternals::Single(schedule),
+                __runtime: None,
+                __bound_inputs: Default::default(),
+                #[cfg(feature = "image")]
+                __preprocess: None,
+                __marker: PhantomData,
+            };
+            // MNN silently falls back to the backup type if the requested backend is not available
+            let needs_check =
+                !matches!(requested, ForwardType::Auto | ForwardType::All | ForwardType::CPU);
+            if let Some(backends) = session.backends().filter(|_| needs_check) {
+                if !backends.contains(&requested) && !backends.contains(&backup) {
+                    return Err(MNNError::from_mnn(ErrorKind::UnsupportedBackend(requested))
+                        .attach_printable(format!(
+                            "Session was created with {backends:?} backends instead"
+                        )));
+                }
+                #[cfg(feature = "tracing")]
+                if !backends.contains(&requested) {
+                    tracing::warn!("{requested:?} is not available, falling back to {backup:?}");
+                }
+            }
+            Ok(session)
+        })
+    }
+
+    /// Create a session on the first of `backends` that can be created on this device
+    ///
+    /// Each backend is tried without falling back to the backup type of the config, the failed
+    /// attempts are reported to the log handler (see [`set_log_handler`](crate::set_log_handler)).
+    ///
+    /// return: the created session and the backend it runs on
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let interpreter = Interpreter::from_file("model.mnn").unwrap();
+    /// let (session, backend) = interpreter
+    ///     .create_session_with_fallback(ScheduleConfig::new(), &[ForwardType::Auto, ForwardType::CPU])
+    ///     .unwrap();
+    /// println!("Running on {backend:?}");
+    /// ```
+    pub fn create_session_with_fallback(
+        &self,
+        schedule: crate::ScheduleConfig,
+        backends: &[ForwardType],
+    ) -> Result<(crate::session::Session, ForwardType)> {
+        let mut failures = Vec::new();
+        for &backend in backends {
+            let config = schedule
+                .clone()
+                .with_type(backend)
+                .with_backup_type(backend);
+            match self.create_session(config) {
+                Ok(session) => return Ok((session, backend)),
+                Err(e) => {
+                    mnn_sys::emit(
+                        mnn_sys::Level::Error,
+                        file!(),
+                        line!() as usize,
+                        &format!("Failed to create a session with {backend:?}: {}", e.kind()),
+                    );
+                    failures.push(format!("{backend:?}: {}", e.kind()));
+                }
+            }
+        }
+        let message = format!(
+            "Failed to create a session with any of {backends:?} ({})",
+            failures.join(", ")
+        );
+        Err(error!(ErrorKind::InterpreterError).attach_printable(message))
+    }
+
+    /// Create a session with a [`Runtime`](crate::Runtime) shared with other sessions / interpreters
+    ///
+    /// `schedule` : the config of the session
+    ///
+    /// `runtime` : the runtime whose backends are used by the session
+    ///
+    /// return: the created session
+    pub fn create_session_with_runtime(
+        &self,
+        mut schedule: crate::ScheduleConfig,
+        runtime: &crate::Runtime,
+    ) -> Result<crate::session::Session> {
+        let _model = self.ensure_model()?;
+        schedule.cap_threads();
+        profile!("Creating session with runtime"; {
+            // The session allocates from the backends shared with the other sessions
+            let _guard = runtime.lock();
+            let modes = runtime.modes();
+            mnn_sys::take_error_logs();
+            let session = self.with_backend_mode(schedule.backend_auto, &modes, || unsafe {
+                mnn_sys::Interpreter_createSessionWithRuntime(
+                    self.inner,
+                    schedule.inner,
+                    runtime.inner.inner,
+                )
+            });
+            if session.is_null() {
+                return Err(session_error("Interpreter_createSessionWithRuntime", schedule.get_type()));
+            }
+            self.__shared.session_created.store(true, Ordering::Relaxed);
             Ok(crate::session::Session {
                 inner: session,
                 net: self.inner,
+                __interpreter: self.__shared.clone(),
                 __session_internals: crate::SessionInternals::Single(schedule),
+                __runtime: Some(runtime.clone()),
+                __bound_inputs: Default::default(),
+                #[cfg(feature = "image")]
+                __preprocess: None,
                 __marker: PhantomData,
             })
         })
     }
 
-    /// Release the model file buffer
-    /// # Safety
-    /// This function is marked unsafe since it's not clear what the safety guarantees are right
-    /// now. With a simple test it caused a segfault so it's marked unsafe
-    pub unsafe fn release_model(&mut self) {
+    /// Create `n` independent sessions of the model with the same config, e.g. to run it on
+    /// several threads at once without loading it several times
+    ///
+    /// The sessions share this interpreter (the model) but each has its own backends, so they
+    /// can run concurrently: move each one to its own thread and run it with a shared reference
+    /// to the interpreter. Sessions created with a shared [`Runtime`](crate::Runtime) run one at
+    /// a time instead.
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let net = Interpreter::from_file("model.mnn").unwrap();
+    /// let sessions = net.sessions_for(2, ScheduleConfig::new()).unwrap();
+    /// std::thread::scope(|scope| {
+    ///     for session in sessions {
+    ///         let net = &net;
+    ///         scope.spawn(move || net.run_session(&session).unwrap());
+    ///     }
+    /// });
+    /// ```
+    pub fn sessions_for(
+        &self,
+        n: usize,
+        schedule: crate::ScheduleConfig,
+    ) -> Result<Vec<crate::session::Session>> {
+        (0..n)
+            .map(|_| self.create_session(schedule.clone()))
+            .collect()
+    }
+
+    /// Release the model buffer to save memory once all the sessions are created
+    ///
+    /// The sessions created before keep working (they can still be resized and run), but no new
+    /// sessions can be created from this interpreter (or any of its clones) afterwards, the
+    /// `create_*session*` functions return an [`ErrorKind::InterpreterError`] instead. It waits
+    /// for the clones that are creating sessions or reading the model buffer meanwhile.
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let mut interpreter = Interpreter::from_file("model.mnn").unwrap();
+    /// let session = interpreter.create_session(ScheduleConfig::new()).unwrap();
+    /// interpreter.release_model();
+    /// interpreter.run_session(&session).unwrap();
+    /// assert!(interpreter.create_session(ScheduleConfig::new()).is_err());
+    /// ```
+    pub fn release_model(&mut self) {
+        let mut released = self
+            .__shared
+            .model_released
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
         unsafe { mnn_sys::Interpreter_releaseModel(self.inner) }
+        *released = true;
+    }
+
+    /// Create a session with `create`, with [`SessionMode::BackendAuto`] if `auto` is set
+    /// ([`ScheduleConfig::set_backend_auto`](crate::ScheduleConfig::set_backend_auto)) and the
+    /// temporary `modes`
+    fn with_backend_mode<R>(
+        &self,
+        auto: bool,
+        modes: &[SessionMode],
+        create: impl FnOnce() -> R,
+    ) -> R {
+        let auto = auto.then_some(SessionMode::BackendAuto);
+        self.with_session_modes(modes.iter().copied().chain(auto), create)
+    }
+
+    /// Run `f` with the temporary session `modes`, serialized with the creation of the other
+    /// sessions of the interpreter (and its clones)
+    ///
+    /// Each mode is set back to the one set with [`Interpreter::set_session_mode`] afterwards, or
+    /// to the default of MNN if none was set.
+    fn with_session_modes<R>(
+        &self,
+        modes: impl IntoIterator<Item = SessionMode> + Clone,
+        f: impl FnOnce() -> R,
+    ) -> R {
+        let set = self.session_modes();
+        for mode in modes.clone() {
+            unsafe { mnn_sys::Interpreter_setSessionMode(self.inner, mode.into()) };
+        }
+        let result = f();
+        for mode in modes {
+            let pair = mode.default_of_pair();
+            let reset = set
+                .iter()
+                .copied()
+                .find(|set| set.default_of_pair() == pair)
+                .unwrap_or(pair);
+            unsafe { mnn_sys::Interpreter_setSessionMode(self.inner, reset.into()) };
+        }
+        result
+    }
+
+    /// Lock the modes set with [`Interpreter::set_session_mode`]
+    fn session_modes(&self) -> std::sync::MutexGuard<'_, Vec<SessionMode>> {
+        self.__shared
+            .session_modes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Check that the model buffer is still there to create sessions from, it can't be released
+    /// while the returned guard is held
+    fn ensure_model(&self) -> Result<RwLockReadGuard<'_, bool>> {
+        let released = self.model_released();
+        ensure!(
+            !*released,
+            ErrorKind::InterpreterError;
+            "The model was released with release_model, no new sessions can be created"
+        );
+        Ok(released)
+    }
+
+    /// Lock the model buffer for reading, the guard is `true` if it was released
+    fn model_released(&self) -> RwLockReadGuard<'_, bool> {
+        self.__shared
+            .model_released
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Run `f` with the model buffer (empty after [`Interpreter::release_model`]), which can't
+    /// be released or updated meanwhile
+    fn with_model_buffer<R>(&self, f: impl FnOnce(&[u8]) -> R) -> R {
+        let released = self.model_released();
+        if *released {
+            return f(&[]);
+        }
+        let mut size = 0;
+        let data = unsafe { mnn_sys::Interpreter_getModelBuffer(self.inner, &mut size) };
+        if data.is_null() || size == 0 {
+            return f(&[]);
+        }
+        f(unsafe { core::slice::from_raw_parts(data.cast(), size) })
     }
 
     /// Create multi-path session with schedule configs and user-specified runtime. created session will be managed in net/interpreter.
@@ -310,18 +949,33 @@ impl Interpreter {
     ///
     /// return: the created session
     pub fn create_multipath_session(
-        &mut self,
+        &self,
         schedule: impl IntoIterator<Item = ScheduleConfig>,
     ) -> Result<crate::session::Session> {
+        let _model = self.ensure_model()?;
         profile!("Creating multipath session"; {
-            let schedules: crate::ScheduleConfigs = schedule.into_iter().collect();
+            let schedules: crate::ScheduleConfigs = schedule
+                .into_iter()
+                .map(|mut config| {
+                    config.cap_threads();
+                    config
+                })
+                .collect();
             let sc: &[_] = schedules.inner.as_ref();
-            let session = unsafe { mnn_sys::Interpreter_createMultiPathSession(self.inner, sc.as_ptr(), sc.len()) };
-            assert!(!session.is_null());
+            let session = self.with_session_modes([], || unsafe {
+                mnn_sys::Interpreter_createMultiPathSession(self.inner, sc.as_ptr(), sc.len())
+            });
+            ensure!(!session.is_null(), ErrorKind::NullResult { op: "Interpreter_createMultiPathSession" });
+            self.__shared.session_created.store(true, Ordering::Relaxed);
             Ok(crate::session::Session {
                 inner: session,
                 net: self.inner,
+                __interpreter: self.__shared.clone(),
                 __session_internals: crate::SessionInternals::MultiSession(schedules),
+                __runtime: None,
+                __bound_inputs: Default::default(),
+                #[cfg(feature = "image")]
+                __preprocess: None,
                 __marker: PhantomData,
             })
         })
@@ -480,14 +1134,12 @@ impl Interpreter {
     }
 
     /// Run a session
-    pub fn run_session(&mut self, session: &crate::session::Session) -> Result<()> {
+    ///
+    /// If any input tensor was resized with [`Interpreter::resize_tensor`] the session must be
+    /// resized with [`Interpreter::resize_session`] before it can be run again.
+    pub fn run_session(&self, session: &crate::session::Session) -> Result<()> {
         profile!("Running session"; {
-            let ret = unsafe { mnn_sys::Interpreter_runSession(self.inner, session.inner) };
-            ensure!(
-                ret == mnn_sys::ErrorCode::ERROR_CODE_NO_ERROR,
-                ErrorKind::InternalError(ret)
-            );
-            Ok(())
+            run_session_raw(self.inner, session)
         })
     }
 
@@ -501,29 +1153,95 @@ impl Interpreter {
     ///
     /// `sync` : synchronously wait for finish of execution or not.
     pub fn run_session_with_callback(
-        &mut self,
+        &self,
         session: &crate::session::Session,
         before: impl Fn(&[RawTensor], OperatorInfo) -> bool + 'static,
         end: impl Fn(&[RawTensor], OperatorInfo) -> bool + 'static,
         sync: bool,
     ) -> Result<()> {
-        let sync = sync as libc::c_int;
-        let before = TensorCallback::from(before).into_ptr();
-        let end = TensorCallback::from(end).into_ptr();
-        let ret = unsafe {
-            mnn_sys::Interpreter_runSessionWithCallBackInfo(
-                self.inner,
-                session.inner,
-                before,
-                end,
-                sync,
-            )
+        run_session_with_callback_raw(self.inner, session, before, end, sync)
+    }
+
+    /// Run a session like [`Interpreter::run_session_with_callback`], `after` also gets the names
+    /// the model gives to the output tensors of the op
+    ///
+    /// `names[i]` is the name of `tensors[i]`. The names are read from the model buffer so it
+    /// can't have been released with [`Interpreter::release_model`]. Ops MNN adds to the graph
+    /// itself don't have names, `names` is shorter than `tensors` (or empty) for them.
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// let net = Interpreter::from_file("tests/assets/realesr.mnn").unwrap();
+    /// let session = net.create_session(ScheduleConfig::new()).unwrap();
+    /// net.run_session_with_named_callback(
+    ///     &session,
+    ///     |_, _| true,
+    ///     |tensors, names, op| {
+    ///         for (tensor, name) in tensors.iter().zip(names) {
+    ///             println!("{:?} {name}: {:?}", op.name(), tensor.shape());
+    ///         }
+    ///         true
+    ///     },
+    ///     true,
+    /// )
+    /// .unwrap();
+    /// ```
+    pub fn run_session_with_named_callback(
+        &self,
+        session: &crate::session::Session,
+        before: impl Fn(&[RawTensor], OperatorInfo) -> bool + 'static,
+        after: impl Fn(&[RawTensor], &[&str], OperatorInfo) -> bool + 'static,
+        sync: bool,
+    ) -> Result<()> {
+        let op_outputs = self.op_output_names()?;
+        let end = move |tensors: &[RawTensor], op: OperatorInfo| {
+            let names: Vec<&str> = op
+                .name()
+                .to_str()
+                .ok()
+                .and_then(|op| op_outputs.get(op))
+                .map(|names| names.iter().map(String::as_str).collect())
+                .unwrap_or_default();
+            after(tensors, &names, op)
         };
-        ensure!(
-            ret == mnn_sys::ErrorCode::ERROR_CODE_NO_ERROR,
-            ErrorKind::InternalError(ret)
-        );
-        Ok(())
+        run_session_with_callback_raw(self.inner, session, before, end, sync)
+    }
+
+    /// The names of the output tensors of each op of the model
+    fn op_output_names(&self) -> Result<HashMap<String, Vec<String>>> {
+        /// # Safety
+        /// `context` has to be the map below and the names valid for the call
+        unsafe extern "C" fn collect(
+            context: *mut c_void,
+            op: *const c_char,
+            tensor: *const c_char,
+        ) {
+            let names = unsafe { &mut *context.cast::<HashMap<String, Vec<String>>>() };
+            let op = unsafe { CStr::from_ptr(op) }.to_string_lossy().into_owned();
+            let tensor = unsafe { CStr::from_ptr(tensor) }
+                .to_string_lossy()
+                .into_owned();
+            names.entry(op).or_default().push(tensor);
+        }
+
+        let mut names = HashMap::new();
+        let valid = self.with_model_buffer(|model| {
+            ensure!(
+                !model.is_empty(),
+                ErrorKind::InterpreterError;
+                "The model was released with release_model, its tensor names aren't available anymore"
+            );
+            Ok(unsafe {
+                mnn_sys::Net_forEachOpOutput(
+                    model.as_ptr().cast(),
+                    model.len(),
+                    Some(collect),
+                    (&mut names as *mut HashMap<String, Vec<String>>).cast(),
+                )
+            })
+        })?;
+        ensure!(valid != 0, ErrorKind::InterpreterError; "The model buffer isn't a valid model");
+        Ok(names)
     }
 
     /// Get all output tensors of a session
@@ -544,7 +1262,23 @@ impl Interpreter {
     /// The API should be called before create session.
     ///
     /// Key Depercerate, keeping for future use!
-    pub fn set_cache_file(&mut self, path: impl AsRef<Path>, key_size: usize) -> Result<()> {
+    ///
+    /// The cache stores the compiled / tuned kernels of the gpu backends (OpenCL, Vulkan, ...) so
+    /// it is specific to the backend and the device (and driver) it was created on.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let net = Interpreter::from_file("model.mnn").unwrap();
+    /// // Loads the cache if it exists
+    /// net.set_cache_file("model.cache", 128).unwrap();
+    /// let mut config = ScheduleConfig::new();
+    /// config.set_type(ForwardType::Auto);
+    /// let session = net.create_session(config).unwrap();
+    /// // Writes the cache if it was missing or outdated
+    /// net.update_cache_file(&session).unwrap();
+    /// ```
+    pub fn set_cache_file(&self, path: impl AsRef<Path>, key_size: usize) -> Result<()> {
         let path = path.as_ref();
         let path = dunce::simplified(path);
         let path = path.to_str().ok_or_else(|| error!(ErrorKind::AsciiError))?;
@@ -553,14 +1287,179 @@ impl Interpreter {
         Ok(())
     }
 
+    /// Use a cache file in `dir` named after a hash of the model, the backend and the MNN
+    /// version, returns the path of the cache file
+    ///
+    /// A changed model gets a new cache file instead of loading a stale cache. The device isn't
+    /// part of the name, use a different `dir` per gpu if there is more than one.
+    ///
+    /// MNN reads and writes a copy of the cache private to this interpreter, which
+    /// [`Interpreter::update_cache_file`] renames to the returned path. So processes sharing
+    /// `dir` never see a partially written cache, the last one to update it wins.
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let net = Interpreter::from_file("model.mnn").unwrap();
+    /// net.enable_auto_cache("cache", ForwardType::OpenCL).unwrap();
+    /// let config = ScheduleConfig::new().with_type(ForwardType::OpenCL);
+    /// let session = net.create_session(config).unwrap();
+    /// net.update_cache_file(&session).unwrap();
+    /// ```
+    pub fn enable_auto_cache(
+        &self,
+        dir: impl AsRef<Path>,
+        forward: ForwardType,
+    ) -> Result<PathBuf> {
+        /// Distinguishes the scratch files of the interpreters of one process
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+
+        let hash = self.with_model_buffer(|model| {
+            ensure!(
+                !model.is_empty(),
+                ErrorKind::InterpreterError;
+                "The model was released with release_model, it can't be hashed"
+            );
+            Ok([
+                model,
+                crate::version().as_bytes(),
+                forward.name().as_bytes(),
+                env::consts::OS.as_bytes(),
+                env::consts::ARCH.as_bytes(),
+            ]
+            .iter()
+            .fold(FNV_OFFSET, |hash, part| fnv1a(fnv1a(hash, part), &[0])))
+        })?;
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)
+            .change_context(ErrorKind::IOError)
+            .attach_printable(dir.to_string_lossy().to_string())?;
+        let name = format!("{}-{hash:016x}.mnncache", forward.name().to_lowercase());
+        let path = dir.join(&name);
+        let scratch = dir.join(format!(
+            "{name}.{}-{}.tmp",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        if path.exists() {
+            std::fs::copy(&path, &scratch)
+                .change_context(ErrorKind::IOError)
+                .attach_printable(path.to_string_lossy().to_string())?;
+        }
+        // 128 is the key size recommended by MNN::Interpreter::setCacheFile
+        self.set_cache_file(&scratch, 128)?;
+        let previous = self
+            .__shared
+            .auto_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .replace(AutoCache {
+                scratch,
+                path: path.clone(),
+            });
+        if let Some(previous) = previous {
+            let _ = std::fs::remove_file(previous.scratch);
+        }
+        Ok(path)
+    }
+
+    /// Set the file with the external weights of the model (e.g. `model.mnn.weight` for models
+    /// converted with `--saveExternalData`)
+    ///
+    /// **Warning:**
+    /// It has to be called before any session is created from this interpreter, the weights are
+    /// loaded while creating sessions. Returns an [`ErrorKind::InterpreterError`] otherwise.
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let net = Interpreter::from_file("model.mnn").unwrap();
+    /// net.set_external_file("model.mnn.weight").unwrap();
+    /// let session = net.create_session(ScheduleConfig::new()).unwrap();
+    /// ```
+    pub fn set_external_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        ensure!(
+            !self.__shared.session_created.load(Ordering::Relaxed),
+            ErrorKind::InterpreterError;
+            "set_external_file has to be called before creating sessions"
+        );
+        let path = path.as_ref();
+        ensure!(path.exists(), ErrorKind::IOError; path.to_string_lossy().to_string(), "File not found");
+        let path = dunce::simplified(path);
+        let path = path.to_str().ok_or_else(|| error!(ErrorKind::AsciiError))?;
+        let c_path = std::ffi::CString::new(path).change_context(ErrorKind::AsciiError)?;
+        // 128 is the default flag of MNN::Interpreter::setExternalFile
+        unsafe { mnn_sys::Interpreter_setExternalFile(self.inner, c_path.as_ptr(), 128) }
+        Ok(())
+    }
+
     /// Update cache file
-    pub fn update_cache_file(&mut self, session: &mut crate::session::Session) -> Result<()> {
-        MNNError::from_error_code(unsafe {
-            mnn_sys::Interpreter_updateCacheFile(self.inner, session.inner)
-        });
+    ///
+    /// Saves the cache set with [`Interpreter::set_cache_file`] after the session was created
+    /// (and resized), this is a no-op if the cache loaded from the file is still valid.
+    ///
+    /// With [`Interpreter::enable_auto_cache`] the written cache is then moved into place.
+    pub fn update_cache_file(&self, session: &crate::session::Session) -> Result<()> {
+        let auto_cache = self
+            .__shared
+            .auto_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let ret = unsafe { mnn_sys::Interpreter_updateCacheFile(self.inner, session.inner) };
+        ensure!(
+            ret == mnn_sys::ErrorCode::ERROR_CODE_NO_ERROR,
+            ErrorKind::InternalError(ret)
+        );
+        if let Some(cache) = auto_cache.as_ref().filter(|cache| cache.scratch.exists()) {
+            std::fs::rename(&cache.scratch, &cache.path)
+                .change_context(ErrorKind::IOError)
+                .attach_printable(cache.path.to_string_lossy().to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Write the weights of a session back into the model buffer
+    ///
+    /// This only makes sense for trainable sessions (models with trainable parameters that were
+    /// updated on device), the weights of other models are left as they are. The updated model
+    /// can then be saved from [`Interpreter::model_buffer`].
+    ///
+    /// Returns an error after [`Interpreter::release_model`].
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let mut interpreter = Interpreter::from_file("model.mnn").unwrap();
+    /// let session = interpreter.create_session(ScheduleConfig::new()).unwrap();
+    /// // ... train the session
+    /// interpreter.update_session_to_model(&session).unwrap();
+    /// std::fs::write("updated.mnn", interpreter.model_buffer()).unwrap();
+    /// ```
+    pub fn update_session_to_model(&mut self, session: &crate::session::Session) -> Result<()> {
+        let released = self
+            .__shared
+            .model_released
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        ensure!(
+            !*released,
+            ErrorKind::InterpreterError;
+            "The model was released with release_model, it can't be updated"
+        );
+        let ret = unsafe { mnn_sys::Interpreter_updateSessionToModel(self.inner, session.inner) };
+        ensure!(
+            ret == mnn_sys::ErrorCode::ERROR_CODE_NO_ERROR,
+            ErrorKind::InternalError(ret)
+        );
         Ok(())
     }
 
+    /// Get a copy of the serialized model held by the interpreter
+    ///
+    /// This includes the weights written with [`Interpreter::update_session_to_model`] and is
+    /// empty after [`Interpreter::release_model`]. It's a copy since a clone of the interpreter
+    /// can release or update the model at any time.
+    pub fn model_buffer(&self) -> Vec<u8> {
+        self.with_model_buffer(<[u8]>::to_vec)
+    }
+
     /// Wait for all output tensors to be ready after computation
     pub fn wait(&self, session: &crate::session::Session) {
         self.outputs(session).iter().for_each(|tinfo| {
@@ -582,11 +1481,10 @@ impl Interpreter {
                 memory_ptr.cast(),
             )
         };
-        ensure!(
-            ret == 1,
-            ErrorKind::InterpreterError;
-            "Failed to get memory usage"
-        );
+        if ret != 1 {
+            return Err(MNNError::from_mnn(ErrorKind::InterpreterError)
+                .attach_printable("Failed to get memory usage"));
+        }
         Ok(memory)
     }
 
@@ -602,40 +1500,314 @@ impl Interpreter {
                 flop_ptr.cast::<libc::c_void>(),
             )
         };
-        ensure!(
-            ret == 1,
-            ErrorKind::InterpreterError;
-            "Failed to get flops"
-        );
+        if ret != 1 {
+            return Err(MNNError::from_mnn(ErrorKind::InterpreterError)
+                .attach_printable("Failed to get flops"));
+        }
         Ok(flop)
     }
 
     /// Get the resize status
     pub fn resize_status(&self, session: &crate::Session) -> Result<ResizeStatus> {
-        let mut resize_status = 0i32;
-        let ptr = &mut resize_status as *mut i32;
-        let ret = unsafe {
-            mnn_sys::Interpreter_getSessionInfo(
-                self.inner,
-                session.inner,
-                mnn_sys::cpp::MNN_Interpreter_SessionInfoCode_RESIZE_STATUS as _,
-                ptr.cast(),
-            )
-        };
+        resize_status_raw(self.inner, session)
+    }
+
+    /// Compute the output shapes of the model for the given input shapes without running it
+    ///
+    /// A temporary cpu session is created with [`SessionMode::ResizeDefer`] so the default input
+    /// shapes are never allocated, resized to `inputs` with [`Session::resize`](crate::Session::resize)
+    /// and dropped. MNN allocates the buffers of the session while computing the shapes, but none
+    /// of the operators are executed. The resize mode of the interpreter is left unchanged.
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// # use std::collections::HashMap;
+    /// let interpreter = Interpreter::from_file("model.mnn").unwrap();
+    /// let shapes = interpreter
+    ///     .infer_shapes(&HashMap::from([("input", vec![1, 3, 256, 256])]))
+    ///     .unwrap();
+    /// println!("{:?}", shapes["output"]);
+    /// ```
+    pub fn infer_shapes(
+        &self,
+        inputs: &std::collections::HashMap<&str, Vec<i32>>,
+    ) -> Result<std::collections::HashMap<String, Vec<i32>>> {
+        let mut session =
+            self.create_session_with_modes(ScheduleConfig::new(), &[SessionMode::ResizeDefer])?;
+        session.resize(inputs)?;
+        Ok(session
+            .outputs()
+            .into_iter()
+            .map(|(name, output)| (name, output.shape().to_vec()))
+            .collect())
+    }
+
+    /// Get the metadata (converter version, bizCode and uuid) of the model
+    ///
+    /// The metadata is read from the model buffer so this returns an error after
+    /// [`Interpreter::release_model`]
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let interpreter = Interpreter::from_file("model.mnn").unwrap();
+    /// let metadata = interpreter.metadata().unwrap();
+    /// println!("{} (converted by MNN {})", metadata.biz_code, metadata.version);
+    /// ```
+    pub fn metadata(&self) -> Result<ModelMetadata> {
+        let released = self.model_released();
         ensure!(
-        ret == 1,
+            !*released,
             ErrorKind::InterpreterError;
-            "Failed to get resize status"
+            "The model was released with release_model, its metadata isn't available anymore"
         );
-        match resize_status {
-            0 => Ok(ResizeStatus::None),
-            1 => Ok(ResizeStatus::NeedMalloc),
-            2 => Ok(ResizeStatus::NeedResize),
-            _ => Err(error!(ErrorKind::InterpreterError)),
+        let string = |ptr: *const libc::c_char| {
+            if ptr.is_null() {
+                String::new()
+            } else {
+                unsafe { CStr::from_ptr(ptr) }
+                    .to_string_lossy()
+                    .into_owned()
+            }
+        };
+        unsafe {
+            Ok(ModelMetadata {
+                version: string(mnn_sys::Interpreter_getModelVersion(self.inner)),
+                biz_code: string(mnn_sys::Interpreter_bizCode(self.inner)),
+                uuid: string(mnn_sys::Interpreter_uuid(self.inner)),
+            })
         }
     }
 }
 
+#[cfg(feature = "rayon")]
+impl Interpreter {
+    /// Load many models and create a session for each of them in parallel (requires the `rayon`
+    /// feature)
+    ///
+    /// The results are in the order of `paths`, a model that fails to load doesn't stop the
+    /// others and its error names the path.
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let models = Interpreter::load_many(&["a.mnn", "b.mnn"], &ScheduleConfig::new());
+    /// for model in models {
+    ///     match model {
+    ///         Ok((interpreter, session)) => interpreter.run_session(&session).unwrap(),
+    ///         Err(e) => eprintln!("{e:?}"),
+    ///     }
+    /// }
+    /// ```
+    pub fn load_many<P: AsRef<Path> + Sync>(
+        paths: &[P],
+        config: &crate::ScheduleConfig,
+    ) -> Vec<Result<(Self, crate::session::Session)>> {
+        Self::load_many_inner(paths, config, None)
+    }
+
+    /// Same as [`Interpreter::load_many`] with the sessions sharing a [`Runtime`](crate::Runtime)
+    ///
+    /// The models are still loaded in parallel but the sessions are created one at a time since
+    /// they allocate from the shared backends.
+    pub fn load_many_with_runtime<P: AsRef<Path> + Sync>(
+        paths: &[P],
+        config: &crate::ScheduleConfig,
+        runtime: &crate::Runtime,
+    ) -> Vec<Result<(Self, crate::session::Session)>> {
+        Self::load_many_inner(paths, config, Some(runtime))
+    }
+
+    fn load_many_inner<P: AsRef<Path> + Sync>(
+        paths: &[P],
+        config: &crate::ScheduleConfig,
+        runtime: Option<&crate::Runtime>,
+    ) -> Vec<Result<(Self, crate::session::Session)>> {
+        use rayon::prelude::*;
+        // ScheduleConfig isn't Sync so every model gets its own copy
+        let configs = paths.iter().map(|_| config.clone()).collect::<Vec<_>>();
+        paths
+            .par_iter()
+            .zip(configs)
+            .map(|(path, config)| {
+                let path = path.as_ref();
+                let load = || -> Result<_> {
+                    let interpreter = Self::from_file(path)?;
+                    let session = match runtime {
+                        Some(runtime) => {
+                            interpreter.create_session_with_runtime(config, runtime)?
+                        }
+                        None => interpreter.create_session(config)?,
+                    };
+                    Ok((interpreter, session))
+                };
+                load().map_err(|e| e.attach_printable(format!("Model {}", path.display())))
+            })
+            .collect()
+    }
+}
+
+/// The error for a session MNN couldn't create
+///
+/// MNN only logs the operators no backend could create, if one was logged while creating the
+/// session this is an [`ErrorKind::UnsupportedOp`] instead of a [`ErrorKind::NullResult`].
+fn session_error(op: &'static str, backend: ForwardType) -> crate::MNNError {
+    let logs = mnn_sys::take_error_logs();
+    let kind = match logs.iter().find_map(|log| unsupported_op_type(log)) {
+        Some(op_type) => ErrorKind::UnsupportedOp {
+            op_type: op_type.to_string(),
+            backend,
+        },
+        None => ErrorKind::NullResult { op },
+    };
+    let unsupported = matches!(kind, ErrorKind::UnsupportedOp { .. });
+    let mut error = logs
+        .into_iter()
+        .fold(error!(kind), |error, log| error.attach_printable(log));
+    if unsupported && backend != ForwardType::CPU {
+        error = error.attach_printable(
+            "Use ForwardType::CPU or set it as the backup type with ScheduleConfig::set_backup_type",
+        );
+    }
+    error
+}
+
+/// The operator type of the message MNN logs when a backend can't create an operator, e.g.
+/// `Don't support type [Convolution3D], conv` (cpu) or
+/// `Don't support type Convolution3D memObject:0, conv` (opencl)
+fn unsupported_op_type(message: &str) -> Option<&str> {
+    let op_type = message
+        .strip_prefix("Don't support type")?
+        .trim_start()
+        .split([',', ' '])
+        .next()?
+        .trim_matches(['[', ']']);
+    (!op_type.is_empty()).then_some(op_type)
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Fail with [`ErrorKind::SchemaVersion`] if the model was converted by a newer MNN than the
+/// linked one, models that don't record their version are left to MNN
+fn ensure_schema_version(model: &[u8]) -> Result<()> {
+    let version = unsafe { mnn_sys::Net_version(model.as_ptr().cast(), model.len()) };
+    if version.is_null() {
+        return Ok(());
+    }
+    let model = unsafe { CStr::from_ptr(version) }
+        .to_string_lossy()
+        .into_owned();
+    let supported = crate::linked_version();
+    let newer = match (version_triple(&model), version_triple(supported)) {
+        (Some(model), Some(supported)) => model > supported,
+        _ => false,
+    };
+    ensure!(
+        !newer,
+        ErrorKind::SchemaVersion {
+            model,
+            supported: supported.to_string(),
+        };
+        "Convert the model with the linked MNN version or update mnn-sys"
+    );
+    Ok(())
+}
+
+/// `(major, minor, patch)` of a `major.minor.patch` version
+fn version_triple(version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = version.trim().splitn(3, '.').map(|part| {
+        let digits = part.len() - part.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        part[..digits].parse::<u32>().ok()
+    });
+    Some((
+        parts.next()??,
+        parts.next()??,
+        parts.next().flatten().unwrap_or(0),
+    ))
+}
+
+/// 64 bit FNV-1a, stable across builds and platforms unlike `std::hash`
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100_0000_01b3)
+    })
+}
+
+/// Get the resize status of a session on the given interpreter
+pub(crate) fn resize_status_raw(
+    net: *mut mnn_sys::Interpreter,
+    session: &crate::Session,
+) -> Result<ResizeStatus> {
+    let mut resize_status = 0i32;
+    let ptr = &mut resize_status as *mut i32;
+    let ret = unsafe {
+        mnn_sys::Interpreter_getSessionInfo(
+            net,
+            session.inner,
+            mnn_sys::cpp::MNN_Interpreter_SessionInfoCode_RESIZE_STATUS as _,
+            ptr.cast(),
+        )
+    };
+    if ret != 1 {
+        return Err(MNNError::from_mnn(ErrorKind::InterpreterError)
+            .attach_printable("Failed to get resize status"));
+    }
+    match resize_status {
+        0 => Ok(ResizeStatus::None),
+        1 => Ok(ResizeStatus::NeedMalloc),
+        2 => Ok(ResizeStatus::NeedResize),
+        _ => Err(error!(ErrorKind::InterpreterError)),
+    }
+}
+
+/// Run a session on the given interpreter
+///
+/// Shared by [`Interpreter::run_session`] and [`Session::run_async`](crate::Session::run_async)
+/// which only has the interpreter pointer of the session
+pub(crate) fn run_session_raw(
+    net: *mut mnn_sys::Interpreter,
+    session: &crate::Session,
+) -> Result<()> {
+    ensure!(
+        resize_status_raw(net, session)? != ResizeStatus::NeedResize,
+        ErrorKind::DynamicTensorError;
+        "Session needs to be resized with resize_session after resizing input tensors"
+    );
+    let _guard = session.__runtime.as_ref().map(crate::Runtime::lock);
+    let ret = unsafe { mnn_sys::Interpreter_runSession(net, session.inner) };
+    ensure!(
+        ret == mnn_sys::ErrorCode::ERROR_CODE_NO_ERROR,
+        ErrorKind::InternalError(ret)
+    );
+    Ok(())
+}
+
+/// Run a session with a callback on the given interpreter
+///
+/// Shared by [`Interpreter::run_session_with_callback`] and
+/// [`Session::profile`](crate::Session::profile)
+pub(crate) fn run_session_with_callback_raw(
+    net: *mut mnn_sys::Interpreter,
+    session: &crate::Session,
+    before: impl Fn(&[RawTensor], OperatorInfo) -> bool + 'static,
+    end: impl Fn(&[RawTensor], OperatorInfo) -> bool + 'static,
+    sync: bool,
+) -> Result<()> {
+    let sync = sync as libc::c_int;
+    let before = TensorCallback::from(before).into_ptr();
+    let end = TensorCallback::from(end).into_ptr();
+    let _guard = session.__runtime.as_ref().map(crate::Runtime::lock);
+    let ret = unsafe {
+        mnn_sys::Interpreter_runSessionWithCallBackInfo(net, session.inner, before, end, sync)
+    };
+    // The callbacks are only called during the run
+    drop(TensorCallback::from_ptr(before));
+    drop(TensorCallback::from_ptr(end));
+    ensure!(
+        ret == mnn_sys::ErrorCode::ERROR_CODE_NO_ERROR,
+        ErrorKind::InternalError(ret)
+    );
+    Ok(())
+}
+
 /// The status of the resize operation
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[repr(C)]
@@ -701,13 +1873,36 @@ impl OperatorInfo<'_> {
     }
 }
 
+#[test]
+fn test_version_triple() {
+    assert_eq!(version_triple("2.9.6"), Some((2, 9, 6)));
+    assert_eq!(version_triple("3.0"), Some((3, 0, 0)));
+    assert_eq!(version_triple("3.1.0-rc1"), Some((3, 1, 0)));
+    assert_eq!(version_triple("<2.0.0"), None);
+    assert!(version_triple("3.0.0") > version_triple("2.10.1"));
+}
+
+#[test]
+fn test_unsupported_op_type() {
+    assert_eq!(
+        unsupported_op_type("Don't support type [Convolution3D], conv3d"),
+        Some("Convolution3D")
+    );
+    assert_eq!(
+        unsupported_op_type("Don't support type GridSample memObject:0, grid"),
+        Some("GridSample")
+    );
+    assert_eq!(unsupported_op_type("Don't support type [], op"), None);
+    assert_eq!(unsupported_op_type("Invalide Session!!"), None);
+}
+
 #[test]
 #[ignore = "This test doesn't work in CI"]
 fn test_run_session_with_callback_info_api() {
     let file = Path::new("tests/assets/realesr.mnn")
         .canonicalize()
         .unwrap();
-    let mut interpreter = Interpreter::from_file(&file).unwrap();
+    let interpreter = Interpreter::from_file(&file).unwrap();
     let session = interpreter.create_session(ScheduleConfig::new()).unwrap();
     interpreter
         .run_session_with_callback(
@@ -725,7 +1920,7 @@ fn check_whether_sync_actually_works() {
     let file = Path::new("tests/assets/realesr.mnn")
         .canonicalize()
         .unwrap();
-    let mut interpreter = Interpreter::from_file(&file).unwrap();
+    let interpreter = Interpreter::from_file(&file).unwrap();
     let session = interpreter.create_session(ScheduleConfig::new()).unwrap();
     let time = std::time::Instant::now();
     interpreter
@@ -756,8 +1951,45 @@ fn try_to_drop_interpreter_before_session() {
     let file = Path::new("tests/assets/realesr.mnn")
         .canonicalize()
         .unwrap();
-    let mut interpreter = Interpreter::from_file(&file).unwrap();
+    let interpreter = Interpreter::from_file(&file).unwrap();
     let session = interpreter.create_session(ScheduleConfig::new()).unwrap();
     drop(interpreter);
     drop(session);
 }
+
+#[test]
+fn interpreter_is_send_sync() {
+    fn is_send_sync<T: Send + Sync>() {}
+    fn is_send<T: Send>() {}
+    is_send_sync::<Interpreter>();
+    is_send_sync::<Arc<Interpreter>>();
+    is_send::<crate::Session>();
+}
+
+#[test]
+fn test_temporary_session_modes_are_restored() {
+    let mut interpreter = Interpreter::from_file("tests/assets/realesr.mnn").unwrap();
+    interpreter.set_session_mode(SessionMode::InputUser);
+    interpreter.set_session_mode(SessionMode::BackendAuto);
+    interpreter.set_session_mode(SessionMode::InputInside);
+    let modes = || interpreter.session_modes().clone();
+    let expected = [SessionMode::BackendAuto, SessionMode::InputInside];
+    assert_eq!(modes(), expected);
+
+    let clone = interpreter.clone();
+    std::thread::scope(|scope| {
+        for backend_auto in [true, false, true, false] {
+            let clone = &clone;
+            scope.spawn(move || {
+                let config = ScheduleConfig::new().with_backend_auto(backend_auto);
+                clone.create_session(config).unwrap();
+            });
+        }
+    });
+    // The temporary modes don't replace the ones set by the user
+    assert_eq!(modes(), expected);
+    assert_eq!(
+        SessionMode::ResizeDefer.default_of_pair(),
+        SessionMode::ResizeDirect
+    );
+}
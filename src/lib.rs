@@ -3,15 +3,15 @@
 //! Ergonomic rust bindings for [MNN](https://github.com/alibaba/MNN)  
 //!
 //! The main data structures used are [`Tensor`] and [`Interpreter`].   
-//! [Interpreter] should be thread safe and can be used to run multiple sessions concurrently.  
-//! [Send] / [Sync] is not implemented for Interpreter yet since we don't know how it will be used.  
+//! [Interpreter] is thread safe ([Send] + [Sync]) and can be used to run multiple sessions concurrently.  
+//! [Session] is only [Send] since the same session must not be run concurrently.  
 //!
 //! ![Codecov](https://img.shields.io/codecov/c/github/aftershootco/mnn-rs?link=https%3A%2F%2Fapp.codecov.io%2Fgithub%2Faftershootco%2Fmnn-rs)
 //! ![GitHub Actions Workflow Status](https://img.shields.io/github/actions/workflow/status/aftershootco/mnn-rs/build.yaml?link=https%3A%2F%2Fgithub.com%2Faftershootco%2Fmnn-rs%2Factions%2Fworkflows%2Fbuild.yaml)
 //! # Example  
 //! ```rust,no_run
 //! use mnn::*;
-//! let mut interpreter = Interpreter::from_bytes([0;100]).unwrap();
+//! let interpreter = Interpreter::from_bytes([0;100]).unwrap();
 //! let mut sc = ScheduleConfig::new();
 //! let session = interpreter.create_session(sc).unwrap();
 //! let mut input = interpreter.input::<f32>(&session, "input").unwrap();
@@ -26,17 +26,37 @@
 //! **NOTE:**  The library is still in development and the API is subject to change.   
 //!
 //! ## Features
-//! - `metal`: Enable mnn Metal backend
-//! - `coreml`: Enable mnn CoreML backend
-//! - `vulkan`: Enable mnn Vulkan backend (unimplemented from rust wrapper)
+//! - `metal`: Enable mnn Metal backend (only on apple targets)
+//! - `coreml`: Enable mnn CoreML backend (only on apple targets)
+//! - `vulkan`: Enable mnn Vulkan backend (pick the device with `BackendConfig::set_vulkan_device`)
 //! - `opencl`: Enable mnn OpenCL backend
-//! - `opengl`: Enable mnn OpenGL backend (unimplemented from rust wrapper)
-//! - `openmp`: Enable mnn Openmp ( disable the mnn-threadpool feature to enable this)
+//! - `opengl`: Enable mnn OpenGL backend (links the OpenGL ES 3 / EGL libraries on linux and android)
+//! - `nnapi`: Enable mnn NNAPI backend (only on android)
+//! - `system-loaders`: Link the system OpenCL / Vulkan loaders instead of loading them at runtime
+//! - `openmp`: Enable mnn Openmp ( disable the mnn-threadpool feature to enable this), see `set_omp_threads`
 //! - `mnn-threadpool`: Enable mnn threadpool ( enabled by default can't be used with openmp)
 //! - `sync`: Enable sync api
 //! - `profile`: Enable profiling ( emits some profiling tracing events )
-//! - `tracing`: Enable tracing ( emits some tracing events )
+//! - `tracing`: Enable tracing ( emits some tracing events and forwards MNN logs to tracing, enabled by default )
+//! - `log`: Forward MNN logs to the `log` crate
+//! - `image`: Create tensors and set session inputs from images of the `image` crate
+//! - `bytemuck`: Cast the host memory of tensors with `Tensor::cast_slice`
+//! - `half`: Use `half::f16` / `half::bf16` for float16 / bfloat16 tensors
+//! - `mmap`: Load models from memory mapped files with `Interpreter::create_from_mmap`
+//! - `rayon`: Load many models in parallel with `Interpreter::load_many`
+//! - `bench`: Time warmup + repeated runs of a session with `Benchmark`
+//! - `portable-cpu`: Build MNN without its SSE / AVX kernels for x86 cpus without SSE (automatic for targets without SSE2)
+//! - `low-memory`: Build MNN with `MNN_LOW_MEMORY` so `MemoryMode::Low` keeps quantized weights quantized and dequantizes them on the fly
+//! - `converter`: Build the MNN converter and convert ONNX / TensorFlow / TFLite / TorchScript models with `convert_model` (considerably longer build)
+//! - `asan`: Build the C shims and MNN with AddressSanitizer and link its runtime (debug builds with gcc / clang on linux, macos and android only), run with e.g. `ASAN_OPTIONS=detect_leaks=1:verify_asan_link_order=0` since the runtime isn't the first library loaded by rust executables
+//! - `mini`: Size optimized build of MNN for binary size sensitive targets (e.g. wasm). `MNN_BUILD_MINI` drops the shape inference and geometry computation so only fixed shape models work (convert them with `--saveStaticModel`, resizing inputs fails), `MNN_REDUCE_SIZE` drops the uncommon ops and the size-costly optimized cpu kernels and `MNN_SUPPORT_DEPRECATED_OP` is turned off which drops the old quantized tflite ops. The backends are unaffected. MNN is built as `MinSizeRel` with one section per function and the build prints the size of `libMNN.a`, can't be used with `converter`
+//! - `arm82`: Build the ARMv8.2 fp16 kernels of MNN (`MNN_ARM82`, enabled by default, noop on targets other than aarch64), `PrecisionMode::Low` sessions on the cpu compute in fp16 on cpus that support it
+//! - `bf16`: Build the bf16 kernels of MNN (`MNN_SUPPORT_BF16`), `PrecisionMode::LowBf16` sessions on the cpu compute in bf16
+//! - `tokio`: Run sessions on the blocking thread pool of tokio with `Session::run_async`
 //! - `crt_static`: Link statically to the C runtime on windows (noop on other platforms)
+//!
+//! The build script of mnn-sys fails early, listing every conflict, when the features can't work for
+//! the target (e.g. `metal` outside apple targets or `openmp` with `mnn-threadpool`).
 //! ## License
 //! This links to the MNN library which is licensed under the Apache License 2.0.  
 //! The rust bindings are licensed under the same Apache License 2.0.  
@@ -45,6 +65,37 @@
 //! The flake.nix provides a nix-shell with all the dependencies required to build the library.  
 //! If not using nix you'll need to clone the git submodule to get the MNN source code in mnn-sys/vendor first  
 //! Or you can export the MNN_SRC environment variable to point to the MNN source code.  
+//! The source is copied to the out dir and patched, the copy is only refreshed when the source
+//! changes. Set `MNN_NO_VENDOR_COPY=1` to build from the source in place instead, the patches in
+//! mnn-sys/patches have to be applied to it already.  
+//!
+//! To skip compiling MNN set `MNN_COMPILE=0` and `MNN_LIB_DIR` to a directory with a prebuilt static
+//! MNN library (and optionally `MNN_INCLUDE_DIR` to its headers). The prebuilt MNN has to be the
+//! same version as the vendored one, the build fails early otherwise.  
+//! Without `MNN_LIB_DIR` an installed MNN is looked up with pkg-config (`MNN.pc` / `mnn.pc`, its
+//! `libdir` has to contain the static library), a version different from the vendored one only
+//! gives a warning since distro packages may lag behind.  
+//!
+//! Extra MNN cmake options can be passed with `MNN_CMAKE_ARGS` (`-DKEY=VALUE` defines separated by
+//! spaces or `;`, e.g. `MNN_CMAKE_ARGS="-DMNN_LOW_MEMORY=ON -DMNN_CPU_WEIGHT_DEQUANT_GEMM=ON"`) and
+//! the C++ standard MNN is built with can be changed from 14 with `MNN_CXX_STANDARD`.  
+//! The cmake generator can be picked with `MNN_CMAKE_GENERATOR` (e.g. `MNN_CMAKE_GENERATOR=Ninja`
+//! for faster builds), a Ninja generator falls back to the default one if `ninja` isn't installed.  
+//!
+//! For android the NDK is found with `ANDROID_NDK_HOME` (or `ANDROID_NDK_ROOT`), the abi is derived
+//! from the target and the platform defaults to android-21 (override with `ANDROID_ABI` /
+//! `ANDROID_PLATFORM`). [cargo-ndk](https://github.com/bbqsrc/cargo-ndk) sets all of them
+//! ```sh
+//! cargo ndk -t arm64-v8a -P 24 build --release
+//! ```
+//!
+//! The `opengl` feature needs the OpenGL ES 3 and EGL development packages on linux (e.g.
+//! `libgles-dev` and `libegl-dev` on debian / ubuntu), set `GL_INCLUDE_DIR` / `GL_LIB_DIR` if
+//! they aren't installed in the default locations.  
+//!
+//! On `wasm32-unknown-emscripten` emscripten only links its filesystem if a file api is used, load
+//! models with [`Interpreter::from_bytes`] / [`Interpreter::from_raw_parts`] instead of
+//! [`Interpreter::from_file`] to keep it out (see `examples/wasm.rs`).  
 //!
 //! ## Compatibility Chart for current crate
 //! | MNN Backend | Compiles | Works |
@@ -68,29 +119,100 @@ pub mod ffi {
 mod profile;
 
 pub mod backend;
+/// The options MNN was built with
+pub mod capabilities;
+/// Model conversion
+#[cfg(feature = "converter")]
+pub mod converter;
 /// Error handling
 pub mod error;
 /// MNN::Interpreter related items
 pub mod interpreter;
+/// Shared runtime for sessions
+pub mod runtime;
 /// Schedule configuration
 pub mod schedule;
+/// Benchmark helpers
+#[cfg(feature = "bench")]
+pub mod bench;
+/// MNN::Express::Module related items
+pub mod module;
 /// MNN::Session related items
 pub mod session;
 /// MNN::Tensor related items
 pub mod tensor;
 
 pub use backend::*;
+#[cfg(feature = "bench")]
+pub use bench::*;
+pub use capabilities::*;
+#[cfg(feature = "converter")]
+pub use converter::*;
 pub use error::*;
 pub use interpreter::*;
+pub use module::*;
+pub use runtime::*;
 pub use schedule::*;
 pub use session::*;
 pub use tensor::*;
 
 pub use ffi::HalideType;
+pub use ffi::{Level as LogLevel, LogRecord, clear_log_handler, set_log_handler};
+pub use ffi::MNNGpuMode;
 pub use ffi::MapType;
 
-/// Re-export of commonly used items
+/// The MNN version (`major.minor.patch`) the crate was built against
+///
+/// Read from `MNNDefine.h` at build time, include it in bug reports.
+///
+/// ```rust
+/// assert_eq!(mnn::version().split('.').count(), 3);
+/// ```
+pub fn version() -> &'static str {
+    ffi::MNN_VERSION
+}
+
+/// The version reported by the linked MNN library (`MNN::getVersion`)
+///
+/// This is the same as [`version`] unless a different prebuilt MNN was linked in.
+pub fn linked_version() -> &'static str {
+    ffi::mnn_version()
+}
+
+/// The types and traits needed by most programs
+///
+/// ```rust
+/// use mnn::prelude::*;
+///
+/// let interpreter = Interpreter::from_file("tests/assets/realesr.mnn")?;
+/// let config = ScheduleConfig::new()
+///     .with_type(ForwardType::CPU)
+///     .with_backend_config(BackendConfig::new().with_precision_mode(PrecisionMode::Normal));
+/// let session = interpreter.create_session(config)?;
+/// let mut input: Tensor<RefMut<Device<f32>>> = interpreter.input(&session, "data")?;
+/// let mut host: Tensor<Host<f32>> = input.create_host_tensor_from_device(false);
+/// host.fill(0.5);
+/// input.copy_from_host_tensor(&host)?;
+/// interpreter.run_session(&session)?;
+/// # Ok::<(), MNNError>(())
+/// ```
 pub mod prelude {
+    pub use crate::{
+        AsTensorShape, BackendConfig, Device, DeviceTensorType, DimensionType, ErrorKind,
+        ForwardType, HalideType, Host, HostTensorType, Interpreter, MNNError, MutableTensorType,
+        PrecisionMode, Ref, RefMut, ScheduleConfig, Session, SessionDevice, SessionMode, Tensor,
+        TensorType,
+    };
+    // The re-exports of the previous prelude, kept so existing imports keep compiling
+    pub use crate::error::*;
+    pub use core::marker::PhantomData;
+    pub use error_stack::{Report, ResultExt};
+    pub use libc::*;
+    pub use mnn_sys::MapType;
+}
+
+/// The items used by most modules of the crate
+mod internal_prelude {
     pub use crate::error::*;
     pub(crate) use crate::profile::profile;
     pub use core::marker::PhantomData;
@@ -0,0 +1,213 @@
+//! The module module provides the [`Module`] and [`VarP`] types wrapping the MNN Express api
+use std::{ffi::CString, path::Path};
+
+use crate::{
+    AsTensorShape, DataType, DimensionType, ScheduleConfig, TensorShape, internal_prelude::*,
+};
+use mnn_sys::halide_type_of;
+
+/// A module loaded with MNN Express
+///
+/// Unlike the [`Interpreter`](crate::Interpreter) / [`Session`](crate::Session) api the module
+/// supports models with control flow.
+///
+/// # Example
+/// ```rust,no_run
+/// use mnn::*;
+/// let module = Module::load(&["data"], &["output"], "model.mnn", &ScheduleConfig::new()).unwrap();
+/// let input = VarP::new([1, 3, 224, 224], &[0.0; 3 * 224 * 224], DimensionType::NCHW).unwrap();
+/// let outputs = module.forward(&[input]).unwrap();
+/// let output = outputs[0].host().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Module {
+    pub(crate) inner: *mut mnn_sys::Module,
+    pub(crate) __marker: PhantomData<()>,
+}
+
+impl Drop for Module {
+    fn drop(&mut self) {
+        unsafe { mnn_sys::Module_destroy(self.inner) }
+    }
+}
+
+impl Module {
+    /// Load a module from a file
+    ///
+    /// `inputs`: the names of the input variables (all model inputs if empty)
+    ///
+    /// `outputs`: the names of the output variables (all model outputs if empty)
+    ///
+    /// `path`: the file path of the model
+    ///
+    /// `config`: the config used to create the runtime of the module
+    pub fn load(
+        inputs: &[&str],
+        outputs: &[&str],
+        path: impl AsRef<Path>,
+        config: &ScheduleConfig,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        ensure!(path.exists(), ErrorKind::IOError; path.to_string_lossy().to_string(), "File not found");
+        let path = path.to_str().ok_or_else(|| error!(ErrorKind::AsciiError))?;
+        let c_path = CString::new(path).change_context(ErrorKind::AsciiError)?;
+        let c_inputs = inputs
+            .iter()
+            .map(|s| CString::new(*s).map_err(|e| error!(ErrorKind::AsciiError, e)))
+            .collect::<Result<Vec<_>>>()?;
+        let c_outputs = outputs
+            .iter()
+            .map(|s| CString::new(*s).map_err(|e| error!(ErrorKind::AsciiError, e)))
+            .collect::<Result<Vec<_>>>()?;
+        let c_inputs = c_inputs.iter().map(|s| s.as_ptr()).collect::<Vec<_>>();
+        let c_outputs = c_outputs.iter().map(|s| s.as_ptr()).collect::<Vec<_>>();
+        let module = unsafe {
+            mnn_sys::Module_load(
+                c_inputs.as_ptr(),
+                c_inputs.len(),
+                c_outputs.as_ptr(),
+                c_outputs.len(),
+                c_path.as_ptr(),
+                config.inner,
+            )
+        };
+        if module.is_null() {
+            return Err(MNNError::from_mnn(ErrorKind::ModelLoad)
+                .attach_printable("Failed to load module")
+                .attach_printable("Module_load returned null"));
+        }
+        Ok(Self {
+            inner: module,
+            __marker: PhantomData,
+        })
+    }
+
+    /// Run the module with the given inputs and return the outputs
+    pub fn forward(&self, inputs: &[VarP]) -> Result<Vec<VarP>> {
+        profile!("Running module"; {
+            let inputs = inputs.iter().map(|v| v.inner).collect::<Vec<_>>();
+            let outputs =
+                unsafe { mnn_sys::Module_forward(self.inner, inputs.as_ptr(), inputs.len()) };
+            ensure!(!outputs.is_null(), ErrorKind::NullResult { op: "Module_forward" });
+            let vars = unsafe {
+                core::slice::from_raw_parts((*outputs).vars, (*outputs).size)
+                    .iter()
+                    .map(|&inner| VarP { inner })
+                    .collect::<Vec<_>>()
+            };
+            unsafe { mnn_sys::destroyVARPArray(outputs) };
+            // MNN returns no outputs if the forward failed
+            let expected = unsafe { mnn_sys::Module_outputCount(self.inner) };
+            if vars.is_empty() || (expected != 0 && vars.len() != expected) {
+                return Err(MNNError::from_mnn(ErrorKind::InterpreterError)
+                    .attach_printable("Module forward failed")
+                    .attach_printable(format!("Expected {expected} outputs, got {}", vars.len())));
+            }
+            if !vars.iter().all(|v| !v.inner.is_null() && v.element_size() > 0) {
+                return Err(MNNError::from_mnn(ErrorKind::InterpreterError)
+                    .attach_printable("Module forward failed"));
+            }
+            Ok(vars)
+        })
+    }
+}
+
+/// A variable (MNN::Express::VARP) used as the input / output of a [`Module`]
+///
+/// Only `f32` variables are supported right now
+#[derive(Debug)]
+pub struct VarP {
+    pub(crate) inner: *mut mnn_sys::VARP,
+}
+
+impl Drop for VarP {
+    fn drop(&mut self) {
+        unsafe { mnn_sys::VARP_destroy(self.inner) }
+    }
+}
+
+impl VarP {
+    /// Create a new input variable with the given shape and copy the data into it
+    ///
+    /// The data is copied as is, so [`DimensionType::NC4HW4`] isn't supported since its memory
+    /// is packed differently from the logical shape.
+    pub fn new(
+        shape: impl AsTensorShape,
+        data: impl AsRef<[f32]>,
+        dm_type: DimensionType,
+    ) -> Result<Self> {
+        ensure!(
+            dm_type != DimensionType::NC4HW4,
+            ErrorKind::TensorError;
+            "NC4HW4 variables can't be created from data"
+        );
+        let shape = shape.as_tensor_shape();
+        let data = data.as_ref();
+        let expected = shape.iter().product::<i32>() as usize;
+        ensure!(
+            data.len() == expected,
+            ErrorKind::SizeMismatch {
+                expected,
+                got: data.len(),
+            }
+        );
+        let inner = unsafe {
+            mnn_sys::VARP_createWith(
+                shape.shape.as_ptr(),
+                shape.size,
+                halide_type_of::<f32>(),
+                data.as_ptr().cast(),
+                dm_type.to_mnn_sys(),
+            )
+        };
+        ensure!(
+            !inner.is_null(),
+            ErrorKind::NullResult {
+                op: "VARP_createWith"
+            }
+        );
+        Ok(Self { inner })
+    }
+
+    /// Get the shape of the variable
+    ///
+    /// Fails for variables with more than 4 dimensions, use [`VarP::dims`] for those.
+    pub fn shape(&self) -> Result<TensorShape> {
+        let dims = self.dims();
+        ensure!(
+            dims.len() <= 4,
+            ErrorKind::TensorError;
+            format!("The variable has {} dimensions, at most 4 fit in a TensorShape", dims.len())
+        );
+        Ok(dims.as_tensor_shape())
+    }
+
+    /// Get the dimensions of the variable (of any rank)
+    pub fn dims(&self) -> Vec<i32> {
+        let rank = unsafe { mnn_sys::VARP_dims(self.inner, core::ptr::null_mut(), 0) };
+        let mut dims = vec![0; rank];
+        unsafe { mnn_sys::VARP_dims(self.inner, dims.as_mut_ptr(), dims.len()) };
+        dims
+    }
+
+    /// Get the size of the variable when counted by elements
+    pub fn element_size(&self) -> usize {
+        unsafe { mnn_sys::VARP_elementSize(self.inner) }
+    }
+
+    /// Get the data of the variable (this computes the variable if needed)
+    pub fn host(&self) -> Result<&[f32]> {
+        let ty = unsafe { mnn_sys::VARP_getType(self.inner) };
+        let f32_ty = halide_type_of::<f32>();
+        ensure!(
+            ty.code == f32_ty.code && ty.bits == f32_ty.bits && ty.lanes == f32_ty.lanes,
+            ErrorKind::HalideTypeMismatch {
+                got: DataType::from(ty).name(),
+            };
+            format!("Expected a f32 variable, got {}", DataType::from(ty))
+        );
+        let data = unsafe { mnn_sys::VARP_readMap(self.inner) };
+        ensure!(!data.is_null(), ErrorKind::TensorError; "Failed to read variable");
+        Ok(unsafe { core::slice::from_raw_parts(data.cast(), self.element_size()) })
+    }
+}
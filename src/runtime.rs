@@ -0,0 +1,218 @@
+//! The runtime module provides the [`Runtime`] struct which can be shared between interpreters
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{ForwardType, ScheduleConfig, ScheduleConfigs, SessionMode, internal_prelude::*};
+
+/// A set of backends (thread pools, gpu contexts, ...) that can be shared between sessions of
+/// different interpreters, which saves memory when loading many small models on the same backend.
+///
+/// Created with [`Runtime::new`] and used with [`Interpreter::create_session_with_runtime`](crate::Interpreter::create_session_with_runtime).
+///
+/// The cache of the backends is shared too, see [`Runtime::set_cache`], and the sessions created
+/// with the runtime use the modes set with [`Runtime::set_mode`].
+///
+/// # Thread safety
+/// MNN doesn't allow using the backends of a runtime concurrently, so the sessions created with
+/// the same runtime are created, run and released one at a time.
+///
+/// # Example
+/// ```rust,no_run
+/// # use mnn::*;
+/// let runtime = Runtime::new([ScheduleConfig::new()]).unwrap();
+/// let a = Interpreter::from_file("a.mnn").unwrap();
+/// let b = Interpreter::from_file("b.mnn").unwrap();
+/// let session_a = a.create_session_with_runtime(ScheduleConfig::new(), &runtime).unwrap();
+/// let session_b = b.create_session_with_runtime(ScheduleConfig::new(), &runtime).unwrap();
+/// a.run_session(&session_a).unwrap();
+/// b.run_session(&session_b).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Runtime {
+    pub(crate) inner: Arc<RuntimeInner>,
+}
+
+#[derive(Debug)]
+pub(crate) struct RuntimeInner {
+    pub(crate) inner: *mut mnn_sys::RuntimeInfo,
+    /// Serializes the creation, runs and release of the sessions sharing this runtime
+    lock: Mutex<()>,
+    /// The modes set with [`Runtime::set_mode`], at most one per pair
+    modes: Mutex<Vec<SessionMode>>,
+    /// The cache file set with [`Runtime::set_cache`]
+    cache: Mutex<Option<RuntimeCache>>,
+    /// The configs the runtime was created with
+    __configs: ScheduleConfigs,
+}
+
+// SAFETY: The runtime is only read when creating sessions and the runs of the sessions using it
+// are serialized with the lock
+unsafe impl Send for RuntimeInner {}
+// SAFETY: Same as above
+unsafe impl Sync for RuntimeInner {}
+
+/// The cache file of [`Runtime::set_cache`]
+#[derive(Debug)]
+struct RuntimeCache {
+    path: PathBuf,
+    /// The loaded cache, MNN keeps pointing into it
+    buffer: Vec<u8>,
+}
+
+impl Drop for RuntimeInner {
+    fn drop(&mut self) {
+        unsafe { mnn_sys::RuntimeInfo_destroy(self.inner) }
+    }
+}
+
+impl Runtime {
+    /// Create a runtime with the backends of the given schedule configs
+    pub fn new(configs: impl IntoIterator<Item = ScheduleConfig>) -> Result<Self> {
+        let configs: ScheduleConfigs = configs
+            .into_iter()
+            .map(|mut config| {
+                config.cap_threads();
+                config
+            })
+            .collect();
+        ensure!(!configs.inner.is_empty(), ErrorKind::InterpreterError; "Runtime needs at least one ScheduleConfig");
+        let inner =
+            unsafe { mnn_sys::Interpreter_createRuntime(configs.inner.as_ptr(), configs.inner.len()) };
+        ensure!(!inner.is_null(), ErrorKind::NullResult { op: "Interpreter_createRuntime" });
+        Ok(Self {
+            inner: Arc::new(RuntimeInner {
+                inner,
+                lock: Mutex::new(()),
+                modes: Mutex::new(Vec::new()),
+                cache: Mutex::new(None),
+                __configs: configs,
+            }),
+        })
+    }
+
+    /// Whether a backend of the given type was created for this runtime
+    pub(crate) fn has_backend(&self, forward_type: ForwardType) -> bool {
+        unsafe { mnn_sys::RuntimeInfo_hasBackend(self.inner.inner, forward_type.to_mnn_sys()) != 0 }
+    }
+
+    /// Set a session mode for the sessions created with this runtime afterwards
+    ///
+    /// The mode replaces the mode of the same pair set on the interpreter with
+    /// [`Interpreter::set_session_mode`](crate::Interpreter::set_session_mode) while creating
+    /// the session, the mode of the interpreter is left unchanged.
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let runtime = Runtime::new([ScheduleConfig::new()]).unwrap();
+    /// runtime.set_mode(SessionMode::MemoryCollect);
+    /// let net = Interpreter::from_file("model.mnn").unwrap();
+    /// let session = net.create_session_with_runtime(ScheduleConfig::new(), &runtime).unwrap();
+    /// ```
+    pub fn set_mode(&self, mode: SessionMode) {
+        let mut modes = self.modes();
+        modes.retain(|set| set.default_of_pair() != mode.default_of_pair());
+        modes.push(mode);
+    }
+
+    /// Load the cache file of the backends (e.g. the tuned OpenCL kernels) from `path`
+    ///
+    /// The file doesn't have to exist yet, [`Runtime::update_cache`] writes it once sessions
+    /// were created. Unlike [`Interpreter::set_cache_file`](crate::Interpreter::set_cache_file)
+    /// the cache is shared by the sessions of every interpreter using this runtime.
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let runtime = Runtime::new([ScheduleConfig::new().with_type(ForwardType::OpenCL)]).unwrap();
+    /// runtime.set_cache("opencl.mnncache").unwrap();
+    /// let net = Interpreter::from_file("model.mnn").unwrap();
+    /// let config = ScheduleConfig::new().with_type(ForwardType::OpenCL);
+    /// let session = net.create_session_with_runtime(config, &runtime).unwrap();
+    /// runtime.update_cache().unwrap();
+    /// ```
+    pub fn set_cache(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let buffer = match std::fs::read(path) {
+            Ok(buffer) => buffer,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                return Err(error!(ErrorKind::IOError, e)
+                    .attach_printable(path.to_string_lossy().to_string()));
+            }
+        };
+        let _guard = self.lock();
+        let mut cache = self.cache();
+        if !buffer.is_empty() {
+            let valid = unsafe {
+                mnn_sys::RuntimeInfo_setCache(
+                    self.inner.inner,
+                    buffer.as_ptr().cast(),
+                    buffer.len(),
+                )
+            };
+            if valid == 0 {
+                let message = format!("The cache file {} isn't valid", path.display());
+                return Err(MNNError::from_mnn(ErrorKind::InterpreterError)
+                    .attach_printable(message)
+                    .attach_printable("The cache doesn't match the backends of the runtime"));
+            }
+        }
+        // The previous buffer is only dropped once MNN points into a new one
+        let buffer = match cache.take() {
+            Some(previous) if buffer.is_empty() => previous.buffer,
+            _ => buffer,
+        };
+        *cache = Some(RuntimeCache {
+            path: path.to_path_buf(),
+            buffer,
+        });
+        Ok(())
+    }
+
+    /// Write the cache of the backends to the file set with [`Runtime::set_cache`]
+    ///
+    /// Nothing is written if the backends have no cache (e.g. the cpu backend).
+    pub fn update_cache(&self) -> Result<()> {
+        let _guard = self.lock();
+        let cache = self.cache();
+        let path = &cache
+            .as_ref()
+            .ok_or_else(|| {
+                error!(ErrorKind::InterpreterError)
+                    .attach_printable("No cache file was set with Runtime::set_cache")
+            })?
+            .path;
+        let mut buffer = core::ptr::null();
+        let size = unsafe { mnn_sys::RuntimeInfo_getCache(self.inner.inner, &mut buffer) };
+        if buffer.is_null() || size == 0 {
+            return Ok(());
+        }
+        let data = unsafe { core::slice::from_raw_parts(buffer.cast::<u8>(), size) };
+        std::fs::write(path, data)
+            .change_context(ErrorKind::IOError)
+            .attach_printable(path.to_string_lossy().to_string())
+    }
+
+    /// Lock the runtime for creating, running or releasing a session
+    pub(crate) fn lock(&self) -> MutexGuard<'_, ()> {
+        self.inner.lock.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The modes set with [`Runtime::set_mode`]
+    pub(crate) fn modes(&self) -> Vec<SessionMode> {
+        self.inner
+            .modes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Lock the cache file set with [`Runtime::set_cache`]
+    fn cache(&self) -> MutexGuard<'_, Option<RuntimeCache>> {
+        self.inner
+            .cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -1,7 +1,11 @@
 use mnn_sys::*;
-use std::{ffi::CString, mem::ManuallyDrop};
+use std::{
+    ffi::CString,
+    mem::ManuallyDrop,
+    sync::atomic::{AtomicI32, Ordering},
+};
 
-use crate::{BackendConfig, prelude::*};
+use crate::{BackendConfig, internal_prelude::*};
 
 /// Backend used for running the model
 ///
@@ -19,6 +23,7 @@ use crate::{BackendConfig, prelude::*};
 /// - `OpenGL`: Use the OpenGL backend for computation (requires the `opengl` feature).
 /// - `Vulkan`: Use the Vulkan backend for computation (requires the `vulkan` feature).
 /// - `CoreML`: Use the CoreML backend for computation (requires the `coreml` feature).
+/// - `Nnapi`: Use the NNAPI backend for computation (requires the `nnapi` feature and android).
 ///
 /// # Example
 ///
@@ -49,17 +54,23 @@ pub enum ForwardType {
     #[cfg(feature = "opencl")]
     /// Use the OpenCL backend for computation.
     OpenCL,
+    /// Use the OpenGL backend for computation.
+    #[cfg(feature = "opengl")]
+    OpenGL,
     /// Use the Vulkan backend for computation.
     #[cfg(feature = "vulkan")]
     Vulkan,
     /// Use the CoreML backend for computation.
     #[cfg(feature = "coreml")]
     CoreML,
+    /// Use the NNAPI backend for computation.
+    #[cfg(all(feature = "nnapi", target_os = "android"))]
+    Nnapi,
 }
 
 impl ForwardType {
     /// Convert the `ForwardType` enum to the corresponding C++ `MNNForwardType` enum.
-    fn to_mnn_sys(self) -> MNNForwardType {
+    pub(crate) fn to_mnn_sys(self) -> MNNForwardType {
         match self {
             ForwardType::Auto => MNNForwardType::MNN_FORWARD_AUTO,
             ForwardType::All => MNNForwardType::MNN_FORWARD_ALL,
@@ -74,10 +85,12 @@ impl ForwardType {
             ForwardType::Vulkan => MNNForwardType::MNN_FORWARD_VULKAN,
             #[cfg(feature = "coreml")]
             ForwardType::CoreML => MNNForwardType::MNN_FORWARD_NN,
+            #[cfg(all(feature = "nnapi", target_os = "android"))]
+            ForwardType::Nnapi => MNNForwardType::MNN_FORWARD_NN,
         }
     }
 
-    fn from_mnn_sys(mode: MNNForwardType) -> Self {
+    pub(crate) fn from_mnn_sys(mode: MNNForwardType) -> Self {
         match mode {
             MNNForwardType::MNN_FORWARD_AUTO => ForwardType::Auto,
             MNNForwardType::MNN_FORWARD_ALL => ForwardType::All,
@@ -92,29 +105,99 @@ impl ForwardType {
             MNNForwardType::MNN_FORWARD_VULKAN => ForwardType::Vulkan,
             #[cfg(feature = "coreml")]
             MNNForwardType::MNN_FORWARD_NN => ForwardType::CoreML,
+            // CoreML and NNAPI are both MNN_FORWARD_NN
+            #[cfg(all(feature = "nnapi", target_os = "android", not(feature = "coreml")))]
+            MNNForwardType::MNN_FORWARD_NN => ForwardType::Nnapi,
             _ => ForwardType::Auto,
         }
     }
 
-    /// List all available `ForwardType` variants as string slices.
-    fn list() -> Vec<&'static str> {
+    /// Iterate over all the `ForwardType` variants enabled in this build (including `All` and
+    /// `Auto`), whether they're available on this device or not.
+    ///
+    /// ```rust
+    /// use mnn::ForwardType;
+    /// for forward_type in ForwardType::iter() {
+    ///     println!("{}: {}", forward_type.name(), forward_type.is_available());
+    /// }
+    /// ```
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Self::variants().into_iter()
+    }
+
+    /// All the `ForwardType` variants enabled in this build.
+    fn variants() -> Vec<Self> {
         vec![
-            "auto",
-            "all",
-            "cpu",
+            ForwardType::Auto,
+            ForwardType::All,
+            ForwardType::CPU,
             #[cfg(feature = "metal")]
-            "metal",
+            ForwardType::Metal,
             #[cfg(feature = "opencl")]
-            "opencl",
+            ForwardType::OpenCL,
             #[cfg(feature = "opengl")]
-            "opengl",
+            ForwardType::OpenGL,
             #[cfg(feature = "vulkan")]
-            "vulkan",
+            ForwardType::Vulkan,
             #[cfg(feature = "coreml")]
-            "coreml",
+            ForwardType::CoreML,
+            #[cfg(all(feature = "nnapi", target_os = "android"))]
+            ForwardType::Nnapi,
         ]
     }
 
+    /// Check whether the backend was compiled in and can be created on this device.
+    ///
+    /// This creates a small runtime with the backend, so it isn't free. `All` and `Auto` are
+    /// always available since they fall back to the CPU.
+    ///
+    /// ```rust
+    /// use mnn::ForwardType;
+    /// assert!(ForwardType::CPU.is_available());
+    /// ```
+    pub fn is_available(self) -> bool {
+        match self {
+            ForwardType::All | ForwardType::Auto => true,
+            _ => {
+                let mut config = crate::ScheduleConfig::new();
+                config.set_type(self);
+                config.set_backup_type(self);
+                crate::Runtime::new([config]).is_ok_and(|runtime| runtime.has_backend(self))
+            }
+        }
+    }
+
+    /// All the backends that are available on this device (see [`ForwardType::is_available`]).
+    ///
+    /// Doesn't include `All` and `Auto`.
+    ///
+    /// ```rust,no_run
+    /// use mnn::*;
+    /// let forward_type = ForwardType::all_available()
+    ///     .into_iter()
+    ///     .find(|f| *f != ForwardType::CPU)
+    ///     .unwrap_or(ForwardType::CPU);
+    /// let mut config = ScheduleConfig::new();
+    /// config.set_type(forward_type);
+    /// ```
+    pub fn all_available() -> Vec<Self> {
+        Self::variants()
+            .into_iter()
+            .filter(|f| !matches!(f, ForwardType::All | ForwardType::Auto))
+            .filter(|f| f.is_available())
+            .collect()
+    }
+
+    /// List all available `ForwardType` variants as string slices.
+    fn list() -> Vec<&'static str> {
+        Self::variants().into_iter().map(Self::to_str).collect()
+    }
+
+    /// The name of the backend, parsed back by [`FromStr`](core::str::FromStr)
+    pub fn name(&self) -> &'static str {
+        self.to_str()
+    }
+
     /// Convert the `ForwardType` enum to a string slice.
     pub fn to_str(self) -> &'static str {
         match self {
@@ -131,6 +214,8 @@ impl ForwardType {
             ForwardType::Vulkan => "vulkan",
             #[cfg(feature = "coreml")]
             ForwardType::CoreML => "coreml",
+            #[cfg(all(feature = "nnapi", target_os = "android"))]
+            ForwardType::Nnapi => "nnapi",
         }
     }
 }
@@ -139,7 +224,7 @@ impl core::str::FromStr for ForwardType {
     type Err = MNNError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
+        match s.to_ascii_lowercase().as_str() {
             "auto" => Ok(ForwardType::Auto),
             "all" => Ok(ForwardType::All),
             "cpu" => Ok(ForwardType::CPU),
@@ -153,6 +238,8 @@ impl core::str::FromStr for ForwardType {
             "vulkan" => Ok(ForwardType::Vulkan),
             #[cfg(feature = "coreml")]
             "coreml" => Ok(ForwardType::CoreML),
+            #[cfg(all(feature = "nnapi", target_os = "android"))]
+            "nnapi" => Ok(ForwardType::Nnapi),
             _ => Err(MNNError::new(crate::ErrorKind::ParseError)
                 .attach_printable(format!(
                     "Invalid ForwardType: {s}, maybe you might need to enable feature {s}"
@@ -165,6 +252,63 @@ impl core::str::FromStr for ForwardType {
     }
 }
 
+/// Process wide cap on the threads of cpu sessions (0 means no cap)
+static GLOBAL_THREAD_NUMBER: AtomicI32 = AtomicI32::new(0);
+
+/// Cap the number of threads every cpu session of the process uses
+///
+/// The thread count of [`ScheduleConfig`]s with [`ForwardType::CPU`] is clamped to `n` when
+/// creating sessions and [`Runtime`](crate::Runtime)s, so many models running together don't use
+/// more than `n` threads each. Sessions created before the call keep their threads.
+/// `0` removes the cap.
+///
+/// With the `mnn-threadpool` feature (the default) MNN runs cpu sessions on its own thread pool
+/// and reuses its workers across sessions, so the cap also bounds the worker threads of the pool.
+/// With `openmp` the sessions run on the OpenMP runtime instead which sizes its own pool (see
+/// `OMP_NUM_THREADS`), the cap only limits the threads requested by each session there.
+///
+/// Other backends (e.g. OpenCL) use the thread count as a mode and are not affected.
+pub fn set_global_thread_number(n: usize) {
+    GLOBAL_THREAD_NUMBER.store(n.min(i32::MAX as usize) as i32, Ordering::Relaxed);
+}
+
+/// The cap set with [`set_global_thread_number`] (`None` if there is no cap)
+pub fn global_thread_number() -> Option<usize> {
+    match GLOBAL_THREAD_NUMBER.load(Ordering::Relaxed) {
+        0 => None,
+        n => Some(n as usize),
+    }
+}
+
+/// Set the number of threads of the OpenMP runtime (`omp_set_num_threads`) for the calling thread
+///
+/// With the `openmp` feature the cpu sessions run their ops in OpenMP parallel regions. MNN sets
+/// the thread count of the session ([`ScheduleConfig::set_num_threads`], clamped by
+/// [`set_global_thread_number`]) before the regions it starts itself, but the regions that don't
+/// request a count use the OpenMP default which is `OMP_NUM_THREADS` or the number of cores.
+/// Setting it to the session's count keeps all of them in line.
+///
+/// OpenMP keeps the count per thread, so this has to be called on the thread running the
+/// sessions. `0` is treated as `1`.
+///
+/// ```rust
+/// # use mnn::*;
+/// set_omp_threads(2);
+/// assert_eq!(omp_threads(), 2);
+/// ```
+#[cfg(feature = "openmp")]
+pub fn set_omp_threads(n: usize) {
+    let n = n.clamp(1, i32::MAX as usize) as c_int;
+    unsafe { mnn_sys::omp::omp_set_num_threads(n) }
+}
+
+/// The number of threads OpenMP parallel regions started from the calling thread use
+/// (`omp_get_max_threads`), see [`set_omp_threads`]
+#[cfg(feature = "openmp")]
+pub fn omp_threads() -> usize {
+    unsafe { mnn_sys::omp::omp_get_max_threads() }.max(1) as usize
+}
+
 /// Configuration for scheduling the forward computation in MNN.
 ///
 /// The `ScheduleConfig` struct is used to configure various parameters for scheduling the forward
@@ -193,6 +337,7 @@ impl core::str::FromStr for ForwardType {
 /// - `new() -> Self`: Creates a new `ScheduleConfig` with default settings.
 /// - `as_ptr_mut(&mut self) -> *mut MNNScheduleConfig`: Returns a mutable raw pointer to the underlying `MNNScheduleConfig`.
 /// - `set_save_tensors(&mut self, save_tensors: &[&str]) -> Result<()>`: Sets the tensors to be saved during computation.
+/// - `with_save_tensors(self, save_tensors: &[&str]) -> Result<Self>`: Same as `set_save_tensors` for building a config.
 /// - `set_type(&mut self, forward_type: ForwardType)`: Sets the type of backend to be used for computation.
 /// - `set_num_threads(&mut self, num_threads: i32)`: Sets the number of threads to be used for computation.
 /// - `set_mode(&mut self, mode: i32)`: Sets the mode of computation.
@@ -211,6 +356,8 @@ impl core::str::FromStr for ForwardType {
 pub struct ScheduleConfig {
     pub(crate) inner: *mut MNNScheduleConfig,
     pub(crate) backend_config: Option<BackendConfig>,
+    /// Create the session with [`SessionMode::BackendAuto`](crate::SessionMode::BackendAuto)
+    pub(crate) backend_auto: bool,
     pub(crate) __marker: core::marker::PhantomData<()>,
 }
 
@@ -219,7 +366,9 @@ impl core::fmt::Debug for ScheduleConfig {
         f.debug_struct("ScheduleConfig")
             .field("type", &self.get_type())
             .field("backup_type", &self.get_backup_type())
+            .field("num_threads", &self.get_num_threads())
             .field("backend_config", &self.backend_config)
+            .field("backend_auto", &self.backend_auto)
             .finish()
     }
 }
@@ -228,14 +377,47 @@ impl core::fmt::Debug for ScheduleConfig {
 impl serde::Serialize for ScheduleConfig {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("ScheduleConfig", 3)?;
+        let mut state = serializer.serialize_struct("ScheduleConfig", 5)?;
         state.serialize_field("type", &self.get_type())?;
         state.serialize_field("backup_type", &self.get_backup_type())?;
+        state.serialize_field("num_threads", &self.get_num_threads())?;
         state.serialize_field("backend_config", &self.backend_config)?;
+        state.serialize_field("backend_auto", &self.backend_auto)?;
         state.end()
     }
 }
 
+/// Missing fields keep the defaults of [`ScheduleConfig::new`]
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ScheduleConfig {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "ScheduleConfig")]
+        struct Fields {
+            #[serde(rename = "type")]
+            forward_type: Option<ForwardType>,
+            backup_type: Option<ForwardType>,
+            num_threads: Option<i32>,
+            backend_config: Option<BackendConfig>,
+            backend_auto: Option<bool>,
+        }
+        let fields = Fields::deserialize(deserializer)?;
+        let mut config = ScheduleConfig::new();
+        if let Some(forward_type) = fields.forward_type {
+            config.set_type(forward_type);
+        }
+        if let Some(backup_type) = fields.backup_type {
+            config.set_backup_type(backup_type);
+        }
+        if let Some(num_threads) = fields.num_threads {
+            config.set_num_threads(num_threads);
+        }
+        config.set_backend_config(fields.backend_config);
+        config.set_backend_auto(fields.backend_auto.unwrap_or_default());
+        Ok(config)
+    }
+}
+
 impl Clone for ScheduleConfig {
     fn clone(&self) -> Self {
         unsafe {
@@ -243,6 +425,7 @@ impl Clone for ScheduleConfig {
             Self {
                 inner,
                 backend_config: self.backend_config.clone(),
+                backend_auto: self.backend_auto,
                 __marker: core::marker::PhantomData,
             }
         }
@@ -278,6 +461,7 @@ impl ScheduleConfig {
             Self {
                 inner,
                 backend_config: None,
+                backend_auto: false,
                 __marker: core::marker::PhantomData,
             }
         }
@@ -285,6 +469,10 @@ impl ScheduleConfig {
 
     /// Sets the tensors to be saved during computation.
     ///
+    /// The saved tensors are kept after running the session and become additional outputs of
+    /// it, so intermediate activations can be read with [`Interpreter::output`] /
+    /// [`Interpreter::outputs`] next to the outputs of the model, which are left as they are.
+    ///
     /// # Arguments
     ///
     /// - `save_tensors`: A slice of tensor names to be saved.
@@ -292,6 +480,9 @@ impl ScheduleConfig {
     /// # Errors
     ///
     /// Returns an error if any of the tensor names contain null bytes.
+    ///
+    /// [`Interpreter::output`]: crate::Interpreter::output
+    /// [`Interpreter::outputs`]: crate::Interpreter::outputs
     pub fn set_save_tensors(&mut self, save_tensors: &[&str]) -> Result<&mut Self> {
         let vec_cstring = save_tensors
             .iter()
@@ -305,6 +496,21 @@ impl ScheduleConfig {
         Ok(self)
     }
 
+    /// Sets the tensors to be saved during computation, see [`ScheduleConfig::set_save_tensors`]
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let net = Interpreter::from_file("model.mnn").unwrap();
+    /// let config = ScheduleConfig::new().with_save_tensors(&["conv5_out"]).unwrap();
+    /// let session = net.create_session(config).unwrap();
+    /// net.run_session(&session).unwrap();
+    /// let features = net.output::<f32>(&session, "conv5_out").unwrap().to_host().unwrap();
+    /// ```
+    pub fn with_save_tensors(mut self, save_tensors: &[&str]) -> Result<Self> {
+        self.set_save_tensors(save_tensors)?;
+        Ok(self)
+    }
+
     /// Sets the type of backend to be used for computation.
     ///
     /// # Arguments
@@ -346,6 +552,24 @@ impl ScheduleConfig {
         self
     }
 
+    /// Gets the number of threads to be used for computation.
+    ///
+    /// This shares its storage with the mode set by [`ScheduleConfig::set_mode`].
+    pub fn get_num_threads(&self) -> i32 {
+        unsafe { mnnsc_get_num_threads(self.inner) }
+    }
+
+    /// Clamp the thread count to [`global_thread_number`] for cpu configs
+    pub(crate) fn cap_threads(&mut self) {
+        let Some(cap) = global_thread_number() else {
+            return;
+        };
+        let cap = cap as i32;
+        if self.get_type() == ForwardType::CPU && self.get_num_threads() > cap {
+            self.set_num_threads(cap);
+        }
+    }
+
     /// Sets the mode of computation.
     ///
     /// # Arguments
@@ -364,6 +588,36 @@ impl ScheduleConfig {
         self
     }
 
+    /// Replaces the tuning / memory flags of gpu (OpenCL / Vulkan) sessions
+    ///
+    /// The flags aren't ORed into the current value, combine them with `|` and pass them at once.
+    /// MNN reads the gpu mode from the same field as the number of threads, so this also replaces
+    /// the value set with [`ScheduleConfig::set_num_threads`] (whose default isn't a gpu mode).
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// let mode = MNNGpuMode::MNN_GPU_TUNING_FAST | MNNGpuMode::MNN_GPU_MEMORY_IMAGE;
+    /// let config = ScheduleConfig::new()
+    ///     .with_num_threads(8)
+    ///     .with_type(ForwardType::Auto)
+    ///     .with_replaced_gpu_mode(mode);
+    /// assert_eq!(config.get_gpu_mode(), mode);
+    /// ```
+    pub fn replace_gpu_mode(&mut self, mode: MNNGpuMode) -> &mut Self {
+        self.set_mode(mode.0 as i32)
+    }
+
+    /// Replaces the tuning / memory flags of gpu (OpenCL / Vulkan) sessions
+    pub fn with_replaced_gpu_mode(mut self, mode: MNNGpuMode) -> Self {
+        self.replace_gpu_mode(mode);
+        self
+    }
+
+    /// Gets the gpu mode (this is the number of threads for cpu sessions)
+    pub fn get_gpu_mode(&self) -> MNNGpuMode {
+        MNNGpuMode(self.get_num_threads() as _)
+    }
+
     /// Sets the backup type of backend to be used if the primary backend fails.
     ///
     /// # Arguments
@@ -413,6 +667,50 @@ impl ScheduleConfig {
         self.set_backend_config(backend_config);
         self
     }
+
+    /// Creates a config that lets MNN pick between `forward_type` and the cpu for each operator
+    ///
+    /// Same as `ScheduleConfig::new().with_type(forward_type).with_backup_type(ForwardType::CPU)`
+    /// with [`ScheduleConfig::set_backend_auto`].
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// let config = ScheduleConfig::auto_backend(ForwardType::OpenCL);
+    /// assert_eq!(config.get_type(), ForwardType::OpenCL);
+    /// assert_eq!(config.get_backup_type(), ForwardType::CPU);
+    /// assert!(config.get_backend_auto());
+    /// ```
+    pub fn auto_backend(forward_type: ForwardType) -> Self {
+        Self::new()
+            .with_type(forward_type)
+            .with_backup_type(ForwardType::CPU)
+            .with_backend_auto(true)
+    }
+
+    /// Sets whether MNN picks the backend of each operator (between the type and the backup type)
+    ///
+    /// The session is created with [`SessionMode::BackendAuto`](crate::SessionMode::BackendAuto)
+    /// instead of the mode of the interpreter, which is left unchanged for the other sessions.
+    /// In this mode MNN asks both backends for an estimate
+    /// of the cost of each operator (from its flops and the memory it touches) and runs the
+    /// operator on the cheaper one, counting the copies between the backends. Large operators end
+    /// up on the gpu and small ones (reshapes, small element-wise ops, ...) on the cpu.
+    /// MNN doesn't expose a threshold for this decision.
+    pub fn set_backend_auto(&mut self, backend_auto: bool) -> &mut Self {
+        self.backend_auto = backend_auto;
+        self
+    }
+
+    /// Sets whether MNN picks the backend of each operator (between the type and the backup type)
+    pub fn with_backend_auto(mut self, backend_auto: bool) -> Self {
+        self.set_backend_auto(backend_auto);
+        self
+    }
+
+    /// Gets whether MNN picks the backend of each operator
+    pub fn get_backend_auto(&self) -> bool {
+        self.backend_auto
+    }
 }
 
 /// A list of `ScheduleConfig` objects to be used for scheduling the forward computation in MNN.
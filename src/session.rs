@@ -1,25 +1,43 @@
-use crate::prelude::*;
+use crate::internal_prelude::*;
+use std::collections::HashMap;
 
 /// A session is a context in which a computation graph is executed.
 ///
 /// Inference unit. multiple sessions could share one net/interpreter.
+///
+/// # Thread safety
+/// A session can be moved to another thread ([Send]) but it can't be shared between threads
+/// ([Sync]) since MNN doesn't allow running the same session concurrently.
+/// ```compile_fail
+/// fn is_sync<T: Sync>() {}
+/// is_sync::<mnn::Session>();
+/// ```
 #[derive(Debug)]
 pub struct Session {
     /// Pointer to the underlying MNN session.
     pub(crate) inner: *mut mnn_sys::Session,
-    /// Pointer to the underlying MNN interpreter
-    /// # Safety Note
-    /// Since the interpreter is actually not owned by session but it is a shared resource we can
-    /// reasonably assume that the interpreter will outlive the session. (This is not a compile
-    /// time gurantee yet)
-    /// TODO: Add a proper lifetime bound to ensure the interpreter outlives the session.
+    /// Pointer to the underlying MNN interpreter, kept alive by `__interpreter`
     pub(crate) net: *mut mnn_sys::Interpreter,
+    /// Owns `net`, so the interpreter outlives the session even if every [`crate::Interpreter`]
+    /// handle is dropped before it
+    pub(crate) __interpreter: std::sync::Arc<crate::interpreter::InterpreterShared>,
     /// Internal session configurations.
     pub(crate) __session_internals: crate::SessionInternals,
-    /// Marker to ensure the struct is not Send or Sync.
+    /// The runtime shared with other sessions (if created with one)
+    pub(crate) __runtime: Option<crate::Runtime>,
+    /// The inputs bound with [`Session::bind_input`]
+    pub(crate) __bound_inputs: HashMap<String, BoundInput>,
+    /// The normalization used by [`Session::set_image`]
+    #[cfg(feature = "image")]
+    pub(crate) __preprocess: Option<crate::Preprocess>,
+    /// Marker for the session (the raw pointers already make the struct `!Send` and `!Sync`).
     pub(crate) __marker: PhantomData<()>,
 }
 
+// SAFETY: The session can be run from any thread as long as it's not run concurrently, which is
+// enforced by not implementing Sync for Session.
+unsafe impl Send for Session {}
+
 /// Enum representing the internal configurations of a session.
 #[derive(Debug)]
 pub enum SessionInternals {
@@ -31,17 +49,1220 @@ pub enum SessionInternals {
 
 impl Session {
     /// Calls the destroy function on the underlying MNN session.
+    ///
+    /// The session can't be used after this, dropping it afterwards doesn't release it again.
     pub fn destroy(&mut self) {
+        self.__bound_inputs.clear();
+        if self.inner.is_null() {
+            return;
+        }
+        // Releasing frees memory of the backends shared with the other sessions of the runtime
+        let _guard = self.__runtime.as_ref().map(crate::Runtime::lock);
         unsafe {
             mnn_sys::Interpreter_releaseSession(self.net, self.inner);
         }
+        self.inner = core::ptr::null_mut();
         // unsafe { mnn_sys::Session_destroy(self.inner) }
     }
 }
 
+#[cfg(feature = "tokio")]
+impl Session {
+    /// Run the session on the blocking thread pool of tokio without blocking the executor
+    ///
+    /// The session is moved to the blocking thread and returned once the run is finished, since
+    /// it must not be run concurrently (see [Thread safety](Session#thread-safety)). The session
+    /// keeps its interpreter alive, so the interpreter can be dropped while the run is pending.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// # async fn run() -> Result<(), MNNError> {
+    /// let interpreter = Interpreter::from_file("model.mnn")?;
+    /// let session = interpreter.create_session(ScheduleConfig::new())?;
+    /// let session = session.run_async().await?;
+    /// let output = interpreter.output::<f32>(&session, "output")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn run_async(self) -> Result<Self> {
+        tokio::task::spawn_blocking(move || {
+            crate::interpreter::run_session_raw(self.net, &self).map(|_| self)
+        })
+        .await
+        .map_err(|e| error!(ErrorKind::InterpreterError, e))?
+    }
+}
+
+#[cfg(feature = "image")]
+impl Session {
+    /// Set the normalization used by [`Session::set_image`] for every image of this session
+    pub fn set_preprocess(&mut self, preprocess: crate::Preprocess) {
+        self.__preprocess = Some(preprocess);
+    }
+
+    /// The normalization set with [`Session::set_preprocess`]
+    pub fn preprocess(&self) -> Option<&crate::Preprocess> {
+        self.__preprocess.as_ref()
+    }
+
+    /// Copy an image into an `f32` input of the session
+    ///
+    /// The image is resized to the input and normalized with the [`Preprocess`](crate::Preprocess)
+    /// set with [`Session::set_preprocess`] (or the default one if none was set), see
+    /// [`Tensor::copy_from_image`](crate::Tensor::copy_from_image).
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let interpreter = Interpreter::from_file("model.mnn").unwrap();
+    /// let mut session = interpreter.create_session(ScheduleConfig::new()).unwrap();
+    /// session.set_preprocess(Preprocess::new([0.485, 0.456, 0.406], [0.229, 0.224, 0.225]));
+    /// let image = image::open("image.png").unwrap();
+    /// session.set_image("input", &image).unwrap();
+    /// interpreter.run_session(&session).unwrap();
+    /// ```
+    pub fn set_image(&mut self, name: &str, img: &image::DynamicImage) -> Result<()> {
+        let preprocess = self.__preprocess.unwrap_or_default();
+        let input = self.input(name).ok_or_else(|| {
+            error!(ErrorKind::IOError)
+                .attach_printable(format!("Input tensor \"{name}\" not found"))
+        })?;
+        ensure!(
+            unsafe { mnn_sys::Tensor_isTypeOf(input.inner, mnn_sys::halide_type_of::<f32>()) },
+            ErrorKind::HalideTypeMismatch { got: "f32" };
+            format!("Input tensor \"{name}\"")
+        );
+        ensure!(!input.is_dynamic_unsized(), ErrorKind::DynamicTensorError);
+        // Packed (NC4HW4) inputs are converted by MNN when copying from the host tensor
+        let dim_type = match input.get_dimension_type() {
+            crate::DimensionType::TensorFlow => crate::DimensionType::TensorFlow,
+            _ => crate::DimensionType::Caffe,
+        };
+        let mut host = crate::Tensor::<crate::Host<f32>>::new(input.shape(), dim_type);
+        host.copy_from_image_with(img, &preprocess)
+            .map_err(|e| e.attach_printable(format!("Input tensor \"{name}\"")))?;
+        let ret = unsafe { mnn_sys::Tensor_copyFromHostTensor(input.inner, host.tensor) };
+        ensure!(ret != 0, ErrorKind::TensorCopyFailed(ret));
+        Ok(())
+    }
+}
+
+/// An input bound to a reusable buffer with [`Session::bind_input`]
+#[derive(Debug)]
+pub(crate) struct BoundInput {
+    /// The input tensor of the session
+    tensor: *mut mnn_sys::Tensor,
+    /// Host tensor staged to the input tensor (`None` if the input tensor is in host memory)
+    staging: Option<*mut mnn_sys::Tensor>,
+    /// The element size of the input tensor when it was bound
+    element_size: usize,
+}
+
+impl BoundInput {
+    /// The tensor the data of the input is written to
+    fn buffer(&self) -> *mut mnn_sys::Tensor {
+        self.staging.unwrap_or(self.tensor)
+    }
+}
+
+impl Drop for BoundInput {
+    fn drop(&mut self) {
+        if let Some(staging) = self.staging {
+            unsafe { mnn_sys::Tensor_destroy(staging) }
+        }
+    }
+}
+
+/// Inputs of a session pointing at caller owned buffers, see [`Session::with_input_ptrs`]
+///
+/// The inputs point back at their own memory when this is dropped, which always happens before
+/// [`Session::with_input_ptrs`] returns since it's only handed out by reference.
+#[derive(Debug)]
+pub struct UserInputs<'s> {
+    session: &'s mut Session,
+    /// The input tensors and their original host memory
+    original: Vec<(*mut mnn_sys::Tensor, *mut u8)>,
+}
+
+impl UserInputs<'_> {
+    /// Point another input tensor at `data` without copying it
+    fn set_input_ptr<H: HalideType>(&mut self, name: &str, data: &mut [H]) -> Result<()> {
+        let input = self.session.input(name).ok_or_else(|| {
+            error!(ErrorKind::IOError)
+                .attach_printable(format!("Input tensor \"{name}\" not found"))
+        })?;
+        ensure!(
+            unsafe { mnn_sys::Tensor_isTypeOf(input.inner, mnn_sys::halide_type_of::<H>()) },
+            ErrorKind::HalideTypeMismatch {
+                got: std::any::type_name::<H>(),
+            }
+        );
+        ensure!(
+            input.element_size() == data.len(),
+            ErrorKind::SizeMismatch {
+                expected: input.element_size(),
+                got: data.len(),
+            }
+        );
+        let tensor = input.inner;
+        ensure!(
+            !unsafe { mnn_sys::Tensor_host(tensor) }.is_null()
+                && input.get_dimension_type() != crate::DimensionType::NC4HW4,
+            ErrorKind::TensorError;
+            format!("Input \"{name}\" isn't an unpacked host tensor"),
+            "Use SessionMode::InputUser on the cpu backend"
+        );
+        let buffer = unsafe { mnn_sys::Tensor_buffer_mut(tensor) };
+        let original = unsafe { core::mem::replace(&mut (*buffer).host, data.as_mut_ptr().cast()) };
+        self.original.push((tensor, original));
+        Ok(())
+    }
+
+    /// Run the session with the inputs
+    pub fn run(&self) -> Result<()> {
+        crate::interpreter::run_session_raw(self.session.net, self.session)
+    }
+
+    /// The session, e.g. to read the outputs before the inputs are released
+    pub fn session(&self) -> &Session {
+        self.session
+    }
+}
+
+impl Drop for UserInputs<'_> {
+    fn drop(&mut self) {
+        // In reverse in case the same input was set twice
+        for (tensor, original) in self.original.drain(..).rev() {
+            unsafe { (*mnn_sys::Tensor_buffer_mut(tensor)).host = original };
+        }
+    }
+}
+
+/// Input shapes queued for a single resize of a session, see [`Session::resize_deferred`]
+///
+/// Nothing is resized if this is dropped without calling [`DeferredResize::resize`].
+#[derive(Debug)]
+pub struct DeferredResize<'s> {
+    session: &'s mut Session,
+    shapes: HashMap<String, Vec<i32>>,
+}
+
+impl DeferredResize<'_> {
+    /// Queue the shape of another input, a later shape for the same input replaces this one
+    pub fn set_input(mut self, name: &str, shape: impl AsRef<[i32]>) -> Self {
+        self.shapes
+            .insert(name.to_string(), shape.as_ref().to_vec());
+        self
+    }
+
+    /// Resize the queued inputs and the session once, see [`Session::resize`] for the errors
+    pub fn resize(self) -> Result<()> {
+        let shapes = self
+            .shapes
+            .iter()
+            .map(|(name, shape)| (name.as_str(), shape.clone()))
+            .collect();
+        self.session.resize(&shapes)
+    }
+}
+
 impl Drop for Session {
     /// Custom drop implementation to ensure the underlying MNN session is properly destroyed.
     fn drop(&mut self) {
         self.destroy();
     }
 }
+
+/// The kind of information that can be queried from a session with [`Session::info`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SessionInfoCode {
+    /// Memory used by the session in MB
+    Memory,
+    /// Float operations needed by the session in M
+    Flops,
+    /// Backends used by the session
+    Backends,
+    /// Resize status of the session
+    ResizeStatus,
+    /// Number of threads / mode used by the session
+    ThreadNumber,
+}
+
+impl SessionInfoCode {
+    fn to_mnn_sys(self) -> libc::c_int {
+        (match self {
+            Self::Memory => mnn_sys::cpp::MNN_Interpreter_SessionInfoCode_MEMORY,
+            Self::Flops => mnn_sys::cpp::MNN_Interpreter_SessionInfoCode_FLOPS,
+            Self::Backends => mnn_sys::cpp::MNN_Interpreter_SessionInfoCode_BACKENDS,
+            Self::ResizeStatus => mnn_sys::cpp::MNN_Interpreter_SessionInfoCode_RESIZE_STATUS,
+            Self::ThreadNumber => mnn_sys::cpp::MNN_Interpreter_SessionInfoCode_THREAD_NUMBER,
+        }) as libc::c_int
+    }
+}
+
+/// Information returned by [`Session::info`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionInfo {
+    /// Memory used by the session in MB
+    Memory(f32),
+    /// Float operations needed by the session in M
+    Flops(f32),
+    /// Backends used by the session (one per schedule config)
+    Backends(Vec<crate::ForwardType>),
+    /// Resize status of the session
+    ResizeStatus(crate::ResizeStatus),
+    /// Number of threads / mode used by the session
+    ThreadNumber(i32),
+}
+
+impl Session {
+    /// Query information about the session
+    ///
+    /// Returns `None` if MNN doesn't have the requested information for this session
+    pub fn info(&self, code: SessionInfoCode) -> Option<SessionInfo> {
+        match code {
+            SessionInfoCode::Memory => self.info_raw::<f32>(code).map(SessionInfo::Memory),
+            SessionInfoCode::Flops => self.info_raw::<f32>(code).map(SessionInfo::Flops),
+            SessionInfoCode::ThreadNumber => {
+                self.info_raw::<i32>(code).map(SessionInfo::ThreadNumber)
+            }
+            SessionInfoCode::ResizeStatus => {
+                let status = match self.info_raw::<i32>(code)? {
+                    0 => crate::ResizeStatus::None,
+                    1 => crate::ResizeStatus::NeedMalloc,
+                    2 => crate::ResizeStatus::NeedResize,
+                    _ => return None,
+                };
+                Some(SessionInfo::ResizeStatus(status))
+            }
+            SessionInfoCode::Backends => {
+                // MNN writes one forward type per pipeline (i.e. per schedule config)
+                let count = match &self.__session_internals {
+                    SessionInternals::Single(_) => 1,
+                    SessionInternals::MultiSession(configs) => configs.inner.len(),
+                };
+                let mut backends = vec![-1i32; count + 1];
+                let ret = unsafe {
+                    mnn_sys::Interpreter_getSessionInfo(
+                        self.net,
+                        self.inner,
+                        code.to_mnn_sys(),
+                        backends.as_mut_ptr().cast(),
+                    )
+                };
+                if ret != 1 {
+                    return None;
+                }
+                Some(SessionInfo::Backends(
+                    backends
+                        .into_iter()
+                        .take_while(|b| *b >= 0)
+                        .map(|b| {
+                            crate::ForwardType::from_mnn_sys(mnn_sys::MNNForwardType(b as _))
+                        })
+                        .collect(),
+                ))
+            }
+        }
+    }
+
+    fn info_raw<T: Default>(&self, code: SessionInfoCode) -> Option<T> {
+        let mut value = T::default();
+        let ret = unsafe {
+            mnn_sys::Interpreter_getSessionInfo(
+                self.net,
+                self.inner,
+                code.to_mnn_sys(),
+                (&mut value as *mut T).cast(),
+            )
+        };
+        (ret == 1).then_some(value)
+    }
+
+    /// Get memory used by the session in MB
+    pub fn memory_mb(&self) -> Option<f32> {
+        match self.info(SessionInfoCode::Memory)? {
+            SessionInfo::Memory(memory) => Some(memory),
+            _ => None,
+        }
+    }
+
+    /// Get float operations needed by the session in M
+    pub fn flops(&self) -> Option<f32> {
+        match self.info(SessionInfoCode::Flops)? {
+            SessionInfo::Flops(flops) => Some(flops),
+            _ => None,
+        }
+    }
+
+    /// Get the backends used by the session
+    pub fn backends(&self) -> Option<Vec<crate::ForwardType>> {
+        match self.info(SessionInfoCode::Backends)? {
+            SessionInfo::Backends(backends) => Some(backends),
+            _ => None,
+        }
+    }
+
+    /// Get the concrete backend MNN selected for the session
+    ///
+    /// Useful for sessions created with [`ForwardType::Auto`](crate::ForwardType::Auto), where
+    /// MNN picks the best backend available on the device. For sessions with several schedule
+    /// configs this is the backend of the first one, see [`Session::backends`] for all of them.
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// let net = Interpreter::from_file("tests/assets/realesr.mnn").unwrap();
+    /// let session = net
+    ///     .create_session(ScheduleConfig::new().with_type(ForwardType::Auto))
+    ///     .unwrap();
+    /// let backend = session.actual_backend().unwrap();
+    /// assert!(!matches!(backend, ForwardType::Auto | ForwardType::All));
+    /// ```
+    pub fn actual_backend(&self) -> Option<crate::ForwardType> {
+        self.backends()?
+            .into_iter()
+            .find(|b| !matches!(b, crate::ForwardType::Auto | crate::ForwardType::All))
+    }
+}
+
+impl Session {
+    /// Get all the input tensors of the session by name
+    ///
+    /// The names come from the model, use [`Interpreter::input`](crate::Interpreter::input) to
+    /// get a typed tensor.
+    pub fn inputs(&self) -> HashMap<String, crate::RawTensor<'_>> {
+        let inputs = unsafe { mnn_sys::Interpreter_getSessionInputAll(self.net, self.inner) };
+        Self::tensor_map(crate::tensor::list::TensorList::from_ptr(inputs))
+    }
+
+    /// Get the input tensor of the session by name
+    pub fn input(&self, name: &str) -> Option<crate::RawTensor<'_>> {
+        let c_name = std::ffi::CString::new(name).ok()?;
+        let input =
+            unsafe { mnn_sys::Interpreter_getSessionInput(self.net, self.inner, c_name.as_ptr()) };
+        (!input.is_null()).then(|| crate::RawTensor::from_ptr(input))
+    }
+
+    /// Get all the output tensors of the session by name
+    pub fn outputs(&self) -> HashMap<String, crate::RawTensor<'_>> {
+        let outputs = unsafe { mnn_sys::Interpreter_getSessionOutputAll(self.net, self.inner) };
+        Self::tensor_map(crate::tensor::list::TensorList::from_ptr(outputs))
+    }
+
+    /// Get the output tensor of the session by name
+    pub fn output(&self, name: &str) -> Option<crate::RawTensor<'_>> {
+        let c_name = std::ffi::CString::new(name).ok()?;
+        let output =
+            unsafe { mnn_sys::Interpreter_getSessionOutput(self.net, self.inner, c_name.as_ptr()) };
+        (!output.is_null()).then(|| crate::RawTensor::from_ptr(output))
+    }
+
+    /// Describe the inputs and outputs of the session
+    ///
+    /// Dynamic dimensions (which have to be given with [`Session::resize`] before running) are
+    /// reported as `-1`.
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let interpreter = Interpreter::from_file("model.mnn").unwrap();
+    /// let session = interpreter.create_session(ScheduleConfig::new()).unwrap();
+    /// for input in session.describe().inputs {
+    ///     println!("{}: {} {:?}", input.name, input.data_type, input.shape);
+    /// }
+    /// ```
+    pub fn describe(&self) -> SessionSpec {
+        let specs = |list: *const mnn_sys::TensorInfoArray| -> Vec<TensorSpec> {
+            crate::tensor::list::TensorList::from_ptr(list)
+                .iter()
+                .map(|info| {
+                    let tensor = info.raw_tensor();
+                    TensorSpec {
+                        name: info.name().to_string(),
+                        data_type: unsafe { mnn_sys::Tensor_getType(tensor.inner) }.into(),
+                        shape: tensor.shape().to_vec(),
+                    }
+                })
+                .collect()
+        };
+        SessionSpec {
+            inputs: specs(unsafe { mnn_sys::Interpreter_getSessionInputAll(self.net, self.inner) }),
+            outputs: specs(unsafe {
+                mnn_sys::Interpreter_getSessionOutputAll(self.net, self.inner)
+            }),
+        }
+    }
+
+    /// Copy all the output tensors of the session to the host, keyed by name
+    ///
+    /// Every output must be of type `H`, otherwise an error naming the mismatching output is
+    /// returned.
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let interpreter = Interpreter::from_file("model.mnn").unwrap();
+    /// let session = interpreter.create_session(ScheduleConfig::new()).unwrap();
+    /// interpreter.run_session(&session).unwrap();
+    /// let outputs = session.collect_outputs::<f32>().unwrap();
+    /// let boxes: &[f32] = &outputs["boxes"];
+    /// ```
+    pub fn collect_outputs<H: HalideType + Copy>(&self) -> Result<HashMap<String, Vec<H>>> {
+        let outputs = unsafe { mnn_sys::Interpreter_getSessionOutputAll(self.net, self.inner) };
+        let outputs = crate::tensor::list::TensorList::from_ptr(outputs);
+        outputs
+            .iter()
+            .map(|info| {
+                let name = info.name().to_string();
+                let tensor = info.tensor::<H>().map_err(|e| {
+                    let ty = unsafe { mnn_sys::Tensor_getType(info.raw_tensor().inner) };
+                    e.attach_printable(format!("Output \"{name}\" has type {ty:?}"))
+                })?;
+                let host = tensor
+                    .to_host()
+                    .map_err(|e| e.attach_printable(format!("Output \"{name}\"")))?;
+                Ok((name, host.host().to_vec()))
+            })
+            .collect()
+    }
+
+    /// Bind an input of the session to a reusable buffer
+    ///
+    /// The data of the input is written with [`Session::bound_input_mut`] and copied to the input
+    /// tensor by [`Session::run_with_inputs`], without allocating on every run. If the input tensor
+    /// is in host memory (e.g. on the cpu or with [`SessionMode::InputUser`](crate::SessionMode::InputUser))
+    /// the buffer is the input tensor itself, otherwise it's a host tensor that is staged to the
+    /// device before each run.
+    ///
+    /// The input has to be bound again after resizing it.
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let interpreter = Interpreter::from_file("model.mnn").unwrap();
+    /// let mut session = interpreter.create_session(ScheduleConfig::new()).unwrap();
+    /// session.bind_input::<f32>("input").unwrap();
+    /// loop {
+    ///     session.bound_input_mut::<f32>("input").unwrap().fill(1.0);
+    ///     session.run_with_inputs().unwrap();
+    ///     let output = interpreter.output::<f32>(&session, "output").unwrap();
+    /// #   break;
+    /// }
+    /// ```
+    pub fn bind_input<H: HalideType>(&mut self, name: &str) -> Result<()> {
+        let input = self.input(name).ok_or_else(|| {
+            error!(ErrorKind::IOError)
+                .attach_printable(format!("Input tensor \"{name}\" not found"))
+        })?;
+        ensure!(
+            unsafe { mnn_sys::Tensor_isTypeOf(input.inner, mnn_sys::halide_type_of::<H>()) },
+            ErrorKind::HalideTypeMismatch {
+                got: std::any::type_name::<H>(),
+            }
+        );
+        ensure!(!input.is_dynamic_unsized(), ErrorKind::DynamicTensorError);
+        let direct = !unsafe { mnn_sys::Tensor_host(input.inner) }.is_null();
+        let bound = BoundInput {
+            tensor: input.inner,
+            staging: (!direct).then(|| input.create_host_tensor_from_device(false).inner),
+            element_size: input.element_size(),
+        };
+        self.__bound_inputs.insert(name.to_string(), bound);
+        Ok(())
+    }
+
+    /// Get the buffer of an input bound with [`Session::bind_input`]
+    pub fn bound_input_mut<H: HalideType>(&mut self, name: &str) -> Result<&mut [H]> {
+        let bound = self.__bound_inputs.get_mut(name).ok_or_else(|| {
+            error!(ErrorKind::IOError).attach_printable(format!(
+                "Input \"{name}\" isn't bound, use bind_input first"
+            ))
+        })?;
+        let buffer = bound.buffer();
+        ensure!(
+            unsafe { mnn_sys::Tensor_isTypeOf(buffer, mnn_sys::halide_type_of::<H>()) },
+            ErrorKind::HalideTypeMismatch {
+                got: std::any::type_name::<H>(),
+            }
+        );
+        let data = unsafe { mnn_sys::Tensor_host_mut(buffer) };
+        ensure!(!data.is_null(), ErrorKind::TensorError; "Tensor doesn't have host memory");
+        Ok(unsafe { core::slice::from_raw_parts_mut(data.cast(), bound.element_size) })
+    }
+
+    /// Copy the bound inputs (see [`Session::bind_input`]) to the input tensors and run the session
+    pub fn run_with_inputs(&mut self) -> Result<()> {
+        for bound in self.__bound_inputs.values() {
+            let size = unsafe { mnn_sys::Tensor_elementSize(bound.tensor) } as usize;
+            ensure!(
+                size == bound.element_size,
+                ErrorKind::SizeMismatch {
+                    expected: size,
+                    got: bound.element_size,
+                };
+                "The input was resized after binding it, bind it again"
+            );
+            if let Some(staging) = bound.staging {
+                let ret = unsafe { mnn_sys::Tensor_copyFromHostTensor(bound.tensor, staging) };
+                ensure!(ret != 0, ErrorKind::TensorCopyFailed(ret));
+            }
+        }
+        crate::interpreter::run_session_raw(self.net, self)
+    }
+
+    /// Point input tensors at the given buffers without copying them while `f` runs
+    ///
+    /// The input tensors have to be in host memory and not packed, which is the case for the cpu
+    /// backend with [`SessionMode::InputUser`](crate::SessionMode::InputUser). The buffers are
+    /// borrowed mutably since MNN accesses them through the input tensors, which point back at
+    /// their own memory before this returns (also if setting an input fails or `f` panics).
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let mut interpreter = Interpreter::from_file("model.mnn").unwrap();
+    /// interpreter.set_session_mode(SessionMode::InputUser);
+    /// let mut session = interpreter.create_session(ScheduleConfig::new()).unwrap();
+    /// let mut image = vec![0.0f32; 3 * 224 * 224];
+    /// session
+    ///     .with_input_ptrs([("input", image.as_mut_slice())], |inputs| inputs.run())
+    ///     .unwrap()
+    ///     .unwrap();
+    /// let output = interpreter.output::<f32>(&session, "output").unwrap();
+    /// ```
+    pub fn with_input_ptrs<'a, H: HalideType, R>(
+        &mut self,
+        inputs: impl IntoIterator<Item = (&'a str, &'a mut [H])>,
+        f: impl FnOnce(&UserInputs<'_>) -> R,
+    ) -> Result<R> {
+        let mut user = UserInputs {
+            session: self,
+            original: Vec::new(),
+        };
+        for (name, data) in inputs {
+            user.set_input_ptr(name, data)?;
+        }
+        Ok(f(&user))
+    }
+
+    /// Run the session once with the given inputs and measure the time spent in each operator
+    ///
+    /// The inputs are copied to the input tensors of the same name before the run, inputs that
+    /// aren't given keep their current data.
+    /// On gpu backends the operators are queued asynchronously, each operator is waited for to
+    /// measure it, so the timings are only approximate (see [`ProfileReport::approximate`]).
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let interpreter = Interpreter::from_file("model.mnn").unwrap();
+    /// let mut session = interpreter.create_session(ScheduleConfig::new()).unwrap();
+    /// let image = vec![0.0f32; 3 * 224 * 224];
+    /// let report = session.profile([("input", image.as_slice())]).unwrap();
+    /// for (op_type, time) in &report.by_type {
+    ///     println!("{op_type}: {time:?}");
+    /// }
+    /// ```
+    pub fn profile<'i, H: HalideType + Copy + 'i>(
+        &mut self,
+        inputs: impl IntoIterator<Item = (&'i str, &'i [H])>,
+    ) -> Result<ProfileReport> {
+        for (name, data) in inputs {
+            self.write_input(name, data)?;
+        }
+
+        let ops = std::rc::Rc::new(std::cell::RefCell::new(Vec::<OpProfile>::new()));
+        let start = std::rc::Rc::new(std::cell::Cell::new(std::time::Instant::now()));
+        let before = {
+            let start = start.clone();
+            move |_: &[crate::RawTensor], _: crate::OperatorInfo| {
+                start.set(std::time::Instant::now());
+                true
+            }
+        };
+        let after = {
+            let (start, ops) = (start.clone(), ops.clone());
+            move |outputs: &[crate::RawTensor], op: crate::OperatorInfo| {
+                // Wait for the (possibly asynchronous) gpu work of the operator
+                outputs
+                    .iter()
+                    .for_each(|output| output.wait(mnn_sys::MapType::MAP_TENSOR_READ, true));
+                let time = start.get().elapsed();
+                let name = op.name().to_string_lossy();
+                let mut ops = ops.borrow_mut();
+                match ops.iter_mut().find(|profile| profile.name == name) {
+                    Some(profile) => profile.time += time,
+                    None => ops.push(OpProfile {
+                        name: name.into_owned(),
+                        type_name: op.type_name().to_string_lossy().into_owned(),
+                        flops: op.flops(),
+                        time,
+                    }),
+                }
+                true
+            }
+        };
+        crate::interpreter::run_session_with_callback_raw(self.net, self, before, after, true)?;
+
+        let mut ops = ops.take();
+        ops.sort_by(|a, b| b.time.cmp(&a.time));
+        let mut by_type = Vec::<(String, std::time::Duration)>::new();
+        for op in &ops {
+            match by_type.iter_mut().find(|(ty, _)| *ty == op.type_name) {
+                Some((_, time)) => *time += op.time,
+                None => by_type.push((op.type_name.clone(), op.time)),
+            }
+        }
+        by_type.sort_by(|a, b| b.1.cmp(&a.1));
+        let approximate = self.backends().is_none_or(|backends| {
+            backends
+                .iter()
+                .any(|backend| *backend != crate::ForwardType::CPU)
+        });
+        Ok(ProfileReport {
+            total: ops.iter().map(|op| op.time).sum(),
+            ops,
+            by_type,
+            approximate,
+        })
+    }
+
+    /// Run the session with `f32` or `i8` inputs and collect all the outputs
+    ///
+    /// Inputs and outputs are passed as they are unless `auto_quantize` is set, then `f32` data
+    /// given for an `i8` input is quantized with the [`QuantParams`](crate::QuantParams) of the
+    /// input tensor and `i8` outputs are dequantized to `f32` with their own. So a fully
+    /// quantized model can be fed either the `i8` values directly or the real `f32` values.
+    /// Returns an error if a tensor that has to be converted doesn't have quantization
+    /// parameters, and for outputs that are neither `f32` nor `i8`.
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let interpreter = Interpreter::from_file("model_int8.mnn").unwrap();
+    /// let mut session = interpreter.create_session(ScheduleConfig::new()).unwrap();
+    /// let image = vec![0.0f32; 3 * 224 * 224];
+    /// let outputs = session.run_typed([("input", image.as_slice().into())], true).unwrap();
+    /// let scores: &[f32] = outputs["scores"].as_f32().unwrap();
+    /// ```
+    pub fn run_typed<'i>(
+        &mut self,
+        inputs: impl IntoIterator<Item = (&'i str, TypedInput<'i>)>,
+        auto_quantize: bool,
+    ) -> Result<HashMap<String, TypedOutput>> {
+        for (name, data) in inputs {
+            let quantized = self.input(name).filter(|input| {
+                auto_quantize
+                    && unsafe {
+                        mnn_sys::Tensor_isTypeOf(input.inner, mnn_sys::halide_type_of::<i8>())
+                    }
+            });
+            match (data, quantized) {
+                (TypedInput::F32(values), Some(input)) => {
+                    let input = unsafe {
+                        crate::Tensor::<crate::Ref<crate::Device<i8>>>::from_ptr(input.inner)
+                    };
+                    let params = input.quant_params().ok_or_else(|| {
+                        error!(ErrorKind::TensorError).attach_printable(format!(
+                            "Input tensor \"{name}\" has no quantization parameters"
+                        ))
+                    })?;
+                    self.write_input(name, &params.quantize(values))?;
+                }
+                (TypedInput::F32(values), None) => self.write_input(name, values)?,
+                (TypedInput::I8(values), _) => self.write_input(name, values)?,
+            }
+        }
+        crate::interpreter::run_session_raw(self.net, self)?;
+
+        let outputs = unsafe { mnn_sys::Interpreter_getSessionOutputAll(self.net, self.inner) };
+        let outputs = crate::tensor::list::TensorList::from_ptr(outputs);
+        outputs
+            .iter()
+            .map(|info| {
+                let name = info.name().to_string();
+                let is_type_of =
+                    |ty| unsafe { mnn_sys::Tensor_isTypeOf(info.raw_tensor().inner, ty) };
+                let output = if is_type_of(mnn_sys::halide_type_of::<f32>()) {
+                    TypedOutput::F32(info.tensor::<f32>()?.to_host()?.host().to_vec())
+                } else if is_type_of(mnn_sys::halide_type_of::<i8>()) {
+                    let tensor = info.tensor::<i8>()?;
+                    let mut host = tensor.to_host()?;
+                    if auto_quantize {
+                        let params = tensor.quant_params().ok_or_else(|| {
+                            error!(ErrorKind::TensorError).attach_printable(format!(
+                                "Output tensor \"{name}\" has no quantization parameters"
+                            ))
+                        })?;
+                        host.set_quant_params(params);
+                        TypedOutput::F32(host.dequantize()?)
+                    } else {
+                        TypedOutput::I8(host.host().to_vec())
+                    }
+                } else {
+                    let ty = unsafe { mnn_sys::Tensor_getType(info.raw_tensor().inner) };
+                    return Err(error!(ErrorKind::HalideTypeMismatch { got: "f32 or i8" })
+                        .attach_printable(format!("Output \"{name}\" has type {ty:?}")));
+                };
+                Ok((name, output))
+            })
+            .collect()
+    }
+
+    /// Run the session, stopping it once `deadline` has passed
+    ///
+    /// Returns an [`ErrorKind::Timeout`] if the run was stopped, the outputs are incomplete then.
+    /// The deadline is only checked after each operator (with the callbacks of
+    /// [`Interpreter::run_session_with_callback`](crate::Interpreter::run_session_with_callback),
+    /// so the session must be in [`SessionMode::Debug`](crate::SessionMode::Debug), the default),
+    /// a single long operator or a hung gpu kernel can't be interrupted.
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// # use std::time::{Duration, Instant};
+    /// let interpreter = Interpreter::from_file("model.mnn").unwrap();
+    /// let mut session = interpreter.create_session(ScheduleConfig::new()).unwrap();
+    /// match session.run_with_deadline(Instant::now() + Duration::from_millis(100)) {
+    ///     Err(e) if matches!(e.kind(), ErrorKind::Timeout) => println!("Too slow"),
+    ///     result => result.unwrap(),
+    /// }
+    /// ```
+    pub fn run_with_deadline(&mut self, deadline: std::time::Instant) -> Result<()> {
+        let timed_out = std::rc::Rc::new(std::cell::Cell::new(false));
+        let after = {
+            let timed_out = timed_out.clone();
+            move |_: &[crate::RawTensor], _: crate::OperatorInfo| {
+                timed_out.set(std::time::Instant::now() >= deadline);
+                !timed_out.get()
+            }
+        };
+        let result = crate::interpreter::run_session_with_callback_raw(
+            self.net,
+            self,
+            |_, _| true,
+            after,
+            true,
+        );
+        if timed_out.get() {
+            return Err(error!(ErrorKind::Timeout)
+                .attach_printable("Stopped after an operator finished past the deadline"));
+        }
+        result
+    }
+
+    /// Run a model with a single input and output on a list of samples, `max_batch` at a time
+    ///
+    /// Each sample is the data of one item of the batch (the input shape without the batch
+    /// dimension). The input is resized to the number of samples of each chunk (the last chunk is
+    /// smaller instead of padded) and the outputs are split back into one `Vec` per sample in the
+    /// same order, the output has to scale with the batch for this. The input is resized back to
+    /// its original shape afterwards (also if a chunk fails), inputs bound with
+    /// [`Session::bind_input`] have to be bound again.
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let interpreter = Interpreter::from_file("model.mnn").unwrap();
+    /// let mut session = interpreter.create_session(ScheduleConfig::new()).unwrap();
+    /// let requests = vec![vec![0.0f32; 3 * 224 * 224]; 5];
+    /// let outputs = session.run_batched(&requests, 2).unwrap();
+    /// assert_eq!(outputs.len(), 5);
+    /// ```
+    pub fn run_batched<H: HalideType + Copy>(
+        &mut self,
+        inputs: &[Vec<H>],
+        max_batch: usize,
+    ) -> Result<Vec<Vec<H>>> {
+        ensure!(max_batch > 0, ErrorKind::InterpreterError; "max_batch must be at least 1");
+        let input = self.single_tensor(self.inputs(), "input")?;
+        let original = crate::RawTensor::from_ptr(input).shape().as_ref().to_vec();
+        ensure!(
+            !original.is_empty() && original[1..].iter().all(|&dim| dim > 0),
+            ErrorKind::DynamicTensorError;
+            format!("Input shape {original:?} needs a fixed size besides the batch dimension")
+        );
+        let sample = original[1..].iter().product::<i32>() as usize;
+
+        let mut batch = original[0];
+        let outputs = self.run_chunks(input, &original, &mut batch, inputs, max_batch, sample);
+        // Resized back even if a chunk failed
+        let restored = if batch != original[0] {
+            self.resize_input(input, &original)
+        } else {
+            Ok(())
+        };
+        let outputs = outputs?;
+        restored?;
+        Ok(outputs)
+    }
+
+    /// The chunks of [`Session::run_batched`], `batch` is the batch size the input is resized to
+    fn run_chunks<H: HalideType + Copy>(
+        &mut self,
+        input: *mut mnn_sys::Tensor,
+        original: &[i32],
+        batch: &mut i32,
+        inputs: &[Vec<H>],
+        max_batch: usize,
+        sample: usize,
+    ) -> Result<Vec<Vec<H>>> {
+        use crate::{Device, Ref, RefMut, Tensor};
+        let mut outputs = Vec::with_capacity(inputs.len());
+        for (index, chunk) in inputs.chunks(max_batch).enumerate() {
+            for (i, item) in chunk.iter().enumerate() {
+                ensure!(
+                    item.len() == sample,
+                    ErrorKind::SizeMismatch {
+                        expected: sample,
+                        got: item.len(),
+                    };
+                    format!("Sample {}", index * max_batch + i)
+                );
+            }
+            if *batch != chunk.len() as i32 {
+                *batch = chunk.len() as i32;
+                let mut shape = original.to_vec();
+                shape[0] = *batch;
+                self.resize_input(input, &shape)?;
+            }
+
+            let mut device = unsafe { Tensor::<RefMut<Device<H>>>::from_ptr(input) };
+            ensure!(
+                device.is_type_of::<H>(),
+                ErrorKind::HalideTypeMismatch {
+                    got: std::any::type_name::<H>(),
+                }
+            );
+            let mut host = device.create_host_tensor_from_device(false);
+            host.try_host_mut()?
+                .chunks_exact_mut(sample)
+                .zip(chunk)
+                .for_each(|(dst, src)| dst.copy_from_slice(src));
+            device.copy_from_host_tensor(&host)?;
+            crate::interpreter::run_session_raw(self.net, self)?;
+
+            let output = self.single_tensor(self.outputs(), "output")?;
+            let output = unsafe { Tensor::<Ref<Device<H>>>::from_ptr(output) };
+            ensure!(
+                output.is_type_of::<H>(),
+                ErrorKind::HalideTypeMismatch {
+                    got: std::any::type_name::<H>(),
+                }
+            );
+            let output = output.to_host()?;
+            let data = output.try_host()?;
+            // The output has to scale with the batch to be split per sample
+            ensure!(
+                !data.is_empty() && data.len() % chunk.len() == 0,
+                ErrorKind::SizeMismatch {
+                    expected: chunk.len(),
+                    got: data.len(),
+                };
+                format!(
+                    "The output of {} elements can't be split into {} samples",
+                    data.len(),
+                    chunk.len()
+                )
+            );
+            let per_sample = data.len() / chunk.len();
+            outputs.extend(data.chunks_exact(per_sample).map(<[H]>::to_vec));
+        }
+        Ok(outputs)
+    }
+
+    /// Resize the inputs of the session to the given shapes and resize the session
+    ///
+    /// Every input with a dynamic shape (a `-1` dimension) has to be given, inputs that aren't
+    /// given keep their shape. Returns an error without resizing anything if a name isn't an
+    /// input of the session, and an error if MNN couldn't resize the session to the shapes.
+    ///
+    /// Inputs bound with [`Session::bind_input`] have to be bound again afterwards.
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// # use std::collections::HashMap;
+    /// let interpreter = Interpreter::from_file("model.mnn").unwrap();
+    /// let mut session = interpreter.create_session(ScheduleConfig::new()).unwrap();
+    /// for len in [16, 128] {
+    ///     let shapes = HashMap::from([("input_ids", vec![1, len]), ("attention_mask", vec![1, len])]);
+    ///     session.resize(&shapes).unwrap();
+    ///     // fill the inputs and run the session
+    /// }
+    /// ```
+    pub fn resize(&mut self, shapes: &HashMap<&str, Vec<i32>>) -> Result<()> {
+        let inputs = self.inputs();
+        for name in shapes.keys() {
+            ensure!(
+                inputs.contains_key(*name),
+                ErrorKind::IOError;
+                format!("Input tensor \"{name}\" not found")
+            );
+        }
+        let mut resized = Vec::with_capacity(shapes.len());
+        for (name, input) in &inputs {
+            match shapes.get(name.as_str()) {
+                Some(shape) => {
+                    ensure!(
+                        !shape.is_empty() && shape.iter().all(|&dim| dim > 0),
+                        ErrorKind::TensorError;
+                        format!("Invalid shape {shape:?} for input \"{name}\"")
+                    );
+                    resized.push((input.inner, shape));
+                }
+                None => ensure!(
+                    !input.is_dynamic_unsized(),
+                    ErrorKind::DynamicTensorError;
+                    format!("Input \"{name}\" with shape {:?} wasn't resized", input.shape())
+                ),
+            }
+        }
+        drop(inputs);
+        {
+            let _guard = self.__runtime.as_ref().map(crate::Runtime::lock);
+            for (input, shape) in &resized {
+                unsafe {
+                    mnn_sys::Interpreter_resizeTensor(self.net, *input, shape.as_ptr(), shape.len())
+                };
+            }
+            unsafe { mnn_sys::Interpreter_resizeSession(self.net, self.inner) };
+        }
+        let status = crate::interpreter::resize_status_raw(self.net, self)?;
+        ensure!(
+            status == crate::ResizeStatus::None,
+            ErrorKind::InterpreterError;
+            format!("MNN couldn't resize the session to {shapes:?} (status {status:?})")
+        );
+        for (input, shape) in resized {
+            let got = crate::RawTensor::from_ptr(input).shape();
+            ensure!(
+                got.as_ref() == shape.as_slice(),
+                ErrorKind::ShapeMismatch {
+                    expected: shape.iter().map(|&dim| dim as usize).collect(),
+                    got: got.as_ref().iter().map(|&dim| dim as usize).collect(),
+                };
+                "MNN resized the input to a different shape"
+            );
+        }
+        Ok(())
+    }
+
+    /// Queue input shapes and resize the session once for all of them
+    ///
+    /// The inputs keep their shape and memory until [`DeferredResize::resize`], which resizes all
+    /// the queued inputs before a single resize of the session like [`Session::resize`], so the
+    /// session isn't reallocated for each input. Combined with [`SessionMode::ResizeDefer`]
+    /// (the session isn't resized to the default shapes when it's created) the memory is only
+    /// allocated for the final shapes.
+    ///
+    /// With [`SessionMode::InputUser`] the input memory is reallocated by the resize, the inputs
+    /// have to be filled again after it and before each run.
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let mut interpreter = Interpreter::from_file("model.mnn").unwrap();
+    /// interpreter.set_session_mode(SessionMode::ResizeDefer);
+    /// let mut session = interpreter.create_session(ScheduleConfig::new()).unwrap();
+    /// session
+    ///     .resize_deferred()
+    ///     .set_input("image", [1, 3, 640, 640])
+    ///     .set_input("mask", [1, 1, 640, 640])
+    ///     .resize()
+    ///     .unwrap();
+    /// ```
+    ///
+    /// [`SessionMode::ResizeDefer`]: crate::SessionMode::ResizeDefer
+    /// [`SessionMode::InputUser`]: crate::SessionMode::InputUser
+    pub fn resize_deferred(&mut self) -> DeferredResize<'_> {
+        DeferredResize {
+            session: self,
+            shapes: HashMap::new(),
+        }
+    }
+
+    /// The only tensor of `tensors`, the inputs / outputs of the session
+    fn single_tensor(
+        &self,
+        tensors: HashMap<String, crate::RawTensor<'_>>,
+        kind: &str,
+    ) -> Result<*mut mnn_sys::Tensor> {
+        let count = tensors.len();
+        let mut tensors = tensors.into_values();
+        match (tensors.next(), tensors.next()) {
+            (Some(tensor), None) => Ok(tensor.inner),
+            _ => {
+                let message = format!("Expected a model with a single {kind}, got {count}");
+                Err(error!(ErrorKind::InterpreterError).attach_printable(message))
+            }
+        }
+    }
+
+    /// Copy `data` to the input tensor `name`, staging it through a host tensor
+    fn write_input<H: HalideType + Copy>(&self, name: &str, data: &[H]) -> Result<()> {
+        let input = self.input(name).ok_or_else(|| {
+            error!(ErrorKind::IOError)
+                .attach_printable(format!("Input tensor \"{name}\" not found"))
+        })?;
+        ensure!(
+            unsafe { mnn_sys::Tensor_isTypeOf(input.inner, mnn_sys::halide_type_of::<H>()) },
+            ErrorKind::HalideTypeMismatch {
+                got: std::any::type_name::<H>(),
+            };
+            format!("Input tensor \"{name}\"")
+        );
+        ensure!(
+            input.element_size() == data.len(),
+            ErrorKind::SizeMismatch {
+                expected: input.element_size(),
+                got: data.len(),
+            };
+            format!("Input tensor \"{name}\"")
+        );
+        let host = input.create_host_tensor_from_device(false);
+        let ret = unsafe {
+            let buffer = mnn_sys::Tensor_host_mut(host.inner).cast::<H>();
+            core::ptr::copy_nonoverlapping(data.as_ptr(), buffer, data.len());
+            mnn_sys::Tensor_copyFromHostTensor(input.inner, host.inner)
+        };
+        host.destroy();
+        ensure!(ret != 0, ErrorKind::TensorCopyFailed(ret));
+        Ok(())
+    }
+
+    /// Resize an input tensor of the session and the session itself
+    fn resize_input(&mut self, input: *mut mnn_sys::Tensor, shape: &[i32]) -> Result<()> {
+        {
+            let _guard = self.__runtime.as_ref().map(crate::Runtime::lock);
+            unsafe {
+                mnn_sys::Interpreter_resizeTensor(self.net, input, shape.as_ptr(), shape.len());
+                mnn_sys::Interpreter_resizeSession(self.net, self.inner);
+            }
+        }
+        let status = crate::interpreter::resize_status_raw(self.net, self)?;
+        ensure!(
+            status == crate::ResizeStatus::None,
+            ErrorKind::InterpreterError;
+            format!("MNN couldn't resize the input to {shape:?} (status {status:?})")
+        );
+        Ok(())
+    }
+
+    fn tensor_map(
+        list: crate::tensor::list::TensorList<'_>,
+    ) -> HashMap<String, crate::RawTensor<'_>> {
+        list.iter()
+            .map(|info| (info.name().to_string(), info.raw_tensor()))
+            .collect()
+    }
+}
+
+/// The timings of a single run measured with [`Session::profile`]
+#[derive(Debug, Clone)]
+pub struct ProfileReport {
+    /// The operators of the model sorted by the time spent in them (slowest first)
+    pub ops: Vec<OpProfile>,
+    /// The time spent in each operator type sorted by time (slowest first)
+    pub by_type: Vec<(String, std::time::Duration)>,
+    /// The time spent in all operators
+    pub total: std::time::Duration,
+    /// Whether the session runs (partly) on a gpu backend where the per operator timings are only
+    /// approximate
+    pub approximate: bool,
+}
+
+/// The measured time of an operator in a [`ProfileReport`]
+#[derive(Debug, Clone)]
+pub struct OpProfile {
+    /// The name of the operator
+    pub name: String,
+    /// The type of the operator (e.g. `Convolution`)
+    pub type_name: String,
+    /// The float operations of the operator in M
+    pub flops: f32,
+    /// The time spent in the operator
+    pub time: std::time::Duration,
+}
+
+/// The inputs and outputs of a session, see [`Session::describe`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionSpec {
+    /// The input tensors
+    pub inputs: Vec<TensorSpec>,
+    /// The output tensors
+    pub outputs: Vec<TensorSpec>,
+}
+
+impl SessionSpec {
+    /// The input named `name`
+    pub fn input(&self, name: &str) -> Option<&TensorSpec> {
+        self.inputs.iter().find(|spec| spec.name == name)
+    }
+
+    /// The output named `name`
+    pub fn output(&self, name: &str) -> Option<&TensorSpec> {
+        self.outputs.iter().find(|spec| spec.name == name)
+    }
+}
+
+/// The name, data type and shape of an input or output of a session
+#[derive(Debug, Clone, PartialEq)]
+pub struct TensorSpec {
+    /// The name of the tensor in the model
+    pub name: String,
+    /// The element type of the tensor
+    pub data_type: crate::DataType,
+    /// The shape of the tensor, dynamic dimensions are `-1`
+    pub shape: Vec<i32>,
+}
+
+impl TensorSpec {
+    /// The number of dimensions of the tensor
+    pub fn rank(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// Whether any dimension of the tensor is dynamic
+    pub fn is_dynamic(&self) -> bool {
+        self.shape.contains(&-1)
+    }
+}
+
+/// The data of an input of [`Session::run_typed`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TypedInput<'d> {
+    /// Real values, quantized for `i8` inputs if requested
+    F32(&'d [f32]),
+    /// Quantized values for an `i8` input
+    I8(&'d [i8]),
+}
+
+impl<'d> From<&'d [f32]> for TypedInput<'d> {
+    fn from(value: &'d [f32]) -> Self {
+        Self::F32(value)
+    }
+}
+
+impl<'d> From<&'d [i8]> for TypedInput<'d> {
+    fn from(value: &'d [i8]) -> Self {
+        Self::I8(value)
+    }
+}
+
+/// The data of an output collected by [`Session::run_typed`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypedOutput {
+    /// An `f32` output, or a dequantized `i8` output
+    F32(Vec<f32>),
+    /// An `i8` output that wasn't dequantized
+    I8(Vec<i8>),
+}
+
+impl TypedOutput {
+    /// The values of an `f32` output
+    pub fn as_f32(&self) -> Option<&[f32]> {
+        match self {
+            Self::F32(values) => Some(values),
+            Self::I8(_) => None,
+        }
+    }
+
+    /// The values of an `i8` output
+    pub fn as_i8(&self) -> Option<&[i8]> {
+        match self {
+            Self::I8(values) => Some(values),
+            Self::F32(_) => None,
+        }
+    }
+}
@@ -1,9 +1,24 @@
-use crate::prelude::*;
+use crate::internal_prelude::*;
 use core::marker::PhantomData;
 use mnn_sys::*;
 pub(crate) mod list;
 mod raw;
 pub use raw::RawTensor;
+mod map;
+pub use map::{MappedTensor, MappedTensorMut};
+mod serialize;
+mod vec;
+pub use vec::VecTensor;
+#[cfg(feature = "image")]
+mod image;
+#[cfg(feature = "image")]
+pub use self::image::{ChannelOrder, Preprocess};
+#[cfg(feature = "bytemuck")]
+mod cast;
+#[cfg(feature = "half")]
+mod float16;
+#[cfg(feature = "half")]
+pub use float16::Float16;
 
 use mnn_sys::HalideType;
 
@@ -17,7 +32,13 @@ macro_rules! seal {
             )*
         };
     }
-seal!(Host<T>, Device<T>, Ref<'_, T>, RefMut<'_, T>);
+seal!(
+    Host<T>,
+    Device<T>,
+    SessionDevice<'_, T>,
+    Ref<'_, T>,
+    RefMut<'_, T>
+);
 
 /// A trait to represent the type of a tensor
 pub trait TensorType: seal::Sealed {
@@ -66,6 +87,16 @@ impl<H: HalideType> TensorType for Device<H> {
     }
 }
 
+impl<H: HalideType> TensorType for SessionDevice<'_, H> {
+    type H = H;
+    fn owned() -> bool {
+        true
+    }
+    fn host() -> bool {
+        false
+    }
+}
+
 impl<T: TensorType> TensorType for Ref<'_, T> {
     type H = T::H;
     fn owned() -> bool {
@@ -87,6 +118,7 @@ impl<T: TensorType> TensorType for RefMut<'_, T> {
 }
 
 impl<H: HalideType> DeviceTensorType for Device<H> {}
+impl<H: HalideType> DeviceTensorType for SessionDevice<'_, H> {}
 impl<H: HalideType> HostTensorType for Host<H> {}
 impl<H: HalideType> OwnedTensorType for Device<H> {}
 impl<H: HalideType> OwnedTensorType for Host<H> {}
@@ -95,6 +127,7 @@ impl<T: DeviceTensorType> DeviceTensorType for RefMut<'_, T> {}
 impl<T: HostTensorType> HostTensorType for Ref<'_, T> {}
 impl<T: HostTensorType> HostTensorType for RefMut<'_, T> {}
 impl<T: OwnedTensorType> MutableTensorType for T {}
+impl<H: HalideType> MutableTensorType for SessionDevice<'_, H> {}
 impl<T: TensorType> MutableTensorType for RefMut<'_, T> {}
 impl<T: TensorType> RefTensorType for Ref<'_, T> {}
 impl<T: TensorType> RefTensorType for RefMut<'_, T> {}
@@ -107,6 +140,10 @@ pub struct Host<T = f32> {
 pub struct Device<T = f32> {
     pub(crate) __marker: PhantomData<T>,
 }
+/// A device tensor allocated by the backend of a session, see [`Tensor::create_for_session`]
+pub struct SessionDevice<'s, T = f32> {
+    pub(crate) __marker: PhantomData<(&'s crate::Session, T)>,
+}
 /// A reference to a any tensor
 pub struct Ref<'t, T> {
     pub(crate) __marker: PhantomData<&'t [T]>,
@@ -118,12 +155,39 @@ pub struct RefMut<'t, T> {
 }
 
 /// A generic tensor that can of host / device / owned / borrowed
+///
+/// # Ownership
+/// Whether a tensor is freed on drop is part of its type. Tensors created by us
+/// ([`Tensor::new`], [`Tensor::create_device`], ...) are [`Host`] / [`Device`] tensors and are
+/// destroyed when dropped, [`SessionDevice`] tensors too but they borrow the session whose backend
+/// allocated them. Tensors MNN owns, like the inputs and outputs of a session, are
+/// [`Ref`] / [`RefMut`] tensors that are never destroyed by us and borrow the session they
+/// belong to, so they can't outlive it
+/// ```rust,compile_fail
+/// # use mnn::*;
+/// let net = Interpreter::from_file("tests/assets/realesr.mnn").unwrap();
+/// let session = net.create_session(ScheduleConfig::new()).unwrap();
+/// let input = net.input::<f32>(&session, "data").unwrap();
+/// drop(session);
+/// input.shape();
+/// ```
+///
+/// The same goes for borrowing an owned tensor with [`Tensor::as_ref`]
+/// ```rust,compile_fail
+/// # use mnn::*;
+/// let tensor = Tensor::<Host<f32>>::new([1, 4], DimensionType::NCHW);
+/// let borrowed = tensor.as_ref();
+/// drop(tensor);
+/// borrowed.shape();
+/// ```
 pub struct Tensor<T: TensorType> {
     pub(crate) tensor: *mut mnn_sys::Tensor,
     __marker: PhantomData<T>,
 }
 
 impl<T: TensorType> Drop for Tensor<T> {
+    /// Only owned tensors ([`Host`] / [`Device`]) are destroyed, [`Ref`] / [`RefMut`] tensors
+    /// (e.g. the inputs and outputs of a session) belong to someone else
     fn drop(&mut self) {
         if T::owned() {
             unsafe {
@@ -141,9 +205,149 @@ impl<H: HalideType> Tensor<Host<H>> {
             __marker: PhantomData,
         }
     }
+
+    /// Create a new host tensor with the specified shape with every element set to `value`
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// let bias = Tensor::from_scalar(0.5f32, [1, 16], DimensionType::NCHW).unwrap();
+    /// assert_eq!(bias.host(), [0.5; 16]);
+    /// ```
+    pub fn from_scalar(value: H, shape: impl AsTensorShape, dm_type: DimensionType) -> Result<Self>
+    where
+        H: Copy,
+    {
+        let shape = shape.as_tensor_shape();
+        crate::ensure!(
+            shape.as_ref().iter().all(|&dim| dim > 0),
+            ErrorKind::TensorError;
+            format!("Invalid shape {shape:?}")
+        );
+        let mut tensor = Self::new(shape, dm_type);
+        tensor.try_fill(value)?;
+        Ok(tensor)
+    }
+
+    /// Create a new host tensor from little endian bytes (e.g. written by [`Tensor::to_bytes_le`])
+    ///
+    /// `bytes` must hold exactly one element of type `H` per element of `shape`
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// let tensor = Tensor::<Host<u16>>::from_bytes_le(&[1, 0, 0, 1], [2], DimensionType::NCHW).unwrap();
+    /// assert_eq!(tensor.host(), [1, 256]);
+    /// ```
+    pub fn from_bytes_le(
+        bytes: &[u8],
+        shape: impl AsTensorShape,
+        dm_type: DimensionType,
+    ) -> Result<Self> {
+        Self::from_bytes_endian(bytes, shape, dm_type, true)
+    }
+
+    /// Create a new host tensor from big endian bytes (e.g. written by [`Tensor::to_bytes_be`])
+    ///
+    /// `bytes` must hold exactly one element of type `H` per element of `shape`
+    pub fn from_bytes_be(
+        bytes: &[u8],
+        shape: impl AsTensorShape,
+        dm_type: DimensionType,
+    ) -> Result<Self> {
+        Self::from_bytes_endian(bytes, shape, dm_type, false)
+    }
+
+    fn from_bytes_endian(
+        bytes: &[u8],
+        shape: impl AsTensorShape,
+        dm_type: DimensionType,
+        little_endian: bool,
+    ) -> Result<Self> {
+        let shape = shape.as_tensor_shape();
+        crate::ensure!(
+            shape.as_ref().iter().all(|&dim| dim > 0),
+            ErrorKind::TensorError;
+            format!("Invalid shape {shape:?}")
+        );
+        let width = core::mem::size_of::<H>();
+        let mut tensor = Self::new(shape, dm_type);
+        let expected = tensor.element_size() * width;
+        crate::ensure!(
+            bytes.len() == expected,
+            ErrorKind::SizeMismatch {
+                expected,
+                got: bytes.len(),
+            }
+        );
+        let halide_type = H::halide_type_of();
+        // Anything but 0 / 1 isn't a valid bool
+        crate::ensure!(
+            !(halide_type.code == halide_type_code_t::halide_type_uint && halide_type.bits == 1)
+                || bytes.iter().all(|&b| b <= 1),
+            ErrorKind::TensorError;
+            "Invalid bool value"
+        );
+        let host = tensor.try_host_mut()?;
+        // SAFETY: the host memory holds `expected` bytes and the values were checked above
+        let data =
+            unsafe { core::slice::from_raw_parts_mut(host.as_mut_ptr().cast::<u8>(), expected) };
+        data.copy_from_slice(bytes);
+        if little_endian != cfg!(target_endian = "little") {
+            swap_bytes(data, width);
+        }
+        Ok(tensor)
+    }
+}
+
+/// Reverse the bytes of every `width` byte wide element of `data`
+fn swap_bytes(data: &mut [u8], width: usize) {
+    if width > 1 {
+        data.chunks_exact_mut(width).for_each(<[u8]>::reverse);
+    }
 }
 
 impl<H: HalideType> Tensor<Device<H>> {
+    /// Create a new device tensor with the specified shape and dimension type
+    ///
+    /// The memory of a device tensor is owned by the backend so it can't be accessed directly,
+    /// the data has to be staged through a host tensor with
+    /// [`copy_from_host_tensor`](Tensor::copy_from_host_tensor) and
+    /// [`copy_to_host_tensor`](Tensor::copy_to_host_tensor).
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// let device = Tensor::<Device<f32>>::create_device([1, 3, 4, 4], DimensionType::NCHW).unwrap();
+    /// let host = device.create_host_tensor_from_device(false);
+    /// assert_eq!(host.shape().dims(), device.shape().dims());
+    /// ```
+    ///
+    /// Accessing the data of a device tensor doesn't compile
+    /// ```rust,compile_fail
+    /// # use mnn::*;
+    /// let device = Tensor::<Device<f32>>::create_device([1, 3, 4, 4], DimensionType::NCHW).unwrap();
+    /// device.host();
+    /// ```
+    pub fn create_device(shape: impl AsTensorShape, dm_type: DimensionType) -> Result<Self> {
+        let shape = shape.as_tensor_shape();
+        let tensor = unsafe {
+            Tensor_createDevice(
+                shape.shape.as_ptr(),
+                shape.size,
+                halide_type_of::<H>(),
+                dm_type.to_mnn_sys(),
+            )
+        };
+        crate::ensure!(
+            !tensor.is_null(),
+            ErrorKind::NullResult {
+                op: "Tensor_createDevice"
+            }
+        );
+        Ok(Self {
+            tensor,
+            __marker: PhantomData,
+        })
+    }
+
     /// Get's a reference to an owned device tensor
     pub fn as_ref(&self) -> Tensor<Ref<'_, Device<H>>> {
         Tensor {
@@ -151,6 +355,138 @@ impl<H: HalideType> Tensor<Device<H>> {
             __marker: PhantomData,
         }
     }
+
+    /// Wrap memory allocated outside of MNN on the device of `backend` (e.g. a `cl_mem` for
+    /// OpenCL or an `id<MTLBuffer>` for Metal)
+    ///
+    /// The tensor doesn't belong to a session, use it with [`copy_from`](Tensor::copy_from) to
+    /// feed it to the input of a session on the same backend or to read an output into it. The
+    /// backend copies directly between the two device buffers, converting the layout if needed.
+    /// Whether a backend accepts external memory depends on MNN (OpenCL and Metal do).
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// # let cl_mem: *mut libc::c_void = core::ptr::null_mut();
+    /// let interpreter = Interpreter::from_file("model.mnn").unwrap();
+    /// let session = interpreter
+    ///     .create_session(ScheduleConfig::new().with_type(ForwardType::OpenCL))
+    ///     .unwrap();
+    /// let external = unsafe {
+    ///     Tensor::<Device<f32>>::wrap_device(
+    ///         cl_mem,
+    ///         [1, 3, 224, 224],
+    ///         DimensionType::NCHW,
+    ///         ForwardType::OpenCL,
+    ///     )
+    /// }
+    /// .unwrap();
+    /// let mut input = interpreter.input::<f32>(&session, "input").unwrap();
+    /// input.copy_from(&external).unwrap();
+    /// interpreter.run_session(&session).unwrap();
+    /// ```
+    ///
+    /// # Safety
+    /// - `handle` has to be a valid buffer of `backend` holding at least the elements of `shape`
+    ///   in the `dm_type` layout, for the element type `H`
+    /// - The buffer has to outlive the tensor and any copy from / to it that is still pending on
+    ///   the device (see [`Tensor::wait`])
+    /// - The tensor never frees the buffer, it stays owned by the caller
+    pub unsafe fn wrap_device(
+        handle: *const libc::c_void,
+        shape: impl AsTensorShape,
+        dm_type: DimensionType,
+        backend: crate::ForwardType,
+    ) -> Result<Self> {
+        crate::ensure!(
+            !handle.is_null(),
+            ErrorKind::TensorError;
+            "Can't wrap a null device buffer"
+        );
+        let tensor = Self::create_device(shape, dm_type)?;
+        let ret = unsafe {
+            Tensor_setDevicePtr(tensor.tensor, handle, backend.to_mnn_sys() as libc::c_int)
+        };
+        crate::ensure!(
+            ret != 0,
+            ErrorKind::TensorError;
+            format!("MNN doesn't accept external memory for the {backend:?} backend")
+        );
+        Ok(tensor)
+    }
+}
+
+impl<'s, H: HalideType> Tensor<SessionDevice<'s, H>> {
+    /// Create a device tensor allocated by the backend of the input `input` of `session`, with
+    /// its current shape, type and layout
+    ///
+    /// Unlike [`create_device`](Tensor::create_device) the tensor has memory, so it can be filled
+    /// once (e.g. with [`copy_from_host_tensor`](Tensor::copy_from_host_tensor)) and copied into
+    /// the input with [`copy_from`](Tensor::copy_from) by the backend itself, without staging the
+    /// data through the host each time. The memory belongs to the backend of the session, so the
+    /// tensor borrows the session.
+    ///
+    /// The tensor always follows an input of the session, there's no variant taking a shape and
+    /// a [`DimensionType`]. Resize the session first for another shape.
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let interpreter = Interpreter::from_file("model.mnn").unwrap();
+    /// let session = interpreter
+    ///     .create_session(ScheduleConfig::new().with_type(ForwardType::OpenCL))
+    ///     .unwrap();
+    /// let mut staged =
+    ///     Tensor::<SessionDevice<f32>>::create_for_session(&session, "input").unwrap();
+    /// let mut host = staged.create_host_tensor_from_device(false);
+    /// host.fill(0.5);
+    /// staged.copy_from_host_tensor(&host).unwrap();
+    /// for _ in 0..10 {
+    ///     let mut input = interpreter.input::<f32>(&session, "input").unwrap();
+    ///     input.copy_from(&staged).unwrap();
+    ///     interpreter.run_session(&session).unwrap();
+    /// }
+    /// ```
+    ///
+    /// The tensor can't outlive the session
+    /// ```rust,compile_fail
+    /// # use mnn::*;
+    /// let interpreter = Interpreter::from_file("tests/assets/realesr.mnn").unwrap();
+    /// let session = interpreter.create_session(ScheduleConfig::new()).unwrap();
+    /// let staged = Tensor::<SessionDevice<f32>>::create_for_session(&session, "data").unwrap();
+    /// drop(session);
+    /// staged.shape();
+    /// ```
+    pub fn create_for_session(session: &'s crate::Session, input: impl AsRef<str>) -> Result<Self> {
+        let name = input.as_ref();
+        let c_name = std::ffi::CString::new(name).change_context(ErrorKind::AsciiError)?;
+        let like =
+            unsafe { Interpreter_getSessionInput(session.net, session.inner, c_name.as_ptr()) };
+        crate::ensure!(
+            !like.is_null(),
+            ErrorKind::TensorError;
+            format!("Input tensor \"{name}\" not found")
+        );
+        let shape: TensorShape = unsafe { Tensor_shape(like) }.into();
+        crate::ensure!(!shape.as_ref().contains(&-1), ErrorKind::DynamicTensorError);
+        crate::ensure!(
+            unsafe { Tensor_isTypeOf(like, halide_type_of::<H>()) },
+            ErrorKind::HalideTypeMismatch {
+                got: std::any::type_name::<H>(),
+            };
+            format!("Input tensor \"{name}\" is not of type {}", std::any::type_name::<H>())
+        );
+        let tensor =
+            unsafe { Interpreter_createTensorOnBackendOf(session.net, session.inner, like) };
+        crate::ensure!(
+            !tensor.is_null(),
+            ErrorKind::NullResult {
+                op: "Interpreter_createTensorOnBackendOf"
+            }
+        );
+        Ok(Self {
+            tensor,
+            __marker: PhantomData,
+        })
+    }
 }
 
 /// The type of the tensor dimension  
@@ -211,19 +547,46 @@ where
         }
     }
     /// Copies the data from a host tensor to the self tensor
+    ///
+    /// A device tensor needs to belong to a backend (e.g. be an input or output of a session),
+    /// a tensor from [`create_device`](Tensor::create_device) has no memory to copy to.
+    /// See `benches/tensor_copy.rs` for the throughput of the copies on each backend.
     pub fn copy_from_host_tensor(&mut self, tensor: &Tensor<Host<T::H>>) -> Result<()> {
+        crate::ensure!(
+            self.element_size() == tensor.element_size(),
+            ErrorKind::ShapeMismatch {
+                expected: self.shape().dims(),
+                got: tensor.shape().dims(),
+            }
+        );
         let ret = unsafe { Tensor_copyFromHostTensor(self.tensor, tensor.tensor) };
         crate::ensure!(ret != 0, ErrorKind::TensorCopyFailed(ret));
         Ok(())
     }
 
     /// Copies the data from the self tensor to a host tensor
+    ///
+    /// Like [`copy_from_host_tensor`](Tensor::copy_from_host_tensor) a device tensor needs to
+    /// belong to a backend.
     pub fn copy_to_host_tensor(&self, tensor: &mut Tensor<Host<T::H>>) -> Result<()> {
+        crate::ensure!(
+            self.element_size() == tensor.element_size(),
+            ErrorKind::ShapeMismatch {
+                expected: self.shape().dims(),
+                got: tensor.shape().dims(),
+            }
+        );
         let ret = unsafe { Tensor_copyToHostTensor(self.tensor, tensor.tensor) };
         crate::ensure!(ret != 0, ErrorKind::TensorCopyFailed(ret));
         Ok(())
     }
 
+    /// Whether the tensor wraps external device memory ([`Tensor::wrap_device`])
+    fn is_wrapped_device(&self) -> bool {
+        // MNN keeps the memory type given to setDevicePtr in the flags, 0 is the cpu
+        unsafe { (*Tensor_buffer(self.tensor)).flags != 0 }
+    }
+
     /// Get the device id of the tensor
     pub fn device_id(&self) -> u64 {
         unsafe { Tensor_deviceId(self.tensor) }
@@ -259,13 +622,31 @@ where
         unsafe { Tensor_batch(self.tensor) as u32 }
     }
 
-    /// Get the size of the tensor when counted by bytes
+    /// Get the size of the tensor when counted by bytes (see [`Tensor::byte_size`])
+    ///
+    /// Use [`Tensor::element_count`] for the length of the host slice
     pub fn size(&self) -> usize {
         unsafe { Tensor_usize(self.tensor) }
     }
 
-    /// Get the size of the tensor when counted by elements
+    /// Get the size of the tensor when counted by elements (same as [`Tensor::element_count`])
     pub fn element_size(&self) -> usize {
+        self.element_count()
+    }
+
+    /// Get the number of elements of the tensor (the product of its shape)
+    ///
+    /// This is the length of the host slice (e.g. [`Tensor::host`]), not the size of the buffer in
+    /// bytes which is [`Tensor::byte_size`]. For [packed](Tensor::is_packed) tensors this doesn't
+    /// count the padding of the channels, so it's less than `byte_size / size_of::<T::H>()`.
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// let tensor = Tensor::<Host<f32>>::new([2, 3], DimensionType::NCHW);
+    /// assert_eq!(tensor.element_count(), 6);
+    /// assert_eq!(tensor.byte_size(), 6 * size_of::<f32>());
+    /// ```
+    pub fn element_count(&self) -> usize {
         unsafe { Tensor_elementSize(self.tensor) as usize }
     }
 
@@ -302,23 +683,122 @@ where
         unsafe { Tensor_buffer_mut(self.tensor) }
     }
 
+    /// Get the raw pointer to the host memory of the tensor
+    ///
+    /// Returns `None` if the tensor doesn't have host memory (e.g. device tensors that aren't
+    /// mapped). [`Tensor::try_host`] and the other safe host accessors build on this.
+    ///
+    /// # Safety
+    /// The pointer is only valid while the tensor is alive and isn't resized (or its session
+    /// resized / released). The memory holds [`Tensor::element_size`] elements of the halide type
+    /// of the tensor in its dimension type's layout, and it must not be written through while a
+    /// slice of the tensor (e.g. from [`Tensor::host`]) is alive.
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// let tensor = Tensor::from_scalar(1.5f32, [1, 4], DimensionType::NCHW).unwrap();
+    /// // SAFETY: the tensor is a 4 element f32 host tensor that outlives the slice
+    /// let data = unsafe {
+    ///     let ptr = tensor.raw_host().expect("No host memory");
+    ///     core::slice::from_raw_parts(ptr.as_ptr().cast::<f32>(), tensor.element_size())
+    /// };
+    /// assert_eq!(data, [1.5; 4]);
+    /// assert_eq!(unsafe { tensor.raw_device() }, None);
+    /// ```
+    pub unsafe fn raw_host(&self) -> Option<core::ptr::NonNull<core::ffi::c_void>> {
+        core::ptr::NonNull::new(unsafe { Tensor_host(self.tensor) }.cast_mut())
+    }
+
+    /// Get the raw device handle of the tensor
+    ///
+    /// Returns `None` for tensors without device memory (e.g. host tensors). What the handle is
+    /// depends on the backend, e.g. a `cl_mem` for OpenCL or a device pointer for CUDA.
+    ///
+    /// # Safety
+    /// The handle is only valid while the tensor is alive and isn't resized (or its session
+    /// resized / released), and MNN may still be using the memory asynchronously, wait for the
+    /// tensor (e.g. with [`Tensor::wait`]) before accessing it.
+    pub unsafe fn raw_device(&self) -> Option<u64> {
+        let device = unsafe { Tensor_deviceId(self.tensor) };
+        (device != 0).then_some(device)
+    }
+
     /// Get the dimension type of the tensor
     pub fn get_dimension_type(&self) -> DimensionType {
         debug_assert!(!self.tensor.is_null());
         From::from(unsafe { Tensor_getDimensionType(self.tensor) })
     }
 
+    /// Get the dimension type of the tensor (same as [`Tensor::get_dimension_type`])
+    pub fn dimension_type(&self) -> DimensionType {
+        self.get_dimension_type()
+    }
+
+    /// Check if the channels of the tensor are packed by 4 ([`DimensionType::NC4HW4`])
+    ///
+    /// The memory of packed tensors doesn't follow [`Tensor::shape`] / [`Tensor::strides`],
+    /// the channels are split in groups of 4 (padded with zeros) with the batch, group, height,
+    /// width and the 4 channels of the group from the outermost to the innermost.
+    /// Copy them to a NCHW / NHWC host tensor with [`Tensor::copy_to_host_tensor`] before reading
+    /// the raw buffer.
+    pub fn is_packed(&self) -> bool {
+        self.get_dimension_type() == DimensionType::NC4HW4
+    }
+
+    /// Get the strides of the tensor in bytes, one per dimension of [`Tensor::shape`]
+    ///
+    /// For [packed](Tensor::is_packed) tensors these are the strides of the unpacked shape and
+    /// don't describe the memory of the tensor.
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// let tensor = Tensor::<Host<f32>>::new([1, 3, 4, 5], DimensionType::NCHW);
+    /// assert_eq!(tensor.strides(), [240, 80, 20, 4]);
+    /// ```
+    pub fn strides(&self) -> Vec<usize> {
+        let halide_type = self.get_type();
+        let bytes = (halide_type.bits as usize).div_ceil(8) * halide_type.lanes as usize;
+        (0..self.dimensions())
+            .map(|index| unsafe { Tensor_stride(self.tensor, index as i32) } as usize * bytes)
+            .collect()
+    }
+
+    /// Get the size of the buffer of the tensor in bytes (same as [`Tensor::size`])
+    ///
+    /// This includes the padding of the channels for [packed](Tensor::is_packed) tensors, use
+    /// [`Tensor::element_count`] for the number of elements
+    pub fn byte_size(&self) -> usize {
+        self.size()
+    }
+
     /// Get the data type of the tensor
     pub fn get_type(&self) -> mnn_sys::halide_type_t {
         unsafe { Tensor_getType(self.tensor) }
     }
 
+    /// Get the data type of the tensor as a [`DataType`]
+    pub fn data_type(&self) -> DataType {
+        self.get_type().into()
+    }
+
     /// Check if the tensor is of the specified data type
     pub fn is_type_of<H: HalideType>(&self) -> bool {
         let htc = halide_type_of::<H>();
         unsafe { Tensor_isTypeOf(self.tensor, htc) }
     }
 
+    /// Get the quantization parameters of the tensor (only set for quantized tensors)
+    pub fn quant_params(&self) -> Option<QuantParams> {
+        let mut params = mnn_sys::QuantParams {
+            scale: 0.0,
+            zero: 0.0,
+            min: 0.0,
+            max: 0.0,
+        };
+        let ret = unsafe { Tensor_getQuantParams(self.tensor, &mut params) };
+        (ret == 1).then_some(params.into())
+    }
+
     /// # Safety
     /// This is very unsafe do not use this unless you know what you are doing
     pub unsafe fn into_raw(self) -> RawTensor<'static> {
@@ -334,13 +814,74 @@ impl<T: MutableTensorType> Tensor<T>
 where
     T::H: HalideType,
 {
+    /// Copies the data of any tensor (host or device, of any backend) to the self tensor
+    ///
+    /// MNN copies directly if either tensor is in host memory (converting the layout if needed).
+    /// A copy between two device tensors (e.g. the output of an OpenCL session to the input of
+    /// a Vulkan session) is staged through a temporary host tensor since MNN has no direct path
+    /// between the memory of two backends, except for external memory from
+    /// [`Tensor::wrap_device`] which is copied by the backend of the other tensor. Two device
+    /// tensors of the same backend (e.g. from [`Tensor::create_for_session`]) are copied by it
+    /// directly.
+    ///
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let interpreter = Interpreter::from_file("model.mnn").unwrap();
+    /// let gpu = interpreter
+    ///     .create_session(ScheduleConfig::new().with_type(ForwardType::OpenCL))
+    ///     .unwrap();
+    /// let cpu = interpreter.create_session(ScheduleConfig::new()).unwrap();
+    /// interpreter.run_session(&gpu).unwrap();
+    /// let output = interpreter.output::<f32>(&gpu, "output").unwrap();
+    /// let mut input = interpreter.input::<f32>(&cpu, "input").unwrap();
+    /// input.copy_from(&output).unwrap();
+    /// ```
+    pub fn copy_from<U: TensorType<H = T::H>>(&mut self, tensor: &Tensor<U>) -> Result<()> {
+        crate::ensure!(
+            self.element_size() == tensor.element_size(),
+            ErrorKind::ShapeMismatch {
+                expected: self.shape().dims(),
+                got: tensor.shape().dims(),
+            }
+        );
+        let ret = unsafe {
+            if !Tensor_host(tensor.tensor).is_null() {
+                Tensor_copyFromHostTensor(self.tensor, tensor.tensor)
+            } else if !Tensor_host(self.tensor).is_null() {
+                Tensor_copyToHostTensor(tensor.tensor, self.tensor)
+            } else if tensor.is_wrapped_device() {
+                // The backend of the session tensor copies from the external memory
+                Tensor_copyFromHostTensor(self.tensor, tensor.tensor)
+            } else if self.is_wrapped_device() {
+                Tensor_copyToHostTensor(tensor.tensor, self.tensor)
+            } else if Tensor_copyOnSameBackend(tensor.tensor, self.tensor) != 0 {
+                // e.g. a tensor from `Tensor::create_for_session` to the input of the session
+                1
+            } else {
+                let staging =
+                    Tensor::<Host<T::H>>::new(tensor.shape(), tensor.get_dimension_type());
+                let ret = Tensor_copyToHostTensor(tensor.tensor, staging.tensor);
+                crate::ensure!(ret != 0, ErrorKind::TensorCopyFailed(ret));
+                Tensor_copyFromHostTensor(self.tensor, staging.tensor)
+            }
+        };
+        crate::ensure!(ret != 0, ErrorKind::TensorCopyFailed(ret));
+        Ok(())
+    }
+
+    /// Set the quantization parameters of the tensor
+    pub fn set_quant_params(&mut self, params: QuantParams) {
+        let params = mnn_sys::QuantParams::from(params);
+        unsafe { Tensor_setQuantParams(self.tensor, &params) }
+    }
+
     /// Fill the tensor with the specified value
     pub fn fill(&mut self, value: T::H)
     where
         T::H: Copy,
     {
         if T::host() {
-            let size = self.element_size();
+            let size = self.element_count();
             assert!(self.is_type_of::<T::H>());
             let result: &mut [T::H] = unsafe {
                 let data = mnn_sys::Tensor_host_mut(self.tensor).cast();
@@ -365,24 +906,26 @@ where
     T::H: HalideType,
 {
     /// Try to map the device tensor to the host memory and get the slice
+    ///
+    /// The slice has [`Tensor::element_count`] elements
     pub fn try_host(&self) -> Result<&[T::H]> {
-        let size = self.element_size();
+        let size = self.element_count();
         ensure!(
             self.is_type_of::<T::H>(),
             ErrorKind::HalideTypeMismatch {
                 got: std::any::type_name::<T::H>(),
             }
         );
-        let result = unsafe {
-            let data = mnn_sys::Tensor_host(self.tensor).cast();
-            core::slice::from_raw_parts(data, size)
-        };
+        let data = unsafe { self.raw_host() }.ok_or_else(|| {
+            error!(ErrorKind::TensorError).attach_printable("Tensor doesn't have host memory")
+        })?;
+        let result = unsafe { core::slice::from_raw_parts(data.as_ptr().cast(), size) };
         Ok(result)
     }
 
     /// Try to map the device tensor to the host memory and get the mutable slice
     pub fn try_host_mut(&mut self) -> Result<&mut [T::H]> {
-        let size = self.element_size();
+        let size = self.element_count();
         ensure!(
             self.is_type_of::<T::H>(),
             ErrorKind::HalideTypeMismatch {
@@ -390,11 +933,9 @@ where
             }
         );
 
-        let result = unsafe {
-            let data: *mut T::H = mnn_sys::Tensor_host_mut(self.tensor).cast();
-            debug_assert!(!data.is_null());
-            core::slice::from_raw_parts_mut(data, size)
-        };
+        let data = unsafe { mnn_sys::Tensor_host_mut(self.tensor) };
+        ensure!(!data.is_null(), ErrorKind::TensorError; "Tensor doesn't have host memory");
+        let result = unsafe { core::slice::from_raw_parts_mut(data.cast(), size) };
         Ok(result)
     }
 
@@ -407,6 +948,194 @@ where
     pub fn host_mut(&mut self) -> &mut [T::H] {
         self.try_host_mut().expect("Failed to get tensor host_mut")
     }
+
+    /// Iterate over the slices of the host memory along the first dimension
+    ///
+    /// Each slice holds the elements of one index of the first dimension (e.g. one row of a
+    /// `[rows, columns]` tensor or one batch of a `[batch, channels, height, width]` tensor).
+    /// Returns an error for [packed](Tensor::is_packed) tensors and 0-dimensional tensors.
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// let data = [0.9, 0.1, 0.2, 0.8, 0.5, 0.5];
+    /// let scores = Tensor::<Ref<Host<f32>>>::borrowed([3, 2], &data);
+    /// let best = scores
+    ///     .outer_iter()
+    ///     .unwrap()
+    ///     .map(|row| if row[0] > row[1] { 0 } else { 1 })
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(best, [0, 1, 1]);
+    /// ```
+    pub fn outer_iter(&self) -> Result<impl ExactSizeIterator<Item = &[T::H]>> {
+        ensure!(!self.is_packed(), ErrorKind::TensorError; "Packed (NC4HW4) tensors can't be sliced");
+        let shape = self.shape();
+        ensure!(
+            shape.size > 0,
+            ErrorKind::TensorError;
+            "0-dimensional tensors don't have a first dimension"
+        );
+        let outer = shape[0].max(0) as usize;
+        let inner = shape
+            .iter()
+            .skip(1)
+            .map(|&dim| dim.max(0) as usize)
+            .product();
+        let data = self.try_host()?;
+        Ok((0..outer).map(move |index| &data[index * inner..(index + 1) * inner]))
+    }
+}
+
+impl<T: HostTensorType + MutableTensorType> Tensor<T>
+where
+    T::H: HalideType + Copy,
+{
+    /// Fill the host memory of the tensor with the specified value
+    ///
+    /// Unlike [`Tensor::fill`] this returns an error instead of panicking if the tensor isn't of
+    /// type `T::H` or doesn't have host memory.
+    ///
+    /// Device tensors have to be filled with [`Tensor::fill`] which stages the value through a
+    /// host tensor
+    /// ```rust,compile_fail
+    /// # use mnn::*;
+    /// let mut device = Tensor::<Device<f32>>::create_device([1, 4], DimensionType::NCHW).unwrap();
+    /// device.try_fill(1.0);
+    /// ```
+    pub fn try_fill(&mut self, value: T::H) -> Result<()> {
+        self.try_host_mut()?.fill(value);
+        Ok(())
+    }
+
+    /// Set the host memory of the tensor to zero
+    pub fn zero(&mut self) -> Result<()> {
+        let data = self.try_host_mut()?;
+        unsafe { core::ptr::write_bytes(data.as_mut_ptr(), 0, data.len()) };
+        Ok(())
+    }
+}
+
+impl<T: HostTensorType> Tensor<T>
+where
+    T::H: HalideType + Copy,
+{
+    /// Copy the tensor to a new host tensor with its dimensions ordered as `to`
+    ///
+    /// Only 4 dimensional NCHW <-> NHWC tensors can be converted (converting to the same layout
+    /// copies the tensor). Packed ([`DimensionType::NC4HW4`]) tensors return an error, copy them
+    /// to a NCHW / NHWC host tensor with [`Tensor::copy_to_host_tensor`] first.
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// // 1x2x2 image with 3 channels
+    /// let data = [0.0f32, 1.0, 2.0, 10.0, 11.0, 12.0, 20.0, 21.0, 22.0, 30.0, 31.0, 32.0];
+    /// let mut nhwc = Tensor::<Host<f32>>::new([1, 2, 2, 3], DimensionType::NHWC);
+    /// nhwc.host_mut().copy_from_slice(&data);
+    /// let nchw = nhwc.convert_layout(DimensionType::NCHW).unwrap();
+    /// assert_eq!(nchw.shape().as_ref(), [1, 3, 2, 2]);
+    /// assert_eq!(nchw.host()[..4], [0.0, 10.0, 20.0, 30.0]);
+    /// ```
+    pub fn convert_layout(&self, to: DimensionType) -> Result<Tensor<Host<T::H>>> {
+        let from = self.get_dimension_type();
+        let shape = self.shape();
+        crate::ensure!(
+            from != DimensionType::NC4HW4 && to != DimensionType::NC4HW4,
+            ErrorKind::TensorError;
+            format!("Can't convert the layout from {from:?} to {to:?}"),
+            "Packed (NC4HW4) tensors aren't supported"
+        );
+        crate::ensure!(
+            !self.is_dynamic_unsized(),
+            ErrorKind::DynamicTensorError;
+            format!("Can't convert the layout of a tensor with shape {shape:?}")
+        );
+        let host = self.try_host()?;
+        if from == to {
+            let mut tensor = Tensor::<Host<T::H>>::new(shape, to);
+            tensor.try_host_mut()?.copy_from_slice(host);
+            return Ok(tensor);
+        }
+        let dims = shape.dims();
+        crate::ensure!(
+            dims.len() == 4,
+            ErrorKind::TensorError;
+            format!("Can't convert the layout from {from:?} to {to:?} of a tensor with shape {shape:?}"),
+            "Only 4 dimensional tensors are supported"
+        );
+        // Dimension i of the converted tensor is dimension perm[i] of this one
+        let perm = match to {
+            DimensionType::NHWC => [0, 2, 3, 1],
+            _ => [0, 3, 1, 2],
+        };
+        let in_strides = [dims[1] * dims[2] * dims[3], dims[2] * dims[3], dims[3], 1];
+        let out_shape = perm.map(|p| dims[p]);
+        let strides = perm.map(|p| in_strides[p]);
+        let mut tensor = Tensor::<Host<T::H>>::new(out_shape.map(|d| d as i32), to);
+        let mut out = tensor.try_host_mut()?.iter_mut();
+        for i0 in 0..out_shape[0] {
+            for i1 in 0..out_shape[1] {
+                for i2 in 0..out_shape[2] {
+                    for i3 in 0..out_shape[3] {
+                        let offset =
+                            i0 * strides[0] + i1 * strides[1] + i2 * strides[2] + i3 * strides[3];
+                        if let Some(value) = out.next() {
+                            *value = host[offset];
+                        }
+                    }
+                }
+            }
+        }
+        Ok(tensor)
+    }
+
+    /// Copy the host memory to little endian bytes, independent of the platform
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// let tensor = Tensor::from_scalar(1u16, [2], DimensionType::NCHW).unwrap();
+    /// assert_eq!(tensor.to_bytes_le().unwrap(), [1, 0, 1, 0]);
+    /// assert_eq!(tensor.to_bytes_be().unwrap(), [0, 1, 0, 1]);
+    /// ```
+    pub fn to_bytes_le(&self) -> Result<Vec<u8>> {
+        self.to_bytes_endian(true)
+    }
+
+    /// Copy the host memory to big endian bytes, independent of the platform
+    pub fn to_bytes_be(&self) -> Result<Vec<u8>> {
+        self.to_bytes_endian(false)
+    }
+
+    fn to_bytes_endian(&self, little_endian: bool) -> Result<Vec<u8>> {
+        let host = self.try_host()?;
+        let width = core::mem::size_of::<T::H>();
+        // SAFETY: any initialized memory can be read as bytes
+        let mut bytes = unsafe {
+            core::slice::from_raw_parts(host.as_ptr().cast::<u8>(), core::mem::size_of_val(host))
+        }
+        .to_vec();
+        if little_endian != cfg!(target_endian = "little") {
+            swap_bytes(&mut bytes, width);
+        }
+        Ok(bytes)
+    }
+}
+
+impl<T: HostTensorType> Tensor<T>
+where
+    T::H: HalideType + Copy + Into<f32>,
+{
+    /// Dequantize the tensor to `f32` using its [`QuantParams`]
+    ///
+    /// Returns an error if the tensor doesn't have quantization parameters
+    pub fn dequantize(&self) -> Result<Vec<f32>> {
+        let params = self.quant_params().ok_or_else(|| {
+            error!(ErrorKind::TensorError).attach_printable("Tensor has no quantization parameters")
+        })?;
+        Ok(self
+            .try_host()?
+            .iter()
+            .map(|&q| (q.into() - params.zero_point) * params.scale)
+            .collect())
+    }
 }
 
 impl<T: DeviceTensorType> Tensor<T>
@@ -420,12 +1149,33 @@ where
         }
     }
 
+    /// Copy the device tensor to a new host tensor with the same dimensions and data type
+    ///
+    /// Device tensors don't expose their memory, so their data is read through a host tensor
+    /// ```rust,no_run
+    /// # use mnn::*;
+    /// let interpreter = Interpreter::from_file("model.mnn").unwrap();
+    /// let session = interpreter.create_session(ScheduleConfig::new()).unwrap();
+    /// interpreter.run_session(&session).unwrap();
+    /// let output = interpreter.output::<f32>(&session, "output").unwrap();
+    /// let output = output.to_host().unwrap();
+    /// let data: &[f32] = output.host();
+    /// ```
+    pub fn to_host(&self) -> Result<Tensor<Host<T::H>>> {
+        let mut out = self.create_host_tensor_from_device(false);
+        self.copy_to_host_tensor(&mut out)?;
+        Ok(out)
+    }
+
     /// Create a host tensor from the device tensor with same dimensions and data type and
     /// optionally copy the data from the device tensor
     pub fn create_host_tensor_from_device(&self, copy_data: bool) -> Tensor<Host<T::H>> {
         let shape = self.shape();
         let dm_type = self.get_dimension_type();
         let mut out = Tensor::new(shape, dm_type);
+        if let Some(params) = self.quant_params() {
+            out.set_quant_params(params);
+        }
 
         if copy_data {
             self.copy_to_host_tensor(&mut out)
@@ -460,7 +1210,10 @@ where
                 )
             }
         };
-        debug_assert!(!tensor.is_null());
+        assert!(
+            !tensor.is_null(),
+            "Failed to create tensor: MNN returned null"
+        );
         Self {
             tensor,
             __marker: PhantomData,
@@ -474,6 +1227,10 @@ where
 {
     fn clone(&self) -> Tensor<T> {
         let tensor_ptr = unsafe { Tensor_clone(self.tensor) };
+        assert!(
+            !tensor_ptr.is_null(),
+            "Failed to clone tensor: MNN returned null"
+        );
         Self {
             tensor: tensor_ptr,
             __marker: PhantomData,
@@ -481,6 +1238,282 @@ where
     }
 }
 
+impl<T: TensorType> core::fmt::Debug for Tensor<T>
+where
+    T::H: HalideType,
+{
+    /// Prints the shape, dimension type, data type and the first 8 values of host tensors
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.fmt_preview(f, 8)
+    }
+}
+
+impl<T: TensorType> Tensor<T>
+where
+    T::H: HalideType,
+{
+    /// Format the shape, dimension type, data type and up to `n` leading values of the tensor
+    ///
+    /// Only the values of host tensors are printed, device tensors show `<device>` instead.
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// let tensor = Tensor::from_scalar(1.5f32, [1, 2, 3], DimensionType::NCHW).unwrap();
+    /// let preview = tensor.to_string_preview(2);
+    /// assert!(preview.contains("shape: [1, 2, 3]"));
+    /// assert!(preview.contains("values: [1.5, 1.5, ..]"));
+    /// ```
+    pub fn to_string_preview(&self, n: usize) -> String {
+        struct Preview<'t, T: TensorType>(&'t Tensor<T>, usize);
+        impl<T: TensorType> core::fmt::Debug for Preview<'_, T>
+        where
+            T::H: HalideType,
+        {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                self.0.fmt_preview(f, self.1)
+            }
+        }
+        format!("{:?}", Preview(self, n))
+    }
+
+    fn fmt_preview(&self, f: &mut core::fmt::Formatter<'_>, n: usize) -> core::fmt::Result {
+        /// Writes the string as is instead of quoting it
+        struct Raw(String);
+        impl core::fmt::Debug for Raw {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+        let halide_type = self.get_type();
+        let values = if T::host() {
+            self.preview_values(n)
+        } else {
+            "<device>".to_string()
+        };
+        f.debug_struct("Tensor")
+            .field("shape", &self.shape())
+            .field("dimension_type", &self.get_dimension_type())
+            .field(
+                "type",
+                &Raw(format!("{:?}({})", halide_type.code, halide_type.bits)),
+            )
+            .field("elements", &self.element_size())
+            .field("values", &Raw(values))
+            .finish()
+    }
+
+    /// The first `n` values of the host memory, formatted according to the halide type
+    fn preview_values(&self, n: usize) -> String {
+        let data = unsafe { Tensor_host(self.tensor) };
+        if data.is_null() {
+            return "<no host memory>".to_string();
+        }
+        let halide_type = self.get_type();
+        let bytes = (halide_type.bits as usize).div_ceil(8) * halide_type.lanes as usize;
+        let elements = self.element_size();
+        // Never read past the buffer even if the element count and the type disagree
+        let count = n.min(elements).min(self.size() / bytes.max(1));
+        macro_rules! values {
+            ($ty:ty) => {
+                values!($ty, |x: &$ty| format!("{x:?}"))
+            };
+            ($ty:ty, $format:expr) => {
+                unsafe { core::slice::from_raw_parts(data.cast::<$ty>(), count) }
+                    .iter()
+                    .map($format)
+                    .collect::<Vec<_>>()
+            };
+        }
+        use halide_type_code_t::*;
+        let values = match (halide_type.code, halide_type.bits) {
+            (halide_type_float, 32) => values!(f32),
+            (halide_type_float, 64) => values!(f64),
+            #[cfg(feature = "half")]
+            (halide_type_float, 16) => values!(half::f16),
+            #[cfg(not(feature = "half"))]
+            (halide_type_float, 16) => values!(u16, |x: &u16| format!("f16({x:#06x})")),
+            #[cfg(feature = "half")]
+            (halide_type_bfloat, 16) => values!(half::bf16),
+            #[cfg(not(feature = "half"))]
+            (halide_type_bfloat, 16) => values!(u16, |x: &u16| format!("bf16({x:#06x})")),
+            (halide_type_int, 8) => values!(i8),
+            (halide_type_int, 16) => values!(i16),
+            (halide_type_int, 32) => values!(i32),
+            (halide_type_int, 64) => values!(i64),
+            (halide_type_uint, 1) => values!(u8, |x: &u8| format!("{}", *x != 0)),
+            (halide_type_uint, 8) => values!(u8),
+            (halide_type_uint, 16) => values!(u16),
+            (halide_type_uint, 32) => values!(u32),
+            (halide_type_uint, 64) => values!(u64),
+            (code, bits) => return format!("<{bits} bit {code:?} values>"),
+        };
+        let more = if elements > count { ", .." } else { "" };
+        format!("[{}{more}]", values.join(", "))
+    }
+}
+
+/// Quantization parameters of an int8 / uint8 tensor
+///
+/// The real value of a quantized value `q` is `(q - zero_point) * scale`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuantParams {
+    /// Scale of the quantized values
+    pub scale: f32,
+    /// Zero point of the quantized values
+    pub zero_point: f32,
+    /// Minimum quantized value
+    pub min: f32,
+    /// Maximum quantized value
+    pub max: f32,
+}
+
+impl QuantParams {
+    /// Quantize `values` to int8, the inverse of [`Tensor::dequantize`]
+    ///
+    /// The values are rounded to the nearest quantized value and clamped to `min..=max` (or the
+    /// range of `i8` if `min` isn't below `max`).
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// let params = QuantParams { scale: 0.5, zero_point: 0.0, min: -127.0, max: 127.0 };
+    /// assert_eq!(params.quantize(&[1.0, -0.6, 1000.0]), [2, -1, 127]);
+    /// ```
+    pub fn quantize(&self, values: &[f32]) -> Vec<i8> {
+        let (min, max) = if self.min < self.max {
+            (self.min.max(i8::MIN as f32), self.max.min(i8::MAX as f32))
+        } else {
+            (i8::MIN as f32, i8::MAX as f32)
+        };
+        values
+            .iter()
+            .map(|&v| (v / self.scale + self.zero_point).round().clamp(min, max) as i8)
+            .collect()
+    }
+}
+
+impl From<mnn_sys::QuantParams> for QuantParams {
+    fn from(value: mnn_sys::QuantParams) -> Self {
+        Self {
+            scale: value.scale,
+            zero_point: value.zero,
+            min: value.min,
+            max: value.max,
+        }
+    }
+}
+
+impl From<QuantParams> for mnn_sys::QuantParams {
+    fn from(value: QuantParams) -> Self {
+        Self {
+            scale: value.scale,
+            zero: value.zero_point,
+            min: value.min,
+            max: value.max,
+        }
+    }
+}
+
+/// The element type of a tensor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataType {
+    /// `bool`
+    Bool,
+    /// `u8`
+    U8,
+    /// `u16`
+    U16,
+    /// `u32`
+    U32,
+    /// `u64`
+    U64,
+    /// `i8`
+    I8,
+    /// `i16`
+    I16,
+    /// `i32`
+    I32,
+    /// `i64`
+    I64,
+    /// `f16` (`half::f16` with the `half` feature)
+    F16,
+    /// `bf16` (`half::bf16` with the `half` feature)
+    BF16,
+    /// `f32`
+    F32,
+    /// `f64`
+    F64,
+    /// A type without a rust equivalent (e.g. handles)
+    Other {
+        /// The `halide_type_code_t` of the type
+        code: u8,
+        /// The width of the type in bits
+        bits: u8,
+    },
+}
+
+impl DataType {
+    /// The data type of `H`
+    pub fn of<H: HalideType>() -> Self {
+        halide_type_of::<H>().into()
+    }
+}
+
+impl From<mnn_sys::halide_type_t> for DataType {
+    fn from(value: mnn_sys::halide_type_t) -> Self {
+        use halide_type_code_t::*;
+        match (value.code, value.bits) {
+            (halide_type_uint, 1) => Self::Bool,
+            (halide_type_uint, 8) => Self::U8,
+            (halide_type_uint, 16) => Self::U16,
+            (halide_type_uint, 32) => Self::U32,
+            (halide_type_uint, 64) => Self::U64,
+            (halide_type_int, 8) => Self::I8,
+            (halide_type_int, 16) => Self::I16,
+            (halide_type_int, 32) => Self::I32,
+            (halide_type_int, 64) => Self::I64,
+            (halide_type_float, 16) => Self::F16,
+            (halide_type_bfloat, 16) => Self::BF16,
+            (halide_type_float, 32) => Self::F32,
+            (halide_type_float, 64) => Self::F64,
+            (code, bits) => Self::Other {
+                code: code as u8,
+                bits,
+            },
+        }
+    }
+}
+
+impl DataType {
+    /// The name of the rust type, `"other"` for [`DataType::Other`]
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Self::Bool => "bool",
+            Self::U8 => "u8",
+            Self::U16 => "u16",
+            Self::U32 => "u32",
+            Self::U64 => "u64",
+            Self::I8 => "i8",
+            Self::I16 => "i16",
+            Self::I32 => "i32",
+            Self::I64 => "i64",
+            Self::F16 => "f16",
+            Self::BF16 => "bf16",
+            Self::F32 => "f32",
+            Self::F64 => "f64",
+            Self::Other { .. } => "other",
+        }
+    }
+}
+
+impl core::fmt::Display for DataType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Other { code, bits } => write!(f, "halide type {code}:{bits}"),
+            _ => f.write_str(self.name()),
+        }
+    }
+}
+
 /// A tensor shape
 #[derive(Clone, Copy)]
 #[repr(C)]
@@ -489,6 +1522,15 @@ pub struct TensorShape {
     pub(crate) size: usize,
 }
 
+impl TensorShape {
+    /// Get the dimensions of the shape as `usize`
+    ///
+    /// Dynamic (unsized) dimensions are reported as `0`
+    pub fn dims(&self) -> Vec<usize> {
+        self.iter().map(|&d| d.max(0) as usize).collect()
+    }
+}
+
 impl From<mnn_sys::TensorShape> for TensorShape {
     fn from(value: mnn_sys::TensorShape) -> Self {
         Self {
@@ -598,6 +1640,229 @@ mod tensor_tests {
             super::Tensor::<super::Host<i32>>::from_ptr(core::ptr::null_mut());
         }
     }
+
+    #[test]
+    fn tensor_shape_dims() {
+        use super::AsTensorShape;
+        assert_eq!([1, 3, 224, 224].as_tensor_shape().dims(), [1, 3, 224, 224]);
+        assert_eq!([-1, 3].as_tensor_shape().dims(), [0, 3]);
+    }
+
+    #[test]
+    fn tensor_outer_iter() {
+        use super::*;
+        let data = (0..12).map(|x| x as f32).collect::<Vec<_>>();
+        let tensor = Tensor::<Ref<Host<f32>>>::borrowed([3, 4], &data);
+        let rows = tensor.outer_iter().unwrap().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], [0.0, 1.0, 2.0, 3.0]);
+        assert_eq!(rows[1], [4.0, 5.0, 6.0, 7.0]);
+        assert_eq!(rows[2], [8.0, 9.0, 10.0, 11.0]);
+    }
+
+    #[test]
+    fn tensor_byte_size_and_element_count() {
+        use super::*;
+        fn check<H: HalideType>() {
+            let tensor = Tensor::<Host<H>>::new([2, 3, 4], DimensionType::NCHW);
+            assert_eq!(tensor.element_count(), 24);
+            assert_eq!(tensor.byte_size(), tensor.element_count() * size_of::<H>());
+        }
+        check::<f32>();
+        check::<u8>();
+        check::<i16>();
+        check::<f64>();
+    }
+
+    #[test]
+    fn device_tensor_without_backend_to_host() {
+        use super::*;
+        let device = Tensor::<Device<f32>>::create_device([1, 3], DimensionType::Caffe).unwrap();
+        assert!(device.to_host().is_err());
+    }
+
+    #[test]
+    fn tensor_strides_nchw() {
+        use super::*;
+        let tensor = Tensor::<Host<f32>>::new([2, 3, 4, 5], DimensionType::NCHW);
+        assert_eq!(tensor.dimension_type(), DimensionType::NCHW);
+        assert!(!tensor.is_packed());
+        let shape = tensor.shape();
+        let strides = tensor.strides();
+        assert_eq!(strides.len(), shape.len());
+        // Row major, the innermost dimension is contiguous
+        assert_eq!(strides[3], size_of::<f32>());
+        for (pair, dim) in strides.windows(2).zip(&shape[1..]) {
+            assert_eq!(pair[0], pair[1] * *dim as usize);
+        }
+        assert_eq!(tensor.byte_size(), strides[0] * shape[0] as usize);
+        assert_eq!(tensor.byte_size(), tensor.host().len() * size_of::<f32>());
+    }
+
+    #[test]
+    fn tensor_convert_layout_round_trip() {
+        use super::*;
+        let data = (0..2 * 4 * 5 * 3).map(|x| x as f32).collect::<Vec<_>>();
+        let mut nhwc = Tensor::<Host<f32>>::new([2, 4, 5, 3], DimensionType::NHWC);
+        nhwc.host_mut().copy_from_slice(&data);
+        let nchw = nhwc.convert_layout(DimensionType::NCHW).unwrap();
+        assert_eq!(nchw.shape().as_ref(), [2, 3, 4, 5]);
+        assert_eq!(nchw.get_dimension_type(), DimensionType::NCHW);
+        // n = 1, c = 2, h = 3, w = 4
+        assert_eq!(
+            nchw.host()[((3 + 2) * 4 + 3) * 5 + 4],
+            data[((4 + 3) * 5 + 4) * 3 + 2]
+        );
+        let back = nchw.convert_layout(DimensionType::NHWC).unwrap();
+        assert_eq!(back.shape().as_ref(), [2, 4, 5, 3]);
+        assert_eq!(back.host(), data);
+        assert_eq!(
+            nchw.convert_layout(DimensionType::NCHW).unwrap().host(),
+            nchw.host()
+        );
+        let flat = Tensor::<Host<f32>>::new([2, 3, 4], DimensionType::NCHW);
+        assert!(flat.convert_layout(DimensionType::NHWC).is_err());
+        assert!(nchw.convert_layout(DimensionType::NC4HW4).is_err());
+    }
+
+    #[test]
+    fn tensor_copy_from_host() {
+        use super::*;
+        let mut nchw = Tensor::<Host<f32>>::new([1, 2, 1, 2], DimensionType::NCHW);
+        nchw.host_mut().copy_from_slice(&[0.0, 1.0, 10.0, 11.0]);
+        let mut nhwc = Tensor::<Host<f32>>::new([1, 1, 2, 2], DimensionType::NHWC);
+        nhwc.copy_from(&nchw.as_ref()).unwrap();
+        assert_eq!(nhwc.host(), [0.0, 10.0, 1.0, 11.0]);
+        let mut small = Tensor::<Host<f32>>::new([1, 2], DimensionType::NCHW);
+        assert!(small.copy_from(&nchw).is_err());
+    }
+
+    #[test]
+    fn tensor_bytes_endianness() {
+        use super::*;
+        let mut tensor = Tensor::<Host<i32>>::new([1, 2], DimensionType::NCHW);
+        tensor.host_mut().copy_from_slice(&[1, 0x01020304]);
+        let le = tensor.to_bytes_le().unwrap();
+        let be = tensor.to_bytes_be().unwrap();
+        assert_eq!(le, [1, 0, 0, 0, 4, 3, 2, 1]);
+        assert_eq!(be, [0, 0, 0, 1, 1, 2, 3, 4]);
+        let from_le = Tensor::<Host<i32>>::from_bytes_le(&le, [1, 2], DimensionType::NCHW).unwrap();
+        let from_be = Tensor::<Host<i32>>::from_bytes_be(&be, [1, 2], DimensionType::NCHW).unwrap();
+        assert_eq!(from_le.host(), [1, 0x01020304]);
+        assert_eq!(from_be.host(), [1, 0x01020304]);
+        // Reading with the wrong endianness swaps every element
+        let swapped = Tensor::<Host<i32>>::from_bytes_be(&le, [1, 2], DimensionType::NCHW).unwrap();
+        assert_eq!(swapped.host(), [0x01000000, 0x04030201]);
+
+        let floats = Tensor::from_scalar(-1.5f64, [3], DimensionType::NCHW).unwrap();
+        let be = floats.to_bytes_be().unwrap();
+        assert_eq!(be[..8], (-1.5f64).to_be_bytes());
+        let back = Tensor::<Host<f64>>::from_bytes_be(&be, [3], DimensionType::NCHW).unwrap();
+        assert_eq!(back.host(), [-1.5; 3]);
+        // Single byte types are the same in both
+        let bytes = Tensor::from_scalar(7u8, [2], DimensionType::NCHW).unwrap();
+        assert_eq!(bytes.to_bytes_le().unwrap(), bytes.to_bytes_be().unwrap());
+
+        let error = Tensor::<Host<i32>>::from_bytes_le(&le[..7], [1, 2], DimensionType::NCHW)
+            .expect_err("Accepted a short buffer");
+        assert!(matches!(
+            error.kind(),
+            ErrorKind::SizeMismatch {
+                expected: 8,
+                got: 7
+            }
+        ));
+        assert!(Tensor::<Host<bool>>::from_bytes_le(&[0, 2], [2], DimensionType::NCHW).is_err());
+        let bools = Tensor::<Host<bool>>::from_bytes_le(&[0, 1], [2], DimensionType::NCHW).unwrap();
+        assert_eq!(bools.host(), [false, true]);
+    }
+
+    #[test]
+    fn tensor_debug_preview() {
+        use super::*;
+        let mut tensor = Tensor::<Host<i32>>::new([1, 2, 3], DimensionType::NCHW);
+        tensor.host_mut().copy_from_slice(&[1, 2, 3, 4, 5, 6]);
+        let debug = format!("{tensor:?}");
+        assert!(debug.contains("shape: [1, 2, 3]"), "{debug}");
+        assert!(debug.contains("dimension_type: Caffe"), "{debug}");
+        assert!(debug.contains("type: halide_type_int(32)"), "{debug}");
+        assert!(debug.contains("elements: 6"), "{debug}");
+        assert!(debug.contains("values: [1, 2, 3, 4, 5, 6]"), "{debug}");
+        assert!(tensor.to_string_preview(2).contains("values: [1, 2, ..]"));
+        assert!(
+            tensor
+                .to_string_preview(100)
+                .contains("values: [1, 2, 3, 4, 5, 6]")
+        );
+        assert!(tensor.to_string_preview(0).contains("values: [, ..]"));
+        let bytes = Tensor::from_scalar(7u8, [4], DimensionType::NCHW).unwrap();
+        assert!(format!("{bytes:?}").contains("values: [7, 7, 7, 7]"));
+    }
+
+    #[test]
+    fn tensor_fill_and_zero() {
+        use super::*;
+        let mut tensor = Tensor::<Host<f32>>::new([1, 2, 3], DimensionType::NCHW);
+        tensor.try_fill(1.5).unwrap();
+        assert_eq!(tensor.host(), [1.5; 6]);
+        tensor.zero().unwrap();
+        assert_eq!(tensor.host(), [0.0; 6]);
+
+        let mut tensor = Tensor::from_scalar(-7i32, [2, 2], DimensionType::NHWC).unwrap();
+        assert_eq!(tensor.host(), [-7; 4]);
+        tensor.zero().unwrap();
+        assert_eq!(tensor.host(), [0; 4]);
+
+        let mut data = [0u8; 5];
+        let mut tensor = Tensor::<RefMut<Host<u8>>>::borrowed_mut([1, 5], &mut data);
+        tensor.try_fill(u8::MAX).unwrap();
+        drop(tensor);
+        assert_eq!(data, [u8::MAX; 5]);
+
+        assert!(Tensor::from_scalar(1u8, [1, -1], DimensionType::NCHW).is_err());
+        assert!(Tensor::from_scalar(1u8, [0, 3], DimensionType::NCHW).is_err());
+    }
+
+    #[test]
+    fn tensor_dequantize_i8() {
+        use super::*;
+        let reference = [-1.0f32, -0.5, 0.0, 0.25, 0.5, 1.0];
+        let params = QuantParams {
+            scale: 1.0 / 127.0,
+            zero_point: 0.0,
+            min: -127.0,
+            max: 127.0,
+        };
+        let mut tensor = Tensor::<Host<i8>>::new([1, 6], DimensionType::Caffe);
+        assert!(tensor.quant_params().is_none());
+        assert!(tensor.dequantize().is_err());
+        tensor
+            .host_mut()
+            .copy_from_slice(&params.quantize(&reference));
+        tensor.set_quant_params(params);
+        assert_eq!(tensor.quant_params(), Some(params));
+        tensor
+            .dequantize()
+            .unwrap()
+            .iter()
+            .zip(reference)
+            .for_each(|(d, r)| assert!((d - r).abs() <= params.scale / 2.0));
+    }
+
+    #[test]
+    fn quant_params_quantize_zero_point() {
+        use super::*;
+        let params = QuantParams {
+            scale: 0.1,
+            zero_point: -10.0,
+            min: -20.0,
+            max: 20.0,
+        };
+        assert_eq!(
+            params.quantize(&[0.0, 1.0, -1.0, 5.0, -5.0]),
+            [-10, 0, -20, 20, -20]
+        );
+    }
 }
 
 impl<T: HostTensorType + RefTensorType> Tensor<T>
@@ -617,7 +1882,10 @@ where
                 DimensionType::Caffe.to_mnn_sys(),
             )
         };
-        debug_assert!(!tensor.is_null());
+        assert!(
+            !tensor.is_null(),
+            "Failed to create tensor: MNN returned null"
+        );
         Self {
             tensor,
             __marker: PhantomData,
@@ -637,7 +1905,10 @@ where
                 DimensionType::Caffe.to_mnn_sys(),
             )
         };
-        debug_assert!(!tensor.is_null());
+        assert!(
+            !tensor.is_null(),
+            "Failed to create tensor: MNN returned null"
+        );
         Self {
             tensor,
             __marker: PhantomData,
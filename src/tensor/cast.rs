@@ -0,0 +1,72 @@
+//! Casting the host memory of tensors with [`bytemuck`] (requires the `bytemuck` feature)
+use crate::{HostTensorType, Tensor, internal_prelude::*};
+use bytemuck::Pod;
+
+impl<T: HostTensorType> Tensor<T>
+where
+    T::H: HalideType,
+{
+    /// Get the raw bytes of the host memory of the tensor
+    pub fn host_bytes(&self) -> Result<&[u8]> {
+        let data = unsafe { mnn_sys::Tensor_host(self.tensor) };
+        ensure!(!data.is_null(), ErrorKind::TensorError; "Tensor doesn't have host memory");
+        Ok(unsafe { core::slice::from_raw_parts(data.cast(), self.size()) })
+    }
+
+    /// Reinterpret the host memory of the tensor as a slice of `P`
+    ///
+    /// Returns an error if the memory isn't aligned for `P` or its size isn't a multiple of the
+    /// size of `P`.
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// let mut tensor = Tensor::<Host<f32>>::new([1, 4], DimensionType::NCHW);
+    /// tensor.fill(1.0);
+    /// let bits: &[u32] = tensor.cast_slice().unwrap();
+    /// assert_eq!(bits, [1.0f32.to_bits(); 4]);
+    /// ```
+    pub fn cast_slice<P: Pod>(&self) -> Result<&[P]> {
+        bytemuck::try_cast_slice(self.host_bytes()?).map_err(|e| {
+            error!(ErrorKind::TensorError).attach_printable(format!(
+                "Failed to cast tensor to [{}]: {e:?}",
+                std::any::type_name::<P>()
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod cast_tests {
+    use crate::{DimensionType, Host, Ref, Tensor};
+
+    #[test]
+    fn cast_f32_tensor() {
+        let data = [1.0f32, -2.0, 3.5, 0.0];
+        let tensor = Tensor::<Ref<Host<f32>>>::borrowed([1, 4], &data);
+        assert_eq!(tensor.cast_slice::<f32>().unwrap(), data);
+        assert_eq!(
+            tensor.cast_slice::<[f32; 2]>().unwrap(),
+            [[1.0, -2.0], [3.5, 0.0]]
+        );
+    }
+
+    #[test]
+    fn cast_i32_tensor() {
+        let mut tensor = Tensor::<Host<i32>>::new([2, 2], DimensionType::NCHW);
+        tensor.host_mut().copy_from_slice(&[1, -1, i32::MAX, 0]);
+        assert_eq!(tensor.cast_slice::<i32>().unwrap(), [1, -1, i32::MAX, 0]);
+        assert_eq!(tensor.cast_slice::<u32>().unwrap()[1], u32::MAX);
+    }
+
+    #[test]
+    fn cast_misaligned_tensor() {
+        let data = [0u32; 4];
+        let bytes: &[u8] = bytemuck::cast_slice(&data);
+        // offset by one byte so the memory isn't aligned for f32
+        let tensor = Tensor::<Ref<Host<u8>>>::borrowed([1, 12], &bytes[1..13]);
+        assert!(tensor.cast_slice::<f32>().is_err());
+        // 12 bytes can't be cast to [f32; 2]
+        let tensor = Tensor::<Ref<Host<u8>>>::borrowed([1, 12], &bytes[..12]);
+        assert!(tensor.cast_slice::<[f32; 2]>().is_err());
+    }
+}
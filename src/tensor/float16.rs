@@ -0,0 +1,65 @@
+//! Float16 tensors with [`half::f16`] and bfloat16 tensors with [`half::bf16`] (requires the
+//! `half` feature)
+use crate::{HostTensorType, Tensor, internal_prelude::*};
+use half::{bf16, f16};
+
+/// The 16 bit floats of the `half` crate, [`half::f16`] and [`half::bf16`]
+pub trait Float16: HalideType + Copy + Into<f32> + seal::Sealed {}
+mod seal {
+    pub trait Sealed {}
+}
+impl seal::Sealed for f16 {}
+impl seal::Sealed for bf16 {}
+impl Float16 for f16 {}
+impl Float16 for bf16 {}
+
+impl<T: HostTensorType> Tensor<T>
+where
+    T::H: Float16,
+{
+    /// Copy the host memory of a float16 / bfloat16 tensor to a `Vec<f32>`
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// use half::{bf16, f16};
+    /// let data = [f16::from_f32(0.5), f16::from_f32(-2.0)];
+    /// let tensor = Tensor::<Ref<Host<f16>>>::borrowed([1, 2], &data);
+    /// assert_eq!(tensor.to_f32_vec().unwrap(), [0.5, -2.0]);
+    /// let data = [bf16::from_f32(0.5), bf16::from_f32(-2.0)];
+    /// let tensor = Tensor::<Ref<Host<bf16>>>::borrowed([1, 2], &data);
+    /// assert_eq!(tensor.to_f32_vec().unwrap(), [0.5, -2.0]);
+    /// ```
+    pub fn to_f32_vec(&self) -> Result<Vec<f32>> {
+        Ok(self.try_host()?.iter().map(|&x| x.into()).collect())
+    }
+}
+
+#[cfg(test)]
+mod float16_tests {
+    use crate::{DataType, DimensionType, Host, Tensor};
+    use half::{bf16, f16};
+
+    #[test]
+    fn f16_host_tensor() {
+        let mut tensor = Tensor::<Host<f16>>::new([1, 2, 2], DimensionType::NCHW);
+        assert!(tensor.is_type_of::<f16>());
+        assert!(!tensor.is_type_of::<f32>());
+        // 2 bytes per element
+        assert_eq!(tensor.size(), 8);
+        tensor.fill(f16::from_f32(1.5));
+        tensor.host_mut()[3] = f16::from_f32(-0.25);
+        assert_eq!(tensor.to_f32_vec().unwrap(), [1.5, 1.5, 1.5, -0.25]);
+    }
+
+    #[test]
+    fn bf16_host_tensor() {
+        let mut tensor = Tensor::<Host<bf16>>::new([1, 2, 2], DimensionType::NCHW);
+        assert!(tensor.is_type_of::<bf16>());
+        assert!(!tensor.is_type_of::<f16>());
+        assert_eq!(tensor.data_type(), DataType::BF16);
+        assert_eq!(tensor.size(), 8);
+        tensor.fill(bf16::from_f32(1.5));
+        tensor.host_mut()[3] = bf16::from_f32(-0.25);
+        assert_eq!(tensor.to_f32_vec().unwrap(), [1.5, 1.5, 1.5, -0.25]);
+    }
+}
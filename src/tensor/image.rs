@@ -0,0 +1,291 @@
+//! Conversion of [`image`] images to tensors (requires the `image` feature)
+use crate::{
+    DimensionType, Host, HostTensorType, MutableTensorType, Tensor, TensorType, internal_prelude::*,
+};
+use image::{DynamicImage, imageops::FilterType};
+
+/// The order of the color channels when converting an image to a tensor
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum ChannelOrder {
+    /// Red, Green, Blue
+    #[default]
+    Rgb,
+    /// Blue, Green, Red (e.g. for models trained with opencv)
+    Bgr,
+}
+
+/// How the pixels of an image are normalized when they're copied to a tensor
+///
+/// Each channel is normalized with `(value / scale - mean) / std`, the default maps the pixels
+/// to `0..1` in rgb order. It can be stored on a session with
+/// [`Session::set_preprocess`](crate::Session::set_preprocess) to be reused by
+/// [`Session::set_image`](crate::Session::set_image).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Preprocess {
+    /// Mean of each channel (in rgb order)
+    pub mean: [f32; 3],
+    /// Standard deviation of each channel (in rgb order)
+    pub std: [f32; 3],
+    /// Swap the red and blue channels (e.g. for models trained with opencv)
+    pub to_bgr: bool,
+    /// The pixel values are divided by this before the normalization
+    pub scale: f32,
+}
+
+impl Default for Preprocess {
+    fn default() -> Self {
+        Self {
+            mean: [0.0; 3],
+            std: [1.0; 3],
+            to_bgr: false,
+            scale: 255.0,
+        }
+    }
+}
+
+impl Preprocess {
+    /// Normalize with the given `mean` and `std` (of the pixels scaled to `0..1`)
+    pub fn new(mean: [f32; 3], std: [f32; 3]) -> Self {
+        Self {
+            mean,
+            std,
+            ..Default::default()
+        }
+    }
+
+    /// Swap the red and blue channels
+    pub fn with_bgr(mut self, to_bgr: bool) -> Self {
+        self.to_bgr = to_bgr;
+        self
+    }
+
+    /// Divide the pixel values by `scale` before the normalization
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+}
+
+impl Tensor<Host<f32>> {
+    /// Create a new `[1, C, H, W]` (or `[1, H, W, C]` for [`DimensionType::NHWC`]) tensor from an
+    /// image with the size of the image
+    ///
+    /// Grayscale images produce a single channel, every other image has 3 channels in `order`.
+    /// Each channel is normalized with `(value / 255 - mean) / std`.
+    pub fn from_image(
+        img: &DynamicImage,
+        mean: [f32; 3],
+        std: [f32; 3],
+        order: ChannelOrder,
+        dim_type: DimensionType,
+    ) -> Result<Self> {
+        let channels = if is_grayscale(img) { 1 } else { 3 };
+        let (width, height) = (img.width() as i32, img.height() as i32);
+        let shape = match dim_type {
+            DimensionType::Caffe => [1, channels, height, width],
+            DimensionType::TensorFlow => [1, height, width, channels],
+            DimensionType::CaffeC4 => {
+                return Err(error!(ErrorKind::TensorError)
+                    .attach_printable("NC4HW4 tensors can't be created from images"));
+            }
+        };
+        let mut tensor = Tensor::new(shape, dim_type);
+        tensor.copy_from_image(img, mean, std, order)?;
+        Ok(tensor)
+    }
+}
+
+impl<T: HostTensorType + MutableTensorType + TensorType<H = f32>> Tensor<T> {
+    /// Copy an image into the tensor
+    ///
+    /// The image is resized to the spatial dimensions of the tensor, converted to the number of
+    /// channels of the tensor (1 for grayscale or 3) and normalized with
+    /// `(value / 255 - mean) / std`.
+    pub fn copy_from_image(
+        &mut self,
+        img: &DynamicImage,
+        mean: [f32; 3],
+        std: [f32; 3],
+        order: ChannelOrder,
+    ) -> Result<()> {
+        let preprocess = Preprocess::new(mean, std).with_bgr(order == ChannelOrder::Bgr);
+        self.copy_from_image_with(img, &preprocess)
+    }
+
+    /// Copy an image into the tensor, normalized with `preprocess`
+    ///
+    /// Same as [`Tensor::copy_from_image`] with the normalization of a [`Preprocess`].
+    pub fn copy_from_image_with(
+        &mut self,
+        img: &DynamicImage,
+        preprocess: &Preprocess,
+    ) -> Result<()> {
+        let dim_type = self.get_dimension_type();
+        let shape = self.shape();
+        ensure!(shape.size == 4, ErrorKind::TensorError; format!("Expected a 4D tensor, got {shape:?}"));
+        let (channels, height, width) = match dim_type {
+            DimensionType::Caffe => (shape[1], shape[2], shape[3]),
+            DimensionType::TensorFlow => (shape[3], shape[1], shape[2]),
+            DimensionType::CaffeC4 => {
+                return Err(error!(ErrorKind::TensorError)
+                    .attach_printable("NC4HW4 tensors can't be filled from images"));
+            }
+        };
+        ensure!(
+            channels == 1 || channels == 3,
+            ErrorKind::TensorError;
+            format!("Expected 1 or 3 channels, got {channels}")
+        );
+        ensure!(
+            height > 0 && width > 0,
+            ErrorKind::TensorError;
+            format!("Expected a non empty tensor, got {height}x{width}")
+        );
+        let (channels, height, width) = (channels as usize, height as usize, width as usize);
+        let img = if img.width() as usize == width && img.height() as usize == height {
+            std::borrow::Cow::Borrowed(img)
+        } else {
+            std::borrow::Cow::Owned(img.resize_exact(
+                width as u32,
+                height as u32,
+                FilterType::Triangle,
+            ))
+        };
+        let pixels: Vec<u8> = if channels == 1 {
+            img.to_luma8().into_raw()
+        } else {
+            img.to_rgb8().into_raw()
+        };
+        let Preprocess {
+            mean,
+            std,
+            to_bgr,
+            scale,
+        } = *preprocess;
+        let plane = width * height;
+        let data = self.try_host_mut()?;
+        for (idx, pixel) in pixels.chunks_exact(channels).enumerate() {
+            for c in 0..channels {
+                // mean and std are in rgb order, so they follow the source channel
+                let src = if to_bgr && channels == 3 { 2 - c } else { c };
+                let value = (pixel[src] as f32 / scale - mean[src]) / std[src];
+                match dim_type {
+                    DimensionType::TensorFlow => data[idx * channels + c] = value,
+                    _ => data[c * plane + idx] = value,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_grayscale(img: &DynamicImage) -> bool {
+    !img.color().has_color()
+}
+
+#[cfg(test)]
+mod image_tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    fn test_image() -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(4, 2, |x, y| {
+            Rgb([(x * 60) as u8, (y * 255) as u8, 255])
+        }))
+    }
+
+    #[test]
+    fn image_to_nchw() {
+        let tensor = Tensor::from_image(
+            &test_image(),
+            [0.0; 3],
+            [1.0; 3],
+            ChannelOrder::Rgb,
+            DimensionType::NCHW,
+        )
+        .unwrap();
+        assert_eq!(tensor.shape().dims(), [1, 3, 2, 4]);
+        let data = tensor.host();
+        assert_eq!(
+            &data[..4],
+            [0.0, 60.0 / 255.0, 120.0 / 255.0, 180.0 / 255.0]
+        );
+        assert_eq!(&data[8..12], [0.0; 4]);
+        assert_eq!(&data[12..16], [1.0; 4]);
+        assert_eq!(&data[16..24], [1.0; 8]);
+    }
+
+    #[test]
+    fn image_to_nhwc_bgr_normalized() {
+        let mut tensor = Tensor::<Host<f32>>::new([1, 2, 4, 3], DimensionType::NHWC);
+        tensor
+            .copy_from_image(&test_image(), [0.5; 3], [0.5; 3], ChannelOrder::Bgr)
+            .unwrap();
+        let data = tensor.host();
+        // first pixel is rgb (0, 0, 255) so bgr (255, 0, 0)
+        assert_eq!(&data[..3], [1.0, -1.0, -1.0]);
+    }
+
+    #[test]
+    fn image_to_nhwc_bgr() {
+        let tensor = Tensor::from_image(
+            &test_image(),
+            [0.0; 3],
+            [1.0; 3],
+            ChannelOrder::Bgr,
+            DimensionType::NHWC,
+        )
+        .unwrap();
+        assert_eq!(tensor.shape().dims(), [1, 2, 4, 3]);
+        // first pixel is rgb (0, 0, 255) so bgr (255, 0, 0)
+        assert_eq!(&tensor.host()[..3], [1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn image_resized_to_grayscale() {
+        let mut tensor = Tensor::<Host<f32>>::new([1, 1, 1, 2], DimensionType::NCHW);
+        tensor
+            .copy_from_image(&test_image(), [0.0; 3], [1.0; 3], ChannelOrder::Rgb)
+            .unwrap();
+        assert_eq!(tensor.host().len(), 2);
+    }
+
+    #[test]
+    fn image_to_empty_tensor() {
+        let mut tensor = Tensor::<Host<f32>>::new([1, 3, 0, 4], DimensionType::NCHW);
+        assert!(
+            tensor
+                .copy_from_image(&test_image(), [0.0; 3], [1.0; 3], ChannelOrder::Rgb)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn image_with_preprocess() {
+        let mut tensor = Tensor::<Host<f32>>::new([1, 3, 2, 4], DimensionType::NCHW);
+        let preprocess = Preprocess::new([0.0; 3], [2.0; 3])
+            .with_bgr(true)
+            .with_scale(1.0);
+        tensor
+            .copy_from_image_with(&test_image(), &preprocess)
+            .unwrap();
+        let data = tensor.host();
+        // the blue channel comes first
+        assert_eq!(&data[..8], [127.5; 8]);
+        assert_eq!(&data[16..20], [0.0, 30.0, 60.0, 90.0]);
+    }
+
+    #[test]
+    fn image_bgr_with_per_channel_mean() {
+        let mut tensor = Tensor::<Host<f32>>::new([1, 3, 2, 4], DimensionType::NCHW);
+        let preprocess = Preprocess::new([0.1, 0.2, 0.3], [0.5, 1.0, 2.0]).with_bgr(true);
+        tensor
+            .copy_from_image_with(&test_image(), &preprocess)
+            .unwrap();
+        let data = tensor.host();
+        // first pixel is rgb (0, 0, 255), each channel keeps the mean and std of its color
+        assert_eq!(data[0], (1.0 - 0.3) / 2.0);
+        assert_eq!(data[8], (0.0 - 0.2) / 1.0);
+        assert_eq!(data[16], (0.0 - 0.1) / 0.5);
+    }
+}
@@ -1,5 +1,5 @@
 #![deny(missing_docs)]
-use crate::{Device, RawTensor, RefMut, Tensor, prelude::*};
+use crate::{Device, RawTensor, RefMut, Tensor, internal_prelude::*};
 use mnn_sys::HalideType;
 
 #[repr(transparent)]
@@ -111,7 +111,11 @@ impl<'t, 'tl> TensorInfo<'t, 'tl> {
 
     pub fn tensor<H: HalideType>(&self) -> Result<Tensor<RefMut<'t, Device<H>>>> {
         debug_assert!(!self.tensor_info.is_null());
-        unsafe { debug_assert!(!(*self.tensor_info).tensor.is_null()) };
+        ensure!(
+            unsafe { !(*self.tensor_info).tensor.is_null() },
+            ErrorKind::NullResult { op: "getTensorInfoArray" };
+            format!("Tensor \"{}\" is null", self.name())
+        );
         let tensor = unsafe { Tensor::from_ptr((*self.tensor_info).tensor.cast()) };
         let shape = tensor.shape();
         ensure!(!shape.as_ref().contains(&-1), ErrorKind::DynamicTensorError);
@@ -128,7 +132,11 @@ impl<'t, 'tl> TensorInfo<'t, 'tl> {
     /// The shape is not checked so it's marked unsafe since futher calls to interpreter might be **unsafe** with this
     pub unsafe fn tensor_unresized<H: HalideType>(&self) -> Result<Tensor<RefMut<'t, Device<H>>>> {
         debug_assert!(!self.tensor_info.is_null());
-        unsafe { debug_assert!(!(*self.tensor_info).tensor.is_null()) };
+        ensure!(
+            unsafe { !(*self.tensor_info).tensor.is_null() },
+            ErrorKind::NullResult { op: "getTensorInfoArray" };
+            format!("Tensor \"{}\" is null", self.name())
+        );
         let tensor = unsafe { Tensor::from_ptr((*self.tensor_info).tensor.cast()) };
         ensure!(
             tensor.is_type_of::<H>(),
@@ -0,0 +1,153 @@
+use super::*;
+
+/// The host memory of a device tensor mapped for reading with [`Tensor::map`]
+///
+/// The memory is unmapped when this is dropped.
+pub struct MappedTensor<'t, H: HalideType> {
+    mapping: Mapping<H>,
+    __marker: PhantomData<&'t [H]>,
+}
+
+/// The host memory of a device tensor mapped for writing with [`Tensor::map_mut`]
+///
+/// The memory is unmapped when this is dropped, which is when backends without shared memory
+/// upload the written data to the device.
+pub struct MappedTensorMut<'t, H: HalideType> {
+    mapping: Mapping<H>,
+    __marker: PhantomData<&'t mut [H]>,
+}
+
+/// A pointer returned by `Tensor::map` and what's needed to unmap it
+struct Mapping<H> {
+    tensor: *mut mnn_sys::Tensor,
+    map_type: MapType,
+    dm_type: DimensionType,
+    ptr: *mut H,
+    len: usize,
+}
+
+impl<H: HalideType> Mapping<H> {
+    fn new<T: TensorType>(tensor: &Tensor<T>, map_type: MapType) -> Result<Self> {
+        let dm_type = tensor.get_dimension_type();
+        let tensor = tensor.tensor;
+        let ptr = unsafe { Tensor_map(tensor, map_type, dm_type.to_mnn_sys()) };
+        crate::ensure!(!ptr.is_null(), ErrorKind::NullResult { op: "Tensor_map" });
+        // The mapped memory includes the channel padding of packed tensors
+        let len = unsafe { Tensor_usize(tensor) } / core::mem::size_of::<H>();
+        Ok(Self {
+            tensor,
+            map_type,
+            dm_type,
+            ptr: ptr.cast(),
+            len,
+        })
+    }
+}
+
+impl<H> Drop for Mapping<H> {
+    fn drop(&mut self) {
+        unsafe {
+            Tensor_unmap(
+                self.tensor,
+                self.map_type,
+                self.dm_type.to_mnn_sys(),
+                self.ptr.cast(),
+            )
+        }
+    }
+}
+
+impl<T: DeviceTensorType> Tensor<T>
+where
+    T::H: HalideType,
+{
+    /// Map the memory of the device tensor into the host address space for reading
+    ///
+    /// Backends with memory shared between the cpu and the device (e.g. OpenCL on integrated
+    /// gpus) map it without copying, the others copy the tensor to host memory like
+    /// [`Tensor::to_host`]. The layout is the tensor's [`DimensionType`].
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// let net = Interpreter::from_file("tests/assets/realesr.mnn").unwrap();
+    /// let session = net.create_session(ScheduleConfig::new()).unwrap();
+    /// net.input::<f32>(&session, "data").unwrap().fill(0.5);
+    /// let input = net.input::<f32>(&session, "data").unwrap();
+    /// let mapped = input.map().unwrap();
+    /// assert!(mapped.iter().all(|&value| value == 0.5));
+    /// ```
+    pub fn map(&self) -> Result<MappedTensor<'_, T::H>> {
+        Ok(MappedTensor {
+            mapping: Mapping::new(self, MapType::MAP_TENSOR_READ)?,
+            __marker: PhantomData,
+        })
+    }
+}
+
+impl<T: DeviceTensorType + MutableTensorType> Tensor<T>
+where
+    T::H: HalideType,
+{
+    /// Map the memory of the device tensor into the host address space for writing
+    ///
+    /// The mapped memory isn't guaranteed to hold the current data of the tensor, it should be
+    /// overwritten completely. See [`Tensor::map`] for when this avoids a copy.
+    pub fn map_mut(&mut self) -> Result<MappedTensorMut<'_, T::H>> {
+        Ok(MappedTensorMut {
+            mapping: Mapping::new(self, MapType::MAP_TENSOR_WRITE)?,
+            __marker: PhantomData,
+        })
+    }
+}
+
+impl<H: HalideType> core::ops::Deref for MappedTensor<'_, H> {
+    type Target = [H];
+    fn deref(&self) -> &Self::Target {
+        unsafe { core::slice::from_raw_parts(self.mapping.ptr, self.mapping.len) }
+    }
+}
+
+impl<H: HalideType> core::ops::Deref for MappedTensorMut<'_, H> {
+    type Target = [H];
+    fn deref(&self) -> &Self::Target {
+        unsafe { core::slice::from_raw_parts(self.mapping.ptr, self.mapping.len) }
+    }
+}
+
+impl<H: HalideType> core::ops::DerefMut for MappedTensorMut<'_, H> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { core::slice::from_raw_parts_mut(self.mapping.ptr, self.mapping.len) }
+    }
+}
+
+#[cfg(test)]
+mod map_tests {
+    use super::*;
+    use crate::{ForwardType, Interpreter, ScheduleConfig};
+
+    fn map_roundtrip(forward: ForwardType) {
+        let net = Interpreter::from_file("tests/assets/realesr.mnn").unwrap();
+        let config = ScheduleConfig::new().with_type(forward);
+        let session = net.create_session(config).unwrap();
+        let mut input = net.input::<f32>(&session, "data").unwrap();
+        input.map_mut().unwrap().fill(2.0);
+        let mapped = input.map().unwrap();
+        assert_eq!(mapped.len(), 3 * 512 * 512);
+        assert!(mapped.iter().all(|&value| value == 2.0));
+        drop(mapped);
+        let host = input.to_host().unwrap();
+        assert!(host.host().iter().all(|&value| value == 2.0));
+    }
+
+    #[test]
+    fn map_cpu() {
+        map_roundtrip(ForwardType::CPU);
+    }
+
+    #[cfg(feature = "opencl")]
+    #[test]
+    #[ignore = "Doesn't work on ci"]
+    fn map_opencl() {
+        map_roundtrip(ForwardType::OpenCL);
+    }
+}
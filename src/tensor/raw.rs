@@ -1,4 +1,4 @@
-use crate::prelude::*;
+use crate::internal_prelude::*;
 use core::marker::PhantomData;
 use mnn_sys::HalideType;
 /// A raw tensor type that doesn't have any guarantees
@@ -104,6 +104,25 @@ impl RawTensor<'_> {
         }
     }
 
+    /// Get the host memory of the tensor, checking the data type and that the tensor has host
+    /// memory at runtime
+    ///
+    /// Returns an error for tensors whose memory is owned by a gpu backend.
+    #[deprecated(
+        note = "use the typed Tensor<Host<H>> (Tensor::to_host for device tensors) which checks this at compile time"
+    )]
+    pub fn host<H: HalideType>(&self) -> Result<&[H]> {
+        crate::ensure!(
+            unsafe { mnn_sys::Tensor_isTypeOf(self.inner, mnn_sys::halide_type_of::<H>()) },
+            ErrorKind::HalideTypeMismatch {
+                got: std::any::type_name::<H>(),
+            }
+        );
+        let data = unsafe { mnn_sys::Tensor_host(self.inner) };
+        crate::ensure!(!data.is_null(), ErrorKind::TensorError; "Tensor doesn't have host memory");
+        Ok(unsafe { core::slice::from_raw_parts(data.cast(), self.element_size()) })
+    }
+
     /// # Safety
     /// This is very unsafe do not use this unless you know what you are doing
     /// Gives a raw pointer to the tensor's data
@@ -0,0 +1,164 @@
+use super::*;
+
+/// The first bytes of a tensor written by [`Tensor::serialize`]
+const MAGIC: [u8; 4] = *b"MNNT";
+/// The version of the format written by [`Tensor::serialize`]
+const VERSION: u8 = 1;
+/// magic, version, dimension type, type code, type bits, rank
+const HEADER_LEN: usize = MAGIC.len() + 5;
+
+impl<T: HostTensorType> Tensor<T>
+where
+    T::H: HalideType + Copy,
+{
+    /// Serialize the tensor to bytes that describe themselves, read them back with
+    /// [`Tensor::deserialize`]
+    ///
+    /// The bytes start with a header holding a magic number, the format version, the
+    /// [`DimensionType`], the [`DataType`] and the shape, followed by the little endian data
+    /// ([`Tensor::to_bytes_le`]), so they can be read on any platform.
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// let tensor = Tensor::from_scalar(0.5f32, [1, 3, 2, 2], DimensionType::NCHW).unwrap();
+    /// let bytes = tensor.serialize().unwrap();
+    /// let restored = Tensor::<Host<f32>>::deserialize(&bytes).unwrap();
+    /// assert_eq!(restored.shape().as_ref(), [1, 3, 2, 2]);
+    /// assert_eq!(restored.host(), tensor.host());
+    /// ```
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        let shape = self.shape();
+        let dm_type = match self.get_dimension_type() {
+            DimensionType::Caffe => 0,
+            DimensionType::CaffeC4 => 1,
+            DimensionType::TensorFlow => 2,
+        };
+        let halide_type = halide_type_of::<T::H>();
+        let mut bytes = Vec::with_capacity(HEADER_LEN + shape.as_ref().len() * 4 + self.size());
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&[
+            VERSION,
+            dm_type,
+            halide_type.code as u8,
+            halide_type.bits,
+            shape.as_ref().len() as u8,
+        ]);
+        for dim in shape.as_ref() {
+            bytes.extend_from_slice(&dim.to_le_bytes());
+        }
+        bytes.extend_from_slice(&self.to_bytes_le()?);
+        Ok(bytes)
+    }
+}
+
+impl<H: HalideType + Copy> Tensor<Host<H>> {
+    /// Read a tensor written by [`Tensor::serialize`]
+    ///
+    /// Returns an [`ErrorKind::ParseError`] if the header is corrupt or from another version of
+    /// the format, an [`ErrorKind::HalideTypeMismatch`] if the tensor isn't of type `H` and an
+    /// [`ErrorKind::SizeMismatch`] if the data doesn't match the shape.
+    pub fn deserialize(bytes: &[u8]) -> Result<Self> {
+        crate::ensure!(
+            bytes.len() >= HEADER_LEN && bytes[..MAGIC.len()] == MAGIC,
+            ErrorKind::ParseError;
+            "Not a serialized tensor"
+        );
+        let [version, dm_type, code, bits, rank] = bytes[MAGIC.len()..HEADER_LEN] else {
+            unreachable!()
+        };
+        crate::ensure!(
+            version == VERSION,
+            ErrorKind::ParseError;
+            format!("Unsupported serialized tensor version {version}, expected {VERSION}")
+        );
+        let dm_type = match dm_type {
+            0 => DimensionType::Caffe,
+            1 => DimensionType::CaffeC4,
+            2 => DimensionType::TensorFlow,
+            _ => {
+                return Err(error!(ErrorKind::ParseError)
+                    .attach_printable(format!("Invalid dimension type {dm_type}")));
+            }
+        };
+        let expected = halide_type_of::<H>();
+        crate::ensure!(
+            expected.code as u8 == code && expected.bits == bits,
+            ErrorKind::HalideTypeMismatch {
+                got: std::any::type_name::<H>(),
+            };
+            format!("The serialized tensor is of type code {code} with {bits} bits")
+        );
+        let rank = rank as usize;
+        crate::ensure!(
+            rank <= 4 && bytes.len() >= HEADER_LEN + rank * 4,
+            ErrorKind::ParseError;
+            format!("Invalid rank {rank}")
+        );
+        let (shape, data) = bytes[HEADER_LEN..].split_at(rank * 4);
+        let shape: Vec<i32> = shape
+            .chunks_exact(4)
+            .map(|dim| i32::from_le_bytes([dim[0], dim[1], dim[2], dim[3]]))
+            .collect();
+        Self::from_bytes_le(data, shape.as_slice(), dm_type)
+    }
+}
+
+#[cfg(test)]
+mod serialize_tests {
+    use super::*;
+
+    fn round_trip<H: HalideType + Copy + PartialEq + core::fmt::Debug>(
+        value: H,
+        shape: &[i32],
+        dm_type: DimensionType,
+    ) {
+        let tensor = Tensor::from_scalar(value, shape, dm_type).unwrap();
+        let bytes = tensor.serialize().unwrap();
+        let restored = Tensor::<Host<H>>::deserialize(&bytes).unwrap();
+        assert_eq!(restored.shape().as_ref(), tensor.shape().as_ref());
+        assert_eq!(restored.get_dimension_type(), dm_type);
+        assert_eq!(restored.host(), tensor.host());
+    }
+
+    #[test]
+    fn serialize_round_trip() {
+        for dm_type in [
+            DimensionType::NCHW,
+            DimensionType::NHWC,
+            DimensionType::NC4HW4,
+        ] {
+            round_trip(1.5f32, &[1, 4, 2, 3], dm_type);
+            round_trip(-7i32, &[2, 4, 3, 1], dm_type);
+            round_trip(200u8, &[1, 4, 1, 1], dm_type);
+            round_trip(-3i8, &[1, 4, 5, 2], dm_type);
+            round_trip(0.25f64, &[1, 4, 1, 3], dm_type);
+        }
+    }
+
+    #[test]
+    fn deserialize_rejects_corrupt_data() {
+        let tensor = Tensor::from_scalar(1.0f32, [2, 3], DimensionType::NCHW).unwrap();
+        let bytes = tensor.serialize().unwrap();
+        let is_parse_error = |bytes: &[u8]| {
+            let err = Tensor::<Host<f32>>::deserialize(bytes)
+                .map(|_| ())
+                .unwrap_err();
+            matches!(err.kind(), ErrorKind::ParseError)
+        };
+
+        assert!(is_parse_error(&bytes[..3]));
+        // magic, version, dimension type and rank
+        for (index, value) in [(0, b'X'), (4, VERSION + 1), (5, 7), (8, 9)] {
+            let mut corrupt = bytes.clone();
+            corrupt[index] = value;
+            assert!(
+                is_parse_error(&corrupt),
+                "Corrupt byte {index} wasn't rejected"
+            );
+        }
+        let err = Tensor::<Host<f32>>::deserialize(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::SizeMismatch { .. }));
+        let err = Tensor::<Host<i32>>::deserialize(&bytes).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::HalideTypeMismatch { .. }));
+    }
+}
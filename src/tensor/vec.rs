@@ -0,0 +1,141 @@
+use super::*;
+
+/// A host tensor using the memory of a [`Vec`] without copying it, see [`Tensor::from_vec`]
+///
+/// MNN doesn't free memory it was given so the `Vec` is kept alongside the tensor and freed
+/// exactly once, after the tensor, when this is dropped.
+///
+/// It dereferences to a [`Tensor<Host<H>>`] for reading, mutable access goes through
+/// [`host_mut`](VecTensor::host_mut) and [`as_mut`](VecTensor::as_mut) since handing out a
+/// `&mut Tensor` would allow swapping the tensor out of the `Vec` it points to.
+pub struct VecTensor<H: HalideType> {
+    // Declared first so it is dropped before the data it points to
+    tensor: Tensor<Host<H>>,
+    data: Vec<H>,
+}
+
+impl<H: HalideType> Tensor<Host<H>> {
+    /// Create a host tensor that takes ownership of `data` instead of copying it
+    ///
+    /// `data` must hold exactly one element per element of `shape`. Packed
+    /// [`DimensionType::NC4HW4`] tensors aren't supported since MNN pads their channels.
+    ///
+    /// ```rust
+    /// # use mnn::*;
+    /// let data = vec![1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0];
+    /// let tensor = Tensor::from_vec(data, [2, 3], DimensionType::NCHW).unwrap();
+    /// assert_eq!(tensor.host(), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    /// let data = tensor.into_vec();
+    /// assert_eq!(data.len(), 6);
+    /// ```
+    ///
+    /// The `Vec` is moved into the tensor so it can't be modified behind its back
+    ///
+    /// ```compile_fail
+    /// # use mnn::*;
+    /// let mut data = vec![0.0f32; 4];
+    /// let tensor = Tensor::from_vec(data, [4], DimensionType::NCHW).unwrap();
+    /// data[0] = 1.0;
+    /// ```
+    pub fn from_vec(
+        mut data: Vec<H>,
+        shape: impl AsTensorShape,
+        dm_type: DimensionType,
+    ) -> Result<VecTensor<H>> {
+        let shape = shape.as_tensor_shape();
+        crate::ensure!(
+            shape.as_ref().iter().all(|&dim| dim > 0),
+            ErrorKind::TensorError;
+            format!("Invalid shape {shape:?}")
+        );
+        crate::ensure!(
+            dm_type != DimensionType::NC4HW4,
+            ErrorKind::TensorError;
+            "NC4HW4 tensors can't be created from a Vec"
+        );
+        let expected = shape.as_ref().iter().product::<i32>() as usize;
+        crate::ensure!(
+            data.len() == expected,
+            ErrorKind::SizeMismatch {
+                expected,
+                got: data.len(),
+            }
+        );
+        let tensor = unsafe {
+            Tensor_createWith(
+                shape.shape.as_ptr(),
+                shape.size,
+                halide_type_of::<H>(),
+                data.as_mut_ptr().cast(),
+                dm_type.to_mnn_sys(),
+            )
+        };
+        crate::ensure!(!tensor.is_null(), ErrorKind::TensorError; "Failed to create tensor");
+        let tensor = Tensor {
+            tensor,
+            __marker: PhantomData,
+        };
+        Ok(VecTensor { tensor, data })
+    }
+}
+
+impl<H: HalideType> VecTensor<H> {
+    /// The mutable host memory, which is the memory of the `Vec`
+    pub fn host_mut(&mut self) -> &mut [H] {
+        self.tensor.host_mut()
+    }
+
+    /// A mutable reference to the tensor, e.g. to copy into it with [`Tensor::copy_from`]
+    pub fn as_mut(&mut self) -> Tensor<RefMut<'_, Host<H>>> {
+        Tensor {
+            tensor: self.tensor.tensor,
+            __marker: PhantomData,
+        }
+    }
+
+    /// Destroy the tensor and get back the `Vec`
+    pub fn into_vec(self) -> Vec<H> {
+        let Self { tensor, data } = self;
+        drop(tensor);
+        data
+    }
+}
+
+impl<H: HalideType> core::ops::Deref for VecTensor<H> {
+    type Target = Tensor<Host<H>>;
+    fn deref(&self) -> &Self::Target {
+        &self.tensor
+    }
+}
+
+impl<H: HalideType> AsRef<Tensor<Host<H>>> for VecTensor<H> {
+    fn as_ref(&self) -> &Tensor<Host<H>> {
+        &self.tensor
+    }
+}
+
+#[cfg(test)]
+mod vec_tests {
+    use super::*;
+
+    #[test]
+    fn from_vec_uses_the_vec_memory() {
+        let data = vec![0i32; 12];
+        let ptr = data.as_ptr();
+        let mut tensor = Tensor::from_vec(data, [3, 4], DimensionType::NCHW).unwrap();
+        assert_eq!(tensor.host().as_ptr(), ptr);
+        tensor.host_mut()[5] = 5;
+        tensor.as_mut().fill(1);
+        let data = tensor.into_vec();
+        assert_eq!(data.as_ptr(), ptr);
+        assert_eq!(data, [1; 12]);
+    }
+
+    #[test]
+    fn from_vec_size_mismatch() {
+        assert!(Tensor::from_vec(vec![0.0f32; 5], [2, 3], DimensionType::NCHW).is_err());
+        assert!(Tensor::from_vec(vec![0.0f32; 6], [2, 0], DimensionType::NCHW).is_err());
+        // The channels of packed tensors are padded
+        assert!(Tensor::from_vec(vec![0.0f32; 12], [1, 3, 2, 2], DimensionType::NC4HW4).is_err());
+    }
+}
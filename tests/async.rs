@@ -0,0 +1,50 @@
+#![cfg(feature = "tokio")]
+pub mod common;
+use common::*;
+use std::sync::Arc;
+
+async fn run_model(net: Arc<Interpreter>, value: f32) -> Result<Vec<f32>> {
+    let session = net.create_session(ScheduleConfig::new())?;
+    net.inputs(&session).iter().for_each(|x| {
+        let mut tensor = x.tensor::<f32>().expect("No tensor");
+        tensor.fill(value);
+    });
+    let session = session.run_async().await?;
+    let outputs = net.outputs(&session);
+    let output = outputs.iter().next().expect("No output").tensor::<f32>()?;
+    let output = output.create_host_tensor_from_device(true);
+    Ok(output.host().to_vec())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_run_async_concurrent() -> Result<()> {
+    let net = Arc::new(Interpreter::from_bytes(Model::new())?);
+    let expected = run_model(net.clone(), 1.0).await?;
+    let mut handles = Vec::new();
+    for _ in 0..2 {
+        // sessions of the same interpreter
+        handles.push(tokio::spawn(run_model(net.clone(), 1.0)));
+        // sessions of different interpreters
+        let other = Arc::new(Interpreter::from_bytes(Model::new())?);
+        handles.push(tokio::spawn(run_model(other, 1.0)));
+    }
+    for handle in handles {
+        assert_eq!(handle.await??, expected);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_async_without_interpreter() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let session = net.create_session(ScheduleConfig::new())?;
+    net.input::<f32>(&session, "data")?.fill(1.0);
+    let expected = {
+        net.run_session(&session)?;
+        session.collect_outputs::<f32>()?
+    };
+    drop(net);
+    let session = session.run_async().await?;
+    assert_eq!(session.collect_outputs::<f32>()?, expected);
+    Ok(())
+}
@@ -8,7 +8,7 @@ use tracing_test::traced_test;
 #[test]
 #[traced_test]
 fn compare_cpu_and_coreml_outputs() {
-    let mut net = mnn::Interpreter::from_file("tests/assets/realesr.mnn").unwrap();
+    let net = mnn::Interpreter::from_file("tests/assets/realesr.mnn").unwrap();
     let cpu_config = ScheduleConfig::new();
     let mut coreml_config = ScheduleConfig::new();
     let mut bc = BackendConfig::new();
@@ -41,3 +41,306 @@ fn compare_cpu_and_coreml_outputs() {
             assert_eq!(cpu.host(), coreml.host());
         });
 }
+
+#[allow(dead_code)]
+fn round_trip_device(backend: ForwardType) -> Result<()> {
+    let net = mnn::Interpreter::from_file("tests/assets/realesr.mnn")?;
+    let mut config = ScheduleConfig::new();
+    config.set_type(backend);
+    let session = net.create_session(config)?;
+    let mut device = net.input::<f32>(&session, "data")?;
+    let mut host = device.create_host_tensor_from_device(false);
+    host.host_mut()
+        .iter_mut()
+        .enumerate()
+        .for_each(|(i, x)| *x = (i % 255) as f32);
+    device.copy_from_host_tensor(&host)?;
+    let mut out = device.create_host_tensor_from_device(false);
+    device.copy_to_host_tensor(&mut out)?;
+    assert_eq!(host.host(), out.host());
+    Ok(())
+}
+
+#[cfg(feature = "metal")]
+#[test]
+#[ignore = "Doesn't work on ci"]
+fn round_trip_device_metal() {
+    round_trip_device(ForwardType::Metal).unwrap();
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+#[ignore = "Doesn't work on ci"]
+fn round_trip_device_opencl() {
+    round_trip_device(ForwardType::OpenCL).unwrap();
+}
+
+/// Feed the input of a session on `backend` from a tensor allocated by the same backend
+fn copy_from_session_tensor(backend: ForwardType) -> Result<()> {
+    let net = mnn::Interpreter::from_bytes(Model::new())?;
+    let config = ScheduleConfig::new()
+        .with_type(backend)
+        .with_backup_type(backend);
+    let mut session = net.create_session(config)?;
+    session.resize(&[("data", vec![1, 3, 32, 32])].into())?;
+    let mut staged = Tensor::<SessionDevice<f32>>::create_for_session(&session, "data")?;
+    assert_eq!(staged.shape().dims(), [1, 3, 32, 32]);
+    let mut host = staged.create_host_tensor_from_device(false);
+    host.host_mut()
+        .iter_mut()
+        .enumerate()
+        .for_each(|(i, x)| *x = (i % 255) as f32 / 255.0);
+    staged.copy_from_host_tensor(&host)?;
+    assert_eq!(staged.to_host()?.host(), host.host());
+
+    let mut input = net.input::<f32>(&session, "data")?;
+    input.copy_from(&staged)?;
+    assert_eq!(input.to_host()?.host(), host.host());
+    drop(input);
+    net.run_session(&session)?;
+    let output = session.collect_outputs::<f32>()?;
+
+    // Same output as feeding the input from the host
+    net.input::<f32>(&session, "data")?
+        .copy_from_host_tensor(&host)?;
+    net.run_session(&session)?;
+    assert_eq!(session.collect_outputs::<f32>()?, output);
+
+    let err = Tensor::<SessionDevice<f32>>::create_for_session(&session, "missing")
+        .map(|_| ())
+        .unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::TensorError));
+    let err = Tensor::<SessionDevice<i32>>::create_for_session(&session, "data")
+        .map(|_| ())
+        .unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::HalideTypeMismatch { .. }));
+    drop(staged);
+    Ok(())
+}
+
+#[test]
+fn copy_from_session_tensor_cpu() -> Result<()> {
+    copy_from_session_tensor(ForwardType::CPU)
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+#[ignore = "Doesn't work on ci"]
+fn copy_from_session_tensor_opencl() -> Result<()> {
+    copy_from_session_tensor(ForwardType::OpenCL)
+}
+
+#[test]
+fn forward_type_names_round_trip() {
+    assert!(ForwardType::iter().count() >= 3);
+    for forward_type in ForwardType::iter() {
+        assert_eq!(
+            forward_type.name().parse::<ForwardType>().ok(),
+            Some(forward_type)
+        );
+    }
+    assert_eq!("CPU".parse::<ForwardType>().ok(), Some(ForwardType::CPU));
+    assert!("tpu".parse::<ForwardType>().is_err());
+}
+
+#[test]
+fn cpu_is_available() {
+    assert!(ForwardType::CPU.is_available());
+    assert!(ForwardType::all_available().contains(&ForwardType::CPU));
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+#[ignore = "Doesn't work on ci"]
+fn opencl_is_available() {
+    assert!(ForwardType::OpenCL.is_available());
+}
+
+#[cfg(feature = "opengl")]
+#[test]
+#[ignore = "Doesn't work on ci"]
+fn opengl_session() -> Result<()> {
+    assert!(ForwardType::OpenGL.is_available());
+    let net = mnn::Interpreter::from_bytes(Model::new())?;
+    // Backup type OpenGL so this fails instead of falling back to the cpu
+    let config = ScheduleConfig::new()
+        .with_type(ForwardType::OpenGL)
+        .with_backup_type(ForwardType::OpenGL);
+    let session = net.create_session(config)?;
+    net.input::<f32>(&session, "data")?.fill(1.0);
+    net.run_session(&session)?;
+    assert!(!session.collect_outputs::<f32>()?.is_empty());
+    Ok(())
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+#[ignore = "Doesn't work on ci"]
+fn opencl_memory_image_mode() -> Result<()> {
+    let net = mnn::Interpreter::from_bytes(Model::new())?;
+    let mode = MNNGpuMode::MNN_GPU_TUNING_FAST | MNNGpuMode::MNN_GPU_MEMORY_IMAGE;
+    let config = ScheduleConfig::new()
+        .with_type(ForwardType::OpenCL)
+        .with_backup_type(ForwardType::OpenCL)
+        .with_replaced_gpu_mode(mode);
+    let session = net.create_session(config)?;
+    net.input::<f32>(&session, "data")?.fill(1.0);
+    net.run_session(&session)?;
+    assert!(!session.collect_outputs::<f32>()?.is_empty());
+    Ok(())
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+#[ignore = "Doesn't work on ci"]
+fn opencl_copy_between_backends() -> Result<()> {
+    let net = mnn::Interpreter::from_bytes(Model::new())?;
+    let opencl = || {
+        ScheduleConfig::new()
+            .with_type(ForwardType::OpenCL)
+            .with_backup_type(ForwardType::OpenCL)
+    };
+    let gpu = net.create_session(opencl())?;
+    let other_gpu = net.create_session(opencl())?;
+    let cpu = net.create_session(ScheduleConfig::new())?;
+    let mut input = net.input::<f32>(&gpu, "data")?;
+    let mut host = input.create_host_tensor_from_device(false);
+    host.host_mut()
+        .iter_mut()
+        .enumerate()
+        .for_each(|(i, x)| *x = (i % 255) as f32);
+    input.copy_from_host_tensor(&host)?;
+
+    // Device to host memory of the cpu backend
+    let mut cpu_input = net.input::<f32>(&cpu, "data")?;
+    cpu_input.copy_from(&input)?;
+    assert_eq!(cpu_input.to_host()?.host(), host.host());
+
+    // Device to device, staged through the host
+    let mut other_input = net.input::<f32>(&other_gpu, "data")?;
+    other_input.copy_from(&input)?;
+    assert_eq!(other_input.to_host()?.host(), host.host());
+    drop((input, cpu_input, other_input));
+
+    net.run_session(&gpu)?;
+    net.run_session(&cpu)?;
+    let outputs = net.outputs(&gpu);
+    let gpu_output = outputs.iter().next().expect("No output").tensor::<f32>()?;
+    let mut output = gpu_output.create_host_tensor_from_device(false);
+    output.copy_from(&gpu_output)?;
+    assert_eq!(output.host(), gpu_output.to_host()?.host());
+    Ok(())
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+#[ignore = "Doesn't work on ci"]
+fn opencl_wrap_device() -> Result<()> {
+    let net = mnn::Interpreter::from_bytes(Model::new())?;
+    let opencl = || {
+        ScheduleConfig::new()
+            .with_type(ForwardType::OpenCL)
+            .with_backup_type(ForwardType::OpenCL)
+            .with_replaced_gpu_mode(MNNGpuMode::MNN_GPU_MEMORY_BUFFER)
+    };
+    // The cl_mem of another session stands in for a buffer allocated by the application
+    let owner = net.create_session(opencl())?;
+    let mut owner_input = net.input::<f32>(&owner, "data")?;
+    let mut host = owner_input.create_host_tensor_from_device(false);
+    host.host_mut()
+        .iter_mut()
+        .enumerate()
+        .for_each(|(i, x)| *x = (i % 255) as f32);
+    owner_input.copy_from_host_tensor(&host)?;
+    owner_input.wait(MapType::MAP_TENSOR_WRITE, true);
+    // In buffer mode the device id is a cl::Buffer, which only holds the cl_mem
+    let cl_mem = unsafe {
+        *(owner_input.raw_device().expect("No device memory") as *const *const libc::c_void)
+    };
+    let external = unsafe {
+        Tensor::<Device<f32>>::wrap_device(
+            cl_mem,
+            owner_input.shape(),
+            DimensionType::NC4HW4,
+            ForwardType::OpenCL,
+        )
+    }?;
+
+    let session = net.create_session(opencl())?;
+    let mut input = net.input::<f32>(&session, "data")?;
+    input.copy_from(&external)?;
+    assert_eq!(input.to_host()?.host(), host.host());
+    drop((input, external));
+    net.run_session(&session)?;
+    assert!(!session.collect_outputs::<f32>()?.is_empty());
+    Ok(())
+}
+
+#[cfg(all(feature = "coreml", target_os = "macos"))]
+#[test]
+fn coreml_compute_units() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    for compute_units in [
+        ComputeUnits::All,
+        ComputeUnits::CpuOnly,
+        ComputeUnits::CpuAndGpu,
+        ComputeUnits::CpuAndNeuralEngine,
+    ] {
+        let config = ScheduleConfig::new()
+            .with_type(ForwardType::CoreML)
+            .with_backend_config(
+                BackendConfig::new().with_coreml_config(CoreMLConfig { compute_units }),
+            );
+        let session = net.create_session(config)?;
+        net.input::<f32>(&session, "data")?.fill(1.0);
+        net.run_session(&session)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+#[ignore = "Doesn't work on ci"]
+fn opencl_auto_backend() -> Result<()> {
+    let net = mnn::Interpreter::from_bytes(Model::new())?;
+    let session = net.create_session(ScheduleConfig::auto_backend(ForwardType::OpenCL))?;
+    let backends = session.backends().expect("No backends");
+    assert!(backends.contains(&ForwardType::OpenCL));
+    assert!(backends.contains(&ForwardType::CPU));
+    net.input::<f32>(&session, "data")?.fill(0.5);
+    net.run_session(&session)?;
+
+    // The mode of the interpreter is back to fixed backends for the next sessions
+    let fixed = net.create_session(
+        ScheduleConfig::new()
+            .with_type(ForwardType::OpenCL)
+            .with_backup_type(ForwardType::OpenCL),
+    )?;
+    let backends = fixed.backends().expect("No backends");
+    assert!(backends.contains(&ForwardType::OpenCL));
+    Ok(())
+}
+
+#[cfg(feature = "vulkan")]
+#[test]
+#[ignore = "Doesn't work on ci"]
+fn vulkan_device_selection() -> Result<()> {
+    let devices = mnn::vulkan_devices();
+    assert!(!devices.is_empty());
+    let net = mnn::Interpreter::from_bytes(Model::new())?;
+    let index = devices.len() as u32 - 1;
+    let config = ScheduleConfig::new()
+        .with_type(ForwardType::Vulkan)
+        .with_backup_type(ForwardType::Vulkan)
+        .with_backend_config(BackendConfig::new().with_vulkan_device(index)?);
+    let session = net.create_session(config)?;
+    net.input::<f32>(&session, "data")?.fill(1.0);
+    net.run_session(&session)?;
+    assert!(!session.collect_outputs::<f32>()?.is_empty());
+    assert!(
+        BackendConfig::new()
+            .with_vulkan_device(devices.len() as u32)
+            .is_err()
+    );
+    Ok(())
+}
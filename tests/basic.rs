@@ -19,12 +19,12 @@ fn test_basic_opencl() -> Result<(), Box<dyn std::error::Error>> {
     let backend = ForwardType::OpenCL;
     let realesr = std::path::Path::new("tests/assets/realesr.mnn");
 
-    let mut net = mnn::Interpreter::from_file(realesr)?;
+    let net = mnn::Interpreter::from_file(realesr)?;
     net.set_cache_file(realesr.with_extension("cache"), 128)?;
     let mut config = ScheduleConfig::new();
     config.set_type(backend);
-    let mut session = net.create_session(config)?;
-    net.update_cache_file(&mut session)?;
+    let session = net.create_session(config)?;
+    net.update_cache_file(&session)?;
 
     net.inputs(&session).iter().for_each(|x| {
         let mut tensor = x.tensor::<f32>().expect("No tensor");
@@ -46,13 +46,184 @@ fn test_basic_opencl() -> Result<(), Box<dyn std::error::Error>> {
     // drop(net);
     Ok(())
 }
+#[cfg(feature = "opencl")]
+#[test]
+#[ignore = "Doesn't work on ci"]
+fn test_cache_file_opencl() -> Result<(), Box<dyn std::error::Error>> {
+    let realesr = std::path::Path::new("tests/assets/realesr.mnn");
+    let cache = std::env::temp_dir().join("mnn-rs-test-cache-file-opencl.cache");
+    let _ = std::fs::remove_file(&cache);
+    let create_session = || -> Result<(), Box<dyn std::error::Error>> {
+        let net = mnn::Interpreter::from_file(realesr)?;
+        net.set_cache_file(&cache, 128)?;
+        let mut config = ScheduleConfig::new();
+        config.set_type(ForwardType::OpenCL);
+        let session = net.create_session(config)?;
+        net.update_cache_file(&session)?;
+        Ok(())
+    };
+
+    // The first session creates the cache
+    create_session()?;
+    let created = std::fs::read(&cache)?;
+    assert!(!created.is_empty());
+    // The second one loads it, MNN only rewrites the file if the cache didn't match
+    create_session()?;
+    assert_eq!(std::fs::read(&cache)?, created);
+    std::fs::remove_file(&cache)?;
+    Ok(())
+}
+
+#[test]
+fn test_auto_cache_keyed_by_model() -> Result<()> {
+    let dir = std::env::temp_dir().join(format!("mnn-rs-test-auto-cache-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let net = mnn::Interpreter::from_file("tests/assets/realesr.mnn")?;
+    let cache = net.enable_auto_cache(&dir, ForwardType::CPU)?;
+    assert!(dir.is_dir());
+    assert_eq!(cache.parent(), Some(dir.as_path()));
+    let session = net.create_session(ScheduleConfig::new())?;
+    net.update_cache_file(&session)?;
+    let leftovers = std::fs::read_dir(&dir)?
+        .filter(|entry| entry.as_ref().is_ok_and(|entry| entry.path() != cache))
+        .count();
+    assert_eq!(leftovers, 0);
+
+    // The same model, loaded again, finds the same cache
+    let same = mnn::Interpreter::from_bytes(Model::new())?;
+    assert_eq!(same.enable_auto_cache(&dir, ForwardType::CPU)?, cache);
+    // A different backend or model doesn't
+    assert_ne!(same.enable_auto_cache(&dir, ForwardType::Auto)?, cache);
+    let changed = mnn::Interpreter::from_file("tests/assets/resizing.mnn")?;
+    assert_ne!(changed.enable_auto_cache(&dir, ForwardType::CPU)?, cache);
+
+    drop((session, net, same, changed));
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_session_mode_input_user() -> Result<()> {
+    let mut net = mnn::Interpreter::from_file("tests/assets/realesr.mnn")?;
+    net.set_session_mode(SessionMode::InputUser);
+    let session = net.create_session(ScheduleConfig::new())?;
+    let mut input = net.input::<f32>(&session, "data")?;
+    let mut host = input.create_host_tensor_from_device(false);
+    let mut outputs = Vec::new();
+    for value in [0.0f32, 1.0] {
+        host.fill(value);
+        input.copy_from_host_tensor(&host)?;
+        net.run_session(&session)?;
+        let session_outputs = net.outputs(&session);
+        let output = session_outputs.iter().next().expect("No output");
+        outputs.push(output.tensor::<f32>()?.create_host_tensor_from_device(true));
+    }
+    assert_ne!(outputs[0].host(), outputs[1].host());
+    Ok(())
+}
+
+#[test]
+fn test_output_to_host() -> Result<()> {
+    let net = mnn::Interpreter::from_file("tests/assets/realesr.mnn")?;
+    let session = net.create_session(ScheduleConfig::new())?;
+    net.input::<f32>(&session, "data")?.fill(1.0);
+    net.run_session(&session)?;
+    let outputs = net.outputs(&session);
+    let output = outputs.iter().next().expect("No output").tensor::<f32>()?;
+    let host = output.to_host()?;
+    assert_eq!(host.shape().dims(), [1, 3, 2048, 2048]);
+    assert_eq!(
+        host.host(),
+        output.create_host_tensor_from_device(true).host()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_release_model() -> Result<()> {
+    let mut net = mnn::Interpreter::from_file("tests/assets/realesr.mnn")?;
+    let session = net.create_session(ScheduleConfig::new())?;
+    net.release_model();
+    net.input::<f32>(&session, "data")?.fill(1.0);
+    net.run_session(&session)?;
+    let err = net.create_session(ScheduleConfig::new()).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InterpreterError));
+    // The error is raised before calling MNN so it doesn't take the MNN logs
+    assert!(!format!("{err:?}").contains("MNN version"));
+    Ok(())
+}
+
+#[test]
+fn test_collect_outputs() -> Result<()> {
+    let net = mnn::Interpreter::from_file("tests/assets/realesr.mnn")?;
+    let session = net.create_session(ScheduleConfig::new())?;
+    net.input::<f32>(&session, "data")?.fill(1.0);
+    net.run_session(&session)?;
+    let outputs = session.collect_outputs::<f32>()?;
+    assert_eq!(outputs.len(), net.outputs(&session).iter().count());
+    for (name, data) in &outputs {
+        let expected = net.output::<f32>(&session, name)?.to_host()?;
+        assert_eq!(data.as_slice(), expected.host());
+    }
+    let err = session.collect_outputs::<i32>().unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::HalideTypeMismatch { .. }));
+    Ok(())
+}
+
+#[test]
+fn test_session_hint_cpu() -> Result<()> {
+    let mut net = mnn::Interpreter::from_file("tests/assets/realesr.mnn")?;
+    net.set_session_hint(SessionHint::MaxTuningNumber, 0);
+    net.set_session_hint(SessionHint::MemAllocatorType, 1);
+    let session = net.create_session(ScheduleConfig::new())?;
+    net.run_session(&session)?;
+    Ok(())
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+#[ignore = "Doesn't work on ci"]
+fn test_session_hint_opencl() -> Result<()> {
+    // The median of a few creations, a single one is too noisy to compare
+    let create_session = |tuning: Option<i32>| -> Result<std::time::Duration> {
+        let mut timings = (0..5)
+            .map(|_| {
+                let mut net = mnn::Interpreter::from_file("tests/assets/realesr.mnn")?;
+                if let Some(tuning) = tuning {
+                    net.set_session_hint(SessionHint::MaxTuningNumber, tuning);
+                }
+                let mut config = ScheduleConfig::new();
+                config.set_type(ForwardType::OpenCL);
+                let now = std::time::Instant::now();
+                let _session = net.create_session(config)?;
+                Ok(now.elapsed())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        timings.sort();
+        Ok(timings[timings.len() / 2])
+    };
+    let tuned = create_session(None)?;
+    let untuned = create_session(Some(0))?;
+    // Leave some margin for the noise of the medians
+    assert!(untuned <= tuned + tuned / 10);
+    Ok(())
+}
+
 #[cfg(feature = "coreml")]
 #[test]
 fn test_basic_coreml() {
     test_basic(ForwardType::CoreML).unwrap();
 }
+#[cfg(all(feature = "nnapi", target_os = "android"))]
+#[test]
+#[ignore = "Doesn't work on ci"]
+fn test_basic_nnapi() {
+    test_basic(ForwardType::Nnapi).unwrap();
+}
 #[cfg(feature = "opengl")]
 #[test]
+#[ignore = "Doesn't work on ci"]
 fn test_basic_opengl() {
     test_basic(ForwardType::OpenGL).unwrap();
 }
@@ -68,3 +239,413 @@ fn test_multi_path_cpu_cpu() {
 // fn test_multi_path_opencl_cpu() {
 //     test_multipath_session(ForwardType::OpenCL, ForwardType::CPU).unwrap();
 // }
+
+#[test]
+fn test_session_info_cpu() -> Result<()> {
+    let net = mnn::Interpreter::from_file("tests/assets/realesr.mnn")?;
+    let mut config = ScheduleConfig::new();
+    config.set_type(ForwardType::CPU);
+    let session = net.create_session(config)?;
+    assert!(session.memory_mb().is_some_and(|m| m > 0.0));
+    assert!(session.flops().is_some_and(|f| f > 0.0));
+    assert_eq!(session.backends(), Some(vec![ForwardType::CPU]));
+    assert_eq!(session.actual_backend(), Some(ForwardType::CPU));
+    Ok(())
+}
+
+#[test]
+fn test_auto_backend_is_concrete() -> Result<()> {
+    let net = mnn::Interpreter::from_bytes(Model::new())?;
+    let mut session = net.create_session(ScheduleConfig::new().with_type(ForwardType::Auto))?;
+    let backend = session.actual_backend().expect("No backend");
+    assert!(!matches!(backend, ForwardType::Auto | ForwardType::All));
+    assert!(session.backends().is_some_and(|b| b.contains(&backend)));
+
+    session.resize(&[("data", vec![1, 3, 32, 32])].into())?;
+    net.input::<f32>(&session, "data")?.fill(0.5);
+    net.run_session(&session)?;
+    for output in net.outputs(&session).iter() {
+        let output = output.tensor::<f32>()?.to_host()?;
+        assert!(output.host().iter().all(|v| v.is_finite()));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_low_precision_cpu() -> Result<()> {
+    let net = mnn::Interpreter::from_file("tests/assets/realesr.mnn")?;
+    let config = ScheduleConfig::new()
+        .with_type(ForwardType::CPU)
+        .with_backend_config(BackendConfig::default().with_precision_mode(PrecisionMode::FP16));
+    let session = net.create_session(config)?;
+    net.inputs(&session).iter().for_each(|x| {
+        x.tensor::<f32>().expect("No tensor").fill(1.0f32);
+    });
+    net.run_session(&session)?;
+    // Low precision only changes the internal compute type, outputs are still exposed as f32
+    for output in net.outputs(&session).iter() {
+        let tensor = output.tensor::<f32>()?;
+        assert_eq!(tensor.shape().as_ref(), [1, 3, 2048, 2048]);
+    }
+    Ok(())
+}
+
+/// Run realesr on the cpu with `precision` and a fixed 64x64 input
+#[cfg(any(feature = "arm82", feature = "bf16"))]
+fn run_with_precision(net: &Interpreter, precision: PrecisionMode) -> Result<Vec<f32>> {
+    let config = ScheduleConfig::new()
+        .with_type(ForwardType::CPU)
+        .with_backend_config(BackendConfig::new().with_precision_mode(precision));
+    let mut session = net.create_session(config)?;
+    session.resize(&[("data", vec![1, 3, 64, 64])].into())?;
+    let input = (0..3 * 64 * 64)
+        .map(|i| (i % 255) as f32 / 255.0)
+        .collect::<Vec<_>>();
+    let outputs = session.run_typed([("data", input.as_slice().into())], false)?;
+    let output = outputs.into_values().next().ok_or("No output")?;
+    Ok(output.as_f32().ok_or("Output isn't f32")?.to_vec())
+}
+
+/// The largest error of `low` relative to `normal`
+#[cfg(any(feature = "arm82", feature = "bf16"))]
+fn max_relative_error(normal: &[f32], low: &[f32]) -> f32 {
+    assert_eq!(normal.len(), low.len());
+    normal
+        .iter()
+        .zip(low)
+        .map(|(n, l)| (n - l).abs() / n.abs().max(1.0))
+        .fold(0.0f32, f32::max)
+}
+
+#[cfg(feature = "arm82")]
+#[test]
+fn test_low_precision_matches_normal() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let normal = run_with_precision(&net, PrecisionMode::Normal)?;
+    let low = run_with_precision(&net, PrecisionMode::Low)?;
+    // fp16 has a 10 bit mantissa, allow for the rounding accumulated over the layers
+    let max_error = max_relative_error(&normal, &low);
+    assert!(max_error < 5e-2, "max relative error {max_error}");
+    Ok(())
+}
+
+#[cfg(feature = "bf16")]
+#[test]
+fn test_bf16_precision_matches_normal() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let normal = run_with_precision(&net, PrecisionMode::Normal)?;
+    let bf16 = run_with_precision(&net, PrecisionMode::BF16)?;
+    // bf16 only has a 7 bit mantissa
+    let max_error = max_relative_error(&normal, &bf16);
+    assert!(max_error < 1e-1, "max relative error {max_error}");
+    Ok(())
+}
+
+#[test]
+fn test_session_tensors_by_name() -> Result<()> {
+    let model = std::fs::read("tests/assets/resizing.mnn")?;
+    let net = Interpreter::from_bytes(&model)?;
+    let session = net.create_session(ScheduleConfig::new())?;
+    let inputs = session.inputs();
+    let mut names = inputs.keys().map(String::as_str).collect::<Vec<_>>();
+    names.sort_unstable();
+    assert_eq!(names, ["inpainted", "mask", "original"]);
+    assert!(session.input("mask").is_some());
+    assert!(session.input("missing").is_none());
+    let outputs = session.outputs();
+    assert!(!outputs.is_empty());
+    for name in outputs.keys() {
+        assert!(session.output(name).is_some());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_session_describe() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let session = net.create_session(ScheduleConfig::new())?;
+    let spec = session.describe();
+    assert_eq!(spec.inputs.len(), 1);
+    let data = spec.input("data").expect("No data input");
+    assert_eq!(data.data_type, DataType::F32);
+    assert_eq!(data.shape, [1, 3, 512, 512]);
+    assert_eq!(data.rank(), 4);
+    assert!(!data.is_dynamic());
+    assert_eq!(spec.outputs.len(), 1);
+    assert_eq!(spec.outputs[0].data_type, DataType::F32);
+    assert_eq!(spec.outputs[0].shape, [1, 3, 2048, 2048]);
+
+    let model = std::fs::read("tests/assets/resizing.mnn")?;
+    let net = Interpreter::from_bytes(&model)?;
+    let session = net.create_session(ScheduleConfig::new())?;
+    let spec = session.describe();
+    let mut names = spec
+        .inputs
+        .iter()
+        .map(|input| input.name.as_str())
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    assert_eq!(names, ["inpainted", "mask", "original"]);
+    assert!(spec.inputs.iter().all(TensorSpec::is_dynamic));
+    assert!(
+        spec.inputs
+            .iter()
+            .all(|input| input.data_type == DataType::F32)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_from_raw_parts() -> Result<()> {
+    let bytes = Model::new().as_ref().to_vec();
+    let net = unsafe { Interpreter::from_raw_parts(bytes.as_ptr(), bytes.len())? };
+    // MNN keeps its own copy of the model
+    drop(bytes);
+    let session = net.create_session(ScheduleConfig::new())?;
+    net.run_session(&session)?;
+    assert!(unsafe { Interpreter::from_raw_parts(std::ptr::null(), 0) }.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_from_bytes_types() -> Result<()> {
+    let model = Model::new();
+    let bytes: &[u8] = model.as_ref();
+    let run = |net: Interpreter| -> Result<()> {
+        let session = net.create_session(ScheduleConfig::new())?;
+        net.run_session(&session)?;
+        Ok(())
+    };
+    run(Interpreter::from_bytes(bytes)?)?;
+    run(Interpreter::from_bytes(bytes.to_vec())?)?;
+    run(Interpreter::from_bytes(&bytes.to_vec())?)?;
+    run(Interpreter::from_bytes(Box::<[u8]>::from(bytes))?)?;
+    run(Interpreter::from_bytes(std::borrow::Cow::Borrowed(bytes))?)?;
+    run(Interpreter::try_from(bytes)?)?;
+    run(bytes.try_into()?)?;
+    assert!(Interpreter::try_from(&[][..]).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_create_from_reader() -> Result<()> {
+    let model = Model::new();
+    let bytes: &[u8] = model.as_ref();
+    let net = Interpreter::create_from_reader(std::io::Cursor::new(bytes), None)?;
+    let session = net.create_session(ScheduleConfig::new())?;
+    net.input::<f32>(&session, "data")?.fill(1.0);
+    net.run_session(&session)?;
+
+    let expected = {
+        let net = Interpreter::from_bytes(bytes)?;
+        let session = net.create_session(ScheduleConfig::new())?;
+        net.input::<f32>(&session, "data")?.fill(1.0);
+        net.run_session(&session)?;
+        session.collect_outputs::<f32>()?
+    };
+    assert_eq!(session.collect_outputs::<f32>()?, expected);
+
+    let limited = |limit| Interpreter::create_from_reader(std::io::Cursor::new(bytes), Some(limit));
+    assert!(limited(bytes.len()).is_ok());
+    assert!(limited(bytes.len() - 1).is_err());
+    assert!(limited(usize::MAX).is_ok());
+    Ok(())
+}
+
+#[test]
+fn test_set_external_file_after_session() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let _session = net.create_session(ScheduleConfig::new())?;
+    assert!(net.set_external_file("tests/assets/realesr.mnn").is_err());
+    Ok(())
+}
+
+/// Needs a model converted with `MNNConvert --saveExternalData` to tests/assets/external.mnn
+#[test]
+#[ignore = "Needs a model with external weights"]
+fn test_set_external_file() -> Result<()> {
+    let graph = std::path::Path::new("tests/assets/external.mnn");
+    let net = Interpreter::from_file(graph)?;
+    net.set_external_file(graph.with_extension("mnn.weight"))?;
+    let session = net.create_session(ScheduleConfig::new())?;
+    for input in net.inputs(&session).iter() {
+        input.tensor::<f32>()?.fill(1.0);
+    }
+    net.run_session(&session)?;
+    let outputs = session.collect_outputs::<f32>()?;
+    assert!(!outputs.is_empty());
+    assert!(outputs.values().flatten().all(|x| x.is_finite()));
+    Ok(())
+}
+
+#[test]
+fn test_clone_interpreter() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let before = net.create_session(ScheduleConfig::new())?;
+    let clone = net.clone();
+    drop(net);
+    // Sessions of the dropped handle keep working through the clone
+    clone.input::<f32>(&before, "data")?.fill(1.0);
+    clone.run_session(&before)?;
+    let expected = before.collect_outputs::<f32>()?;
+
+    let session = clone.create_session(ScheduleConfig::new())?;
+    clone.input::<f32>(&session, "data")?.fill(1.0);
+    let handle = std::thread::spawn({
+        let clone = clone.clone();
+        move || -> Result<()> {
+            let session = clone.create_session(ScheduleConfig::new())?;
+            clone.run_session(&session)?;
+            Ok(())
+        }
+    });
+    clone.run_session(&session)?;
+    assert_eq!(session.collect_outputs::<f32>()?, expected);
+    handle.join().expect("Thread panicked")?;
+    Ok(())
+}
+
+#[test]
+fn test_session_outlives_interpreter() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let mut session = net.create_session(ScheduleConfig::new())?;
+    let clone = net.clone();
+    drop(net);
+    drop(clone);
+    // The session keeps the model alive on its own
+    let input = session.input("data").expect("No input");
+    input.tensor::<f32>()?.fill(1.0);
+    session.run_with_deadline(std::time::Instant::now() + std::time::Duration::from_secs(60))?;
+    let outputs = session.collect_outputs::<f32>()?;
+    assert!(outputs.values().flatten().all(|x| x.is_finite()));
+    drop(session);
+    Ok(())
+}
+
+#[test]
+fn test_missing_model_is_a_clean_error() {
+    let missing = "tests/assets/does-not-exist.mnn";
+    let error = Interpreter::from_file(missing).expect_err("Loaded a missing model");
+    assert!(matches!(error.kind(), ErrorKind::IOError));
+    let error = Interpreter::from_bytes(b"not a model").expect_err("Loaded an invalid model");
+    assert!(matches!(error.kind(), ErrorKind::ModelLoad));
+    assert!(format!("{error:?}").contains("MNN version"));
+    assert!(Interpreter::from_bytes([0u8; 0]).is_err());
+    let error = Module::load(&["data"], &["output"], missing, &ScheduleConfig::new())
+        .expect_err("Loaded a missing module");
+    assert!(matches!(error.kind(), ErrorKind::IOError));
+}
+
+#[test]
+fn test_version() {
+    let version = mnn::version();
+    let components = version
+        .split('.')
+        .map(|component| component.parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_or_else(|_| panic!("Invalid version {version}"));
+    assert_eq!(components.len(), 3, "Invalid version {version}");
+    assert_eq!(mnn::linked_version(), version);
+    let error = Interpreter::from_bytes(b"not a model").expect_err("Loaded an invalid model");
+    assert!(format!("{error:?}").contains(version));
+}
+
+#[test]
+fn test_model_metadata() -> Result<()> {
+    let mut net = Interpreter::from_bytes(Model::new())?;
+    let metadata = net.metadata()?;
+    assert!(!metadata.version.is_empty());
+    assert_eq!(net.clone().metadata()?, metadata);
+    net.release_model();
+    assert!(net.metadata().is_err());
+    Ok(())
+}
+
+#[test]
+fn test_schema_version_too_new() -> Result<()> {
+    let version = Interpreter::from_bytes(Model::new())?.metadata()?.version;
+    // Flatbuffers strings are nul terminated, bump every digit of the recorded version
+    let needle = format!("{version}\0");
+    let mut model = Model::new().as_ref().to_vec();
+    let at = model
+        .windows(needle.len())
+        .position(|window| window == needle.as_bytes())
+        .expect("The model doesn't record its version");
+    let bogus: String = version
+        .chars()
+        .map(|c| if c.is_ascii_digit() { '9' } else { c })
+        .collect();
+    model[at..at + bogus.len()].copy_from_slice(bogus.as_bytes());
+
+    let path = std::env::temp_dir().join(format!("mnn-rs-schema-{}.mnn", std::process::id()));
+    std::fs::write(&path, &model)?;
+    let from_file = Interpreter::from_file(&path);
+    std::fs::remove_file(&path)?;
+    // The version is only checked to explain a model MNN can't load, the same way for both
+    match (Interpreter::from_bytes(&model), from_file) {
+        (Ok(_), Ok(_)) => {}
+        (Err(from_bytes), Err(from_file)) => {
+            for error in [from_bytes, from_file] {
+                match error.kind() {
+                    ErrorKind::SchemaVersion { model, supported } => {
+                        assert_eq!(*model, bogus);
+                        assert_eq!(supported, mnn::linked_version());
+                    }
+                    kind => panic!("Unexpected error {kind:?}"),
+                }
+            }
+        }
+        (from_bytes, from_file) => panic!(
+            "from_bytes ({}) and from_file ({}) disagree",
+            from_bytes.is_ok(),
+            from_file.is_ok()
+        ),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_update_session_to_model() -> Result<()> {
+    let mut net = Interpreter::from_bytes(Model::new())?;
+    assert_eq!(net.model_buffer(), Model::new().as_ref());
+    let session = net.create_session(ScheduleConfig::new())?;
+    net.input::<f32>(&session, "data")?.fill(0.5);
+    net.run_session(&session)?;
+    let expected = session.collect_outputs::<f32>()?;
+
+    net.update_session_to_model(&session)?;
+    let updated = Interpreter::from_bytes(net.model_buffer())?;
+    let updated_session = updated.create_session(ScheduleConfig::new())?;
+    updated.input::<f32>(&updated_session, "data")?.fill(0.5);
+    updated.run_session(&updated_session)?;
+    assert_eq!(updated_session.collect_outputs::<f32>()?, expected);
+
+    let clone = net.clone();
+    net.release_model();
+    assert!(net.model_buffer().is_empty());
+    assert!(net.update_session_to_model(&session).is_err());
+    // Released for every clone
+    assert!(clone.model_buffer().is_empty());
+    assert!(clone.metadata().is_err());
+    assert!(clone.create_session(ScheduleConfig::new()).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_run_with_deadline() -> Result<()> {
+    use std::time::{Duration, Instant};
+    let net = Interpreter::from_bytes(Model::new())?;
+    let mut session = net.create_session(ScheduleConfig::new())?;
+    net.input::<f32>(&session, "data")?.fill(0.5);
+
+    let err = session
+        .run_with_deadline(Instant::now())
+        .expect_err("The run should time out");
+    assert!(matches!(err.kind(), ErrorKind::Timeout));
+
+    session.run_with_deadline(Instant::now() + Duration::from_secs(600))?;
+    let outputs = session.collect_outputs::<f32>()?;
+    net.run_session(&session)?;
+    assert_eq!(session.collect_outputs::<f32>()?, outputs);
+    Ok(())
+}
@@ -0,0 +1,64 @@
+pub mod common;
+use common::*;
+
+/// 3x16x16 samples to keep the upscaling model fast
+const SAMPLE: [i32; 4] = [1, 3, 16, 16];
+
+fn sample(seed: usize) -> Vec<f32> {
+    (0..3 * 16 * 16)
+        .map(|i| ((i * 7 + seed * 31) % 255) as f32 / 255.0)
+        .collect()
+}
+
+#[test]
+fn test_run_batched_matches_single_runs() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let inputs = (0..5).map(sample).collect::<Vec<_>>();
+
+    let mut single = net.create_session(ScheduleConfig::new())?;
+    let mut input = net.input::<f32>(&single, "data")?;
+    net.resize_tensor(&mut input, SAMPLE);
+    drop(input);
+    net.resize_session(&mut single);
+    let mut expected = Vec::new();
+    for item in &inputs {
+        let mut input = net.input::<f32>(&single, "data")?;
+        let mut host = input.create_host_tensor_from_device(false);
+        host.host_mut().copy_from_slice(item);
+        input.copy_from_host_tensor(&host)?;
+        net.run_session(&single)?;
+        let outputs = single.collect_outputs::<f32>()?;
+        expected.push(outputs.into_values().next().expect("No output"));
+    }
+
+    let mut session = net.create_session(ScheduleConfig::new())?;
+    let mut input = net.input::<f32>(&session, "data")?;
+    net.resize_tensor(&mut input, SAMPLE);
+    drop(input);
+    net.resize_session(&mut session);
+    let outputs = session.run_batched(&inputs, 2)?;
+    assert_eq!(outputs.len(), 5);
+    for (output, expected) in outputs.iter().zip(&expected) {
+        assert_eq!(output.len(), 3 * 64 * 64);
+        output
+            .iter()
+            .zip(expected)
+            .for_each(|(o, e)| assert!((o - e).abs() < 1e-3, "{o} != {e}"));
+    }
+    // The input is resized back to a single sample
+    assert_eq!(
+        net.input::<f32>(&session, "data")?.shape().dims(),
+        [1, 3, 16, 16]
+    );
+
+    assert!(session.run_batched(&[vec![0.0f32; 3]], 2).is_err());
+    assert!(session.run_batched(&inputs, 0).is_err());
+    // The input is also resized back if a later chunk fails
+    let invalid = vec![sample(0), sample(1), vec![0.0f32; 3]];
+    assert!(session.run_batched(&invalid, 2).is_err());
+    assert_eq!(
+        net.input::<f32>(&session, "data")?.shape().dims(),
+        [1, 3, 16, 16]
+    );
+    Ok(())
+}
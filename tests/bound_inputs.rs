@@ -0,0 +1,106 @@
+pub mod common;
+use common::*;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+/// Counts the allocations of the current thread so other tests don't interfere
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|a| a.set(a.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+#[test]
+fn test_bound_inputs_dont_allocate() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let mut session = net.create_session(ScheduleConfig::new())?;
+    session.bind_input::<f32>("data")?;
+    assert!(session.bound_input_mut::<i32>("data").is_err());
+    // warmup
+    session.bound_input_mut::<f32>("data")?.fill(0.5);
+    session.run_with_inputs()?;
+
+    let before = allocations();
+    for frame in 0..3 {
+        session.bound_input_mut::<f32>("data")?.fill(frame as f32);
+        session.run_with_inputs()?;
+    }
+    assert_eq!(allocations() - before, 0);
+
+    let expected = {
+        let session = net.create_session(ScheduleConfig::new())?;
+        net.input::<f32>(&session, "data")?.fill(2.0);
+        net.run_session(&session)?;
+        session.collect_outputs::<f32>()?
+    };
+    assert_eq!(session.collect_outputs::<f32>()?, expected);
+    Ok(())
+}
+
+#[test]
+fn test_bind_unknown_input() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let mut session = net.create_session(ScheduleConfig::new())?;
+    assert!(session.bind_input::<f32>("missing").is_err());
+    assert!(session.bound_input_mut::<f32>("data").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_with_input_ptrs_doesnt_copy() -> Result<()> {
+    let mut net = Interpreter::from_bytes(Model::new())?;
+    let mut data = (0..3 * 512 * 512)
+        .map(|i| (i % 255) as f32 / 255.0)
+        .collect::<Vec<f32>>();
+    let expected = {
+        let session = net.create_session(ScheduleConfig::new())?;
+        net.input::<f32>(&session, "data")?
+            .host_mut()
+            .copy_from_slice(&data);
+        net.run_session(&session)?;
+        session.collect_outputs::<f32>()?
+    };
+
+    net.set_session_mode(SessionMode::InputUser);
+    let mut session = net.create_session(ScheduleConfig::new())?;
+    let own = net.input::<f32>(&session, "data")?.host().as_ptr();
+    let mut short = data[1..].to_vec();
+    let short = [("data", short.as_mut_slice())];
+    assert!(session.with_input_ptrs(short, |_| ()).is_err());
+    let mut ints = vec![0i32; data.len()];
+    let ints = [("data", ints.as_mut_slice())];
+    assert!(session.with_input_ptrs(ints, |_| ()).is_err());
+    assert_eq!(net.input::<f32>(&session, "data")?.host().as_ptr(), own);
+    let ptr = data.as_ptr();
+    let outputs = session.with_input_ptrs([("data", data.as_mut_slice())], |inputs| {
+        // The input points at the caller's buffer instead of a copy
+        let input = net.input::<f32>(inputs.session(), "data")?;
+        assert_eq!(input.host().as_ptr(), ptr);
+        drop(input);
+        inputs.run()?;
+        inputs.session().collect_outputs::<f32>()
+    })??;
+    assert_eq!(outputs, expected);
+    assert_eq!(net.input::<f32>(&session, "data")?.host().as_ptr(), own);
+    Ok(())
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "converter")]
+pub mod common;
+use common::*;
+
+#[test]
+fn test_convert_model() -> Result<()> {
+    let dir = std::env::temp_dir().join(format!("mnn-converter-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("Failed to create the temp dir");
+    let converted = dir.join("realesr.mnn");
+    // No onnx model in the assets, re-run the converter on the mnn model instead
+    convert_model("tests/assets/realesr.mnn", &converted, ModelFormat::Mnn)?;
+    let net = Interpreter::from_file(&converted)?;
+    // convert_model always writes the MNN bizCode
+    assert_eq!(net.metadata()?.biz_code, "MNN");
+    let session = net.create_session(ScheduleConfig::new())?;
+    assert_eq!(
+        net.input::<f32>(&session, "data")?.shape().as_ref(),
+        [1, 3, 512, 512]
+    );
+    std::fs::remove_dir_all(&dir).ok();
+    Ok(())
+}
+
+#[test]
+fn test_convert_missing_model() {
+    let error = convert_model("does-not-exist.onnx", "out.mnn", ModelFormat::Onnx)
+        .expect_err("Converted a missing model");
+    assert!(matches!(error.kind(), ErrorKind::IOError));
+}
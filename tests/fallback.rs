@@ -0,0 +1,42 @@
+pub mod common;
+use common::*;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn test_create_session_with_fallback() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let backends = [
+        #[cfg(feature = "vulkan")]
+        ForwardType::Vulkan,
+        #[cfg(feature = "metal")]
+        ForwardType::Metal,
+        ForwardType::CPU,
+    ];
+    let expected = backends
+        .iter()
+        .position(|backend| backend.is_available())
+        .expect("CPU is always available");
+
+    let records = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&records);
+    set_log_handler(move |record| {
+        if record.message.starts_with("Failed to create a session") {
+            sink.lock().unwrap().push(record.message.to_string());
+        }
+    });
+    let result = net.create_session_with_fallback(ScheduleConfig::new(), &backends);
+    clear_log_handler();
+
+    let (session, backend) = result?;
+    // On a cpu only machine this is always the cpu
+    assert_eq!(backend, backends[expected]);
+    assert_eq!(session.backends(), Some(vec![backend]));
+    // One record for every backend that was tried before
+    assert_eq!(records.lock().unwrap().len(), expected);
+
+    assert!(
+        net.create_session_with_fallback(ScheduleConfig::new(), &[])
+            .is_err()
+    );
+    Ok(())
+}
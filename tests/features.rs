@@ -0,0 +1,48 @@
+//! The build script of mnn-sys rejects feature combinations that can't work before building MNN
+
+/// Run `cargo check` on mnn-sys with `args` and return whether it succeeded and its stderr
+fn check_mnn_sys(args: &[&str]) -> (bool, String) {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".into());
+    // A separate target dir, the one of the running tests is locked
+    let output = std::process::Command::new(cargo)
+        .args(["check", "--quiet", "-p", "mnn-sys"])
+        .args(args)
+        .env(
+            "CARGO_TARGET_DIR",
+            std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("feature-checks"),
+        )
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("Failed to run cargo");
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+#[test]
+#[ignore = "Runs cargo"]
+fn test_openmp_with_threadpool_fails_fast() {
+    let (success, stderr) = check_mnn_sys(&["--features", "openmp"]);
+    assert!(!success);
+    assert!(
+        stderr.contains("can't be built for")
+            && stderr.contains("openmp feature can't be used with the mnn-threadpool feature"),
+        "{stderr}"
+    );
+}
+
+#[cfg(not(target_vendor = "apple"))]
+#[test]
+#[ignore = "Runs cargo"]
+fn test_apple_only_features_fail_fast() {
+    let (success, stderr) = check_mnn_sys(&["--features", "metal,coreml,openmp"]);
+    assert!(!success);
+    // All the conflicts are listed
+    for feature in ["metal", "coreml", "openmp"] {
+        assert!(
+            stderr.contains(&format!("the {feature} feature")),
+            "{stderr}"
+        );
+    }
+}
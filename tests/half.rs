@@ -0,0 +1,39 @@
+#![cfg(feature = "half")]
+pub mod common;
+use common::*;
+use half::f16;
+
+fn run(net: &Interpreter, precision: PrecisionMode) -> Result<Vec<f32>> {
+    let config = ScheduleConfig::new()
+        .with_type(ForwardType::CPU)
+        .with_backend_config(BackendConfig::default().with_precision_mode(precision));
+    let session = net.create_session(config)?;
+    net.input::<f32>(&session, "data")?.fill(0.5);
+    net.run_session(&session)?;
+    let outputs = net.outputs(&session);
+    let output = outputs.iter().next().expect("No output");
+    // MNN converts the outputs of float16 sessions back to f32
+    assert!(output.tensor::<f16>().is_err());
+    Ok(output.tensor::<f32>()?.to_host()?.host().to_vec())
+}
+
+#[test]
+fn test_fp16_session_output_as_f16() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let reference = run(&net, PrecisionMode::High)?;
+    let low = run(&net, PrecisionMode::FP16)?;
+
+    let mut tensor = Tensor::<Host<f16>>::new([1, 3, 2048, 2048], DimensionType::NCHW);
+    tensor
+        .host_mut()
+        .iter_mut()
+        .zip(&low)
+        .for_each(|(h, &l)| *h = f16::from_f32(l));
+    let upcast = tensor.to_f32_vec()?;
+    assert_eq!(upcast.len(), reference.len());
+    upcast
+        .iter()
+        .zip(&reference)
+        .for_each(|(u, r)| assert!((u - r).abs() <= 0.05 + r.abs() * 0.01, "{u} != {r}"));
+    Ok(())
+}
@@ -0,0 +1,36 @@
+#![cfg(feature = "image")]
+pub mod common;
+use common::*;
+use image::{DynamicImage, Rgb, RgbImage};
+
+#[test]
+fn test_session_set_image() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let mut session = net.create_session(ScheduleConfig::new())?;
+    let img = DynamicImage::ImageRgb8(RgbImage::from_fn(64, 32, |x, y| {
+        Rgb([(x * 4) as u8, (y * 8) as u8, 128])
+    }));
+    let preprocess = Preprocess::new([0.485, 0.456, 0.406], [0.229, 0.224, 0.225]).with_bgr(true);
+    session.set_preprocess(preprocess);
+    assert_eq!(session.preprocess(), Some(&preprocess));
+
+    session.set_image("data", &img)?;
+    let first = net
+        .input::<f32>(&session, "data")?
+        .to_host()?
+        .host()
+        .to_vec();
+    net.input::<f32>(&session, "data")?.fill(0.0);
+    session.set_image("data", &img)?;
+    let second = net
+        .input::<f32>(&session, "data")?
+        .to_host()?
+        .host()
+        .to_vec();
+    assert_eq!(first, second);
+
+    let mut expected = Tensor::<Host<f32>>::new([1, 3, 512, 512], DimensionType::NCHW);
+    expected.copy_from_image_with(&img, &preprocess)?;
+    assert_eq!(first, expected.host());
+    Ok(())
+}
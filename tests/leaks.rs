@@ -0,0 +1,99 @@
+pub mod common;
+use common::*;
+
+#[test]
+fn test_borrowed_tensors_are_not_freed() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let session = net.create_session(ScheduleConfig::new())?;
+    // None of these own the tensors of the session, destroying them would free the inputs /
+    // outputs of the session
+    for _ in 0..1000 {
+        drop(net.input::<f32>(&session, "data")?);
+        drop(net.raw_input(&session, "data")?);
+        drop(
+            net.inputs(&session)
+                .iter()
+                .next()
+                .expect("No input")
+                .tensor::<f32>()?,
+        );
+        drop(session.input("data"));
+        drop(
+            net.outputs(&session)
+                .iter()
+                .next()
+                .expect("No output")
+                .tensor::<f32>()?,
+        );
+        drop(session.outputs());
+    }
+    net.input::<f32>(&session, "data")?.fill(1.0);
+    net.run_session(&session)?;
+
+    let expected = {
+        let session = net.create_session(ScheduleConfig::new())?;
+        net.input::<f32>(&session, "data")?.fill(1.0);
+        net.run_session(&session)?;
+        session.collect_outputs::<f32>()?
+    };
+    assert_eq!(session.collect_outputs::<f32>()?, expected);
+    Ok(())
+}
+
+#[test]
+fn test_session_destroy_then_drop() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let mut session = net.create_session(ScheduleConfig::new())?;
+    session.bind_input::<f32>("data")?;
+    session.destroy();
+    session.destroy();
+    drop(session);
+    Ok(())
+}
+
+/// Resident memory of the process in bytes
+#[cfg(target_os = "linux")]
+fn rss() -> usize {
+    let statm = std::fs::read_to_string("/proc/self/statm").expect("Failed to read statm");
+    let pages: usize = statm
+        .split_whitespace()
+        .nth(1)
+        .and_then(|pages| pages.parse().ok())
+        .expect("Failed to parse statm");
+    pages * unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_create_and_drop_doesnt_leak() -> Result<()> {
+    fn cycle(count: usize) -> Result<()> {
+        for _ in 0..count {
+            drop(Interpreter::from_bytes(Model::new())?);
+            drop(Tensor::<Host<f32>>::new(
+                [1, 3, 64, 64],
+                DimensionType::NCHW,
+            ));
+            drop(Runtime::new([ScheduleConfig::new()])?);
+        }
+        let net = Interpreter::from_bytes(Model::new())?;
+        for _ in 0..count / 20 {
+            let session = net.create_session(ScheduleConfig::new())?;
+            let input = net.input::<f32>(&session, "data")?;
+            drop(input.create_host_tensor_from_device(false));
+            drop(input);
+            drop(session);
+        }
+        Ok(())
+    }
+    // Let the allocators settle before measuring
+    cycle(100)?;
+    let before = rss();
+    cycle(2000)?;
+    let growth = rss().saturating_sub(before);
+    assert!(
+        growth < 64 * 1024 * 1024,
+        "Resident memory grew by {} MiB",
+        growth / 1024 / 1024
+    );
+    Ok(())
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "rayon")]
+pub mod common;
+use common::*;
+
+const MODEL: &str = "tests/assets/realesr.mnn";
+
+#[test]
+fn test_load_many() -> Result<()> {
+    let paths = [MODEL, MODEL, "tests/assets/missing.mnn", MODEL];
+    let models = Interpreter::load_many(&paths, &ScheduleConfig::new());
+    assert_eq!(models.len(), paths.len());
+    assert!(models[2].is_err());
+    for model in models.into_iter().filter_map(|model| model.ok()) {
+        let (net, session) = model;
+        net.input::<f32>(&session, "data")?.fill(0.5);
+        net.run_session(&session)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_load_many_with_runtime() -> Result<()> {
+    let runtime = Runtime::new([ScheduleConfig::new()])?;
+    let models = Interpreter::load_many_with_runtime(&[MODEL; 3], &ScheduleConfig::new(), &runtime);
+    let mut outputs = Vec::new();
+    for model in models {
+        let (net, session) = model?;
+        net.input::<f32>(&session, "data")?.fill(0.5);
+        net.run_session(&session)?;
+        outputs.push(session.collect_outputs::<f32>()?);
+    }
+    assert!(outputs.windows(2).all(|w| w[0] == w[1]));
+    Ok(())
+}
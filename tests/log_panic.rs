@@ -0,0 +1,20 @@
+pub mod common;
+use common::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[test]
+fn test_panicking_log_handler() {
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+    set_log_handler(|_| {
+        CALLS.fetch_add(1, Ordering::SeqCst);
+        panic!("log handler panicked");
+    });
+    // MNN logs an error for the invalid model, the panic must not unwind through MNN
+    assert!(Interpreter::from_bytes([0u8; 16]).is_err());
+    assert!(Interpreter::from_bytes([1u8; 16]).is_err());
+    clear_log_handler();
+    assert!(CALLS.load(Ordering::SeqCst) > 0);
+    // MNN still works afterwards
+    let net = Interpreter::from_bytes(Model::new()).expect("Failed to load model");
+    assert!(net.create_session(ScheduleConfig::new()).is_ok());
+}
@@ -0,0 +1,16 @@
+pub mod common;
+use common::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[test]
+fn test_log_handler_clears_itself() {
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+    set_log_handler(|_| {
+        CALLS.fetch_add(1, Ordering::SeqCst);
+        // Must not deadlock on the handler lock
+        clear_log_handler();
+    });
+    assert!(Interpreter::from_bytes([0u8; 16]).is_err());
+    assert!(Interpreter::from_bytes([1u8; 16]).is_err());
+    assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,18 @@
+pub mod common;
+use common::*;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn test_log_handler() {
+    let records = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&records);
+    set_log_handler(move |record| {
+        sink.lock()
+            .unwrap()
+            .push((record.level, record.message.to_string()));
+    });
+    assert!(Interpreter::from_bytes([0u8; 16]).is_err());
+    clear_log_handler();
+    let records = records.lock().unwrap();
+    assert!(records.iter().any(|(level, _)| *level == LogLevel::Error));
+}
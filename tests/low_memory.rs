@@ -0,0 +1,42 @@
+#![cfg(all(feature = "low-memory", target_os = "linux"))]
+pub mod common;
+use common::*;
+
+/// Peak resident memory of the process in bytes
+fn peak_rss() -> usize {
+    let status = std::fs::read_to_string("/proc/self/status").expect("Failed to read status");
+    let kib: usize = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))
+        .and_then(|value| value.trim().trim_end_matches("kB").trim().parse().ok())
+        .expect("Failed to parse VmHWM");
+    kib * 1024
+}
+
+#[test]
+fn test_low_memory_session() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let config = ScheduleConfig::new()
+        .with_type(ForwardType::CPU)
+        .with_backend_config(BackendConfig::new().with_memory_mode(MemoryMode::Low));
+    let mut session = net.create_session(config)?;
+    let mut input = net.input::<f32>(&session, "data")?;
+    net.resize_tensor(&mut input, [1, 3, 64, 64]);
+    drop(input);
+    net.resize_session(&mut session);
+    net.input::<f32>(&session, "data")?.fill(0.5);
+    net.run_session(&session)?;
+    let output = session.collect_outputs::<f32>()?;
+    assert!(
+        output
+            .values()
+            .all(|output| output.iter().all(|x| x.is_finite()))
+    );
+    // A 512MB board has to fit the os as well
+    assert!(
+        peak_rss() < 256 * 1024 * 1024,
+        "Peak resident memory was {} MiB",
+        peak_rss() / 1024 / 1024
+    );
+    Ok(())
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "mmap")]
+pub mod common;
+use common::*;
+use std::collections::HashMap;
+
+fn run(net: &Interpreter) -> Result<HashMap<String, Vec<f32>>> {
+    let session = net.create_session(ScheduleConfig::new())?;
+    net.input::<f32>(&session, "data")?.fill(0.5);
+    net.run_session(&session)?;
+    Ok(session.collect_outputs::<f32>()?)
+}
+
+#[test]
+fn test_mmap_matches_file() -> Result<()> {
+    let path = std::path::Path::new("tests/assets/realesr.mnn");
+    let expected = run(&Interpreter::from_file(path)?)?;
+    let net = Interpreter::create_from_mmap(path)?;
+    // Sessions created from the mapped model keep working for the whole lifetime of the
+    // interpreter
+    let session = net.create_session(ScheduleConfig::new())?;
+    assert_eq!(run(&net)?, expected);
+    net.input::<f32>(&session, "data")?.fill(0.5);
+    net.run_session(&session)?;
+    assert_eq!(session.collect_outputs::<f32>()?, expected);
+    drop(session);
+    drop(net);
+    Ok(())
+}
+
+#[test]
+fn test_mmap_missing_file() {
+    assert!(Interpreter::create_from_mmap("tests/assets/missing.mnn".as_ref()).is_err());
+}
@@ -0,0 +1,33 @@
+pub mod common;
+use common::*;
+
+#[test]
+fn test_module_forward_cpu() -> Result<()> {
+    let module = Module::load(
+        &["data"],
+        &[],
+        "tests/assets/realesr.mnn",
+        &ScheduleConfig::new(),
+    )?;
+    let input = VarP::new(
+        [1, 3, 512, 512],
+        vec![1.0f32; 3 * 512 * 512],
+        DimensionType::NCHW,
+    )?;
+    let outputs = module.forward(&[input])?;
+    assert_eq!(outputs.len(), 1);
+    assert_eq!(outputs[0].shape()?.dims(), [1, 3, 2048, 2048]);
+    assert_eq!(outputs[0].dims(), [1, 3, 2048, 2048]);
+    assert_eq!(outputs[0].host()?.len(), 3 * 2048 * 2048);
+    Ok(())
+}
+
+#[test]
+fn test_module_input_size_mismatch() {
+    assert!(VarP::new([1, 3, 4, 4], [0.0f32; 10], DimensionType::NCHW).is_err());
+}
+
+#[test]
+fn test_module_input_nc4hw4() {
+    assert!(VarP::new([1, 3, 4, 4], [0.0f32; 48], DimensionType::NC4HW4).is_err());
+}
@@ -0,0 +1,130 @@
+pub mod common;
+use common::*;
+use std::{cell::RefCell, collections::HashSet, rc::Rc};
+
+#[test]
+fn test_profile_report() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let mut session = net.create_session(ScheduleConfig::new())?;
+
+    let names = Rc::new(RefCell::new(HashSet::new()));
+    let seen = names.clone();
+    net.run_session_with_callback(
+        &session,
+        move |_, op| {
+            seen.borrow_mut().insert(op.name().to_owned());
+            true
+        },
+        |_, _| true,
+        true,
+    )?;
+
+    let input = vec![0.5f32; 3 * 512 * 512];
+    let report = session.profile([("data", input.as_slice())])?;
+    assert_eq!(report.ops.len(), names.borrow().len());
+    assert!(!report.approximate);
+    assert!(report.ops.windows(2).all(|w| w[0].time >= w[1].time));
+    assert_eq!(
+        report
+            .by_type
+            .iter()
+            .map(|(_, time)| *time)
+            .sum::<std::time::Duration>(),
+        report.total
+    );
+    assert!(session.profile([("data", &input[1..])]).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_save_tensors() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let create = |config: ScheduleConfig| -> Result<Session> {
+        let mut session = net.create_session(config)?;
+        session.resize(&[("data", vec![1, 3, 32, 32])].into())?;
+        net.input::<f32>(&session, "data")?.fill(0.5);
+        Ok(session)
+    };
+    let plain = create(ScheduleConfig::new())?;
+    let outputs: HashSet<String> = net
+        .outputs(&plain)
+        .iter()
+        .map(|x| x.name().into())
+        .collect();
+
+    // The op names of the model double as the names of the tensors they produce
+    let ops = Rc::new(RefCell::new(Vec::new()));
+    let seen = ops.clone();
+    net.run_session_with_callback(
+        &plain,
+        move |_, op| {
+            seen.borrow_mut()
+                .push(op.name().to_string_lossy().into_owned());
+            true
+        },
+        |_, _| true,
+        true,
+    )?;
+    let ops = ops.take();
+    let intermediate: Vec<&str> = ops
+        .iter()
+        .map(String::as_str)
+        .filter(|name| !outputs.contains(*name))
+        .collect();
+
+    let saving = create(ScheduleConfig::new().with_save_tensors(&intermediate)?)?;
+    net.run_session(&saving)?;
+    let saved: Vec<String> = net
+        .outputs(&saving)
+        .iter()
+        .map(|x| x.name().to_string())
+        .filter(|name| !outputs.contains(name))
+        .collect();
+    assert!(!saved.is_empty(), "No intermediate tensor was saved");
+    for name in &saved {
+        assert!(intermediate.contains(&name.as_str()));
+        let tensor = net.output::<f32>(&saving, name)?.to_host()?;
+        assert!(!tensor.host().is_empty());
+    }
+    for name in &outputs {
+        let expected = net.output::<f32>(&plain, name)?.to_host()?;
+        let got = net.output::<f32>(&saving, name)?.to_host()?;
+        assert_eq!(expected.host(), got.host());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_named_callback() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let mut session = net.create_session(ScheduleConfig::new())?;
+    session.resize(&[("data", vec![1, 3, 32, 32])].into())?;
+    net.input::<f32>(&session, "data")?.fill(0.5);
+
+    let dumped = Rc::new(RefCell::new(HashSet::new()));
+    let seen = dumped.clone();
+    net.run_session_with_named_callback(
+        &session,
+        |_, _| true,
+        move |tensors, names, _| {
+            assert!(names.len() <= tensors.len());
+            seen.borrow_mut()
+                .extend(names.iter().map(|name| name.to_string()));
+            true
+        },
+        true,
+    )?;
+    let dumped = dumped.take();
+
+    assert!(dumped.len() > 1);
+    assert!(dumped.iter().all(|name| !name.is_empty()));
+    // The final outputs are the last activations
+    for output in net.outputs(&session).iter() {
+        assert!(
+            dumped.contains(output.name()),
+            "{} wasn't dumped",
+            output.name()
+        );
+    }
+    Ok(())
+}
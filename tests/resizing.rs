@@ -4,13 +4,13 @@ use common::*;
 #[test]
 pub fn test_resizing() -> Result<()> {
     let model = std::fs::read("tests/assets/resizing.mnn").expect("No resizing model");
-    let mut net = Interpreter::from_bytes(&model).unwrap();
+    let net = Interpreter::from_bytes(&model).unwrap();
     net.set_cache_file("resizing.cache", 128)?;
     let config = ScheduleConfig::default();
     #[cfg(feature = "opencl")]
     config.set_type(ForwardType::OpenCL);
     let mut session = net.create_session(config).unwrap();
-    net.update_cache_file(&mut session)?;
+    net.update_cache_file(&session)?;
 
     let now = std::time::Instant::now();
     let mut mask = unsafe { net.input_unresized::<f32>(&session, "mask") }?;
@@ -42,3 +42,182 @@ pub fn test_resizing() -> Result<()> {
     println!("{:?}", now.elapsed());
     Ok(())
 }
+
+#[test]
+pub fn test_resize_input_nchw() -> Result<()> {
+    let net = Interpreter::from_file("tests/assets/realesr.mnn")?;
+    let mut session = net.create_session(ScheduleConfig::default())?;
+    net.resize_input_nchw(&session, "data", 1, 3, 32, 48)?;
+    assert!(net.run_session(&session).is_err());
+    net.resize_session(&mut session);
+    net.input::<f32>(&session, "data")?.fill(1.0);
+    net.run_session(&session)?;
+    let outputs = net.outputs(&session);
+    let output = outputs.iter().next().expect("No output");
+    assert_eq!(output.tensor::<f32>()?.shape().dims(), [1, 3, 128, 192]);
+    drop(outputs);
+
+    assert!(
+        net.resize_input_nchw(&session, "data", 1, 3, 0, 48)
+            .is_err()
+    );
+    assert!(
+        net.resize_input_nchw(&session, "missing", 1, 3, 32, 48)
+            .is_err()
+    );
+    let net = Interpreter::from_file("tests/assets/resizing.mnn")?;
+    let session = net.create_session(ScheduleConfig::default())?;
+    let err = net
+        .resize_input_nchw(&session, "mask", 1, 1, 32, 32)
+        .unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::TensorError));
+    Ok(())
+}
+
+#[test]
+pub fn test_resizing_batch() -> Result<()> {
+    let net = Interpreter::from_file("tests/assets/realesr.mnn")?;
+    let mut session = net.create_session(ScheduleConfig::default())?;
+    for batch in [2, 1] {
+        let mut input = net.input::<f32>(&session, "data")?;
+        let shape = input.shape();
+        net.resize_tensor(&mut input, [batch, shape[1], shape[2], shape[3]]);
+        drop(input);
+        // Forgetting to resize the session is caught before running
+        assert!(net.run_session(&session).is_err());
+
+        net.resize_session(&mut session);
+        net.input::<f32>(&session, "data")?.fill(1.0);
+        net.run_session(&session)?;
+        for output in net.outputs(&session).iter() {
+            assert_eq!(output.tensor::<f32>()?.shape()[0], batch);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+pub fn test_session_resize() -> Result<()> {
+    use std::collections::HashMap;
+    let model = std::fs::read("tests/assets/resizing.mnn").expect("No resizing model");
+    let net = Interpreter::from_bytes(&model)?;
+    let mut session = net.create_session(ScheduleConfig::default())?;
+
+    // Missing and unknown inputs are rejected before anything is resized
+    let partial = HashMap::from([("mask", vec![256, 256])]);
+    assert!(session.resize(&partial).is_err());
+    let unknown = HashMap::from([("mask", vec![256, 256]), ("unknown", vec![1])]);
+    assert!(session.resize(&unknown).is_err());
+
+    for size in [256, 512] {
+        let shapes = HashMap::from([
+            ("mask", vec![size, size]),
+            ("original", vec![size, size, 3]),
+            ("inpainted", vec![size, size, 3]),
+        ]);
+        session.resize(&shapes)?;
+        for (name, input) in session.inputs() {
+            assert_eq!(input.shape().as_ref(), shapes[name.as_str()].as_slice());
+        }
+        for name in shapes.keys() {
+            net.input::<f32>(&session, name)?.fill(1.0);
+        }
+        net.run_session(&session)?;
+    }
+    Ok(())
+}
+
+#[test]
+pub fn test_infer_shapes() -> Result<()> {
+    use std::collections::HashMap;
+    let model = std::fs::read("tests/assets/resizing.mnn").expect("No resizing model");
+    let net = Interpreter::from_bytes(&model)?;
+    let shapes = HashMap::from([
+        ("mask", vec![256, 256]),
+        ("original", vec![256, 256, 3]),
+        ("inpainted", vec![256, 256, 3]),
+    ]);
+    let inferred = net.infer_shapes(&shapes)?;
+    assert!(!inferred.is_empty());
+
+    let mut session = net.create_session(ScheduleConfig::default())?;
+    session.resize(&shapes)?;
+    for name in shapes.keys() {
+        net.input::<f32>(&session, name)?.fill(1.0);
+    }
+    net.run_session(&session)?;
+    let outputs = session.outputs();
+    assert_eq!(inferred.len(), outputs.len());
+    for (name, output) in outputs {
+        assert_eq!(inferred[&name].as_slice(), output.shape().as_ref());
+    }
+
+    // Missing inputs of a dynamic model can't be inferred
+    assert!(
+        net.infer_shapes(&HashMap::from([("mask", vec![256, 256])]))
+            .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+pub fn test_infer_shapes_keeps_resize_mode() -> Result<()> {
+    use std::collections::HashMap;
+    let model = std::fs::read("tests/assets/resizing.mnn").expect("No resizing model");
+    let mut net = Interpreter::from_bytes(&model)?;
+    net.set_session_mode(SessionMode::ResizeDefer);
+    net.infer_shapes(&HashMap::from([
+        ("mask", vec![256, 256]),
+        ("original", vec![256, 256, 3]),
+        ("inpainted", vec![256, 256, 3]),
+    ]))?;
+    // Still deferred, the session isn't resized when it's created
+    let session = net.create_session(ScheduleConfig::default())?;
+    assert_eq!(net.resize_status(&session)?, ResizeStatus::NeedResize);
+    Ok(())
+}
+
+#[test]
+pub fn test_resize_deferred() -> Result<()> {
+    use std::collections::HashMap;
+    let model = std::fs::read("tests/assets/resizing.mnn").expect("No resizing model");
+    let run = |net: &Interpreter, session: &Session| -> Result<HashMap<String, Vec<f32>>> {
+        for name in ["mask", "original", "inpainted"] {
+            net.input::<f32>(session, name)?.fill(0.5);
+        }
+        net.run_session(session)?;
+        Ok(session.collect_outputs::<f32>()?)
+    };
+
+    let net = Interpreter::from_bytes(&model)?;
+    let mut session = net.create_session(ScheduleConfig::default())?;
+    session.resize(&HashMap::from([
+        ("mask", vec![256, 256]),
+        ("original", vec![256, 256, 3]),
+        ("inpainted", vec![256, 256, 3]),
+    ]))?;
+    let expected = run(&net, &session)?;
+
+    let mut deferred = Interpreter::from_bytes(&model)?;
+    deferred.set_session_mode(SessionMode::ResizeDefer);
+    let mut session = deferred.create_session(ScheduleConfig::default())?;
+    // Nothing is resized until the queued shapes are applied
+    let before = session.input("mask").expect("No mask").shape().to_vec();
+    let queued = session
+        .resize_deferred()
+        .set_input("mask", [256, 256])
+        .set_input("original", [256, 256, 3]);
+    drop(queued);
+    assert_eq!(
+        session.input("mask").expect("No mask").shape().to_vec(),
+        before
+    );
+    session
+        .resize_deferred()
+        .set_input("mask", [256, 256])
+        .set_input("original", [256, 256, 3])
+        .set_input("inpainted", [256, 256, 3])
+        .resize()?;
+    assert_eq!(run(&deferred, &session)?, expected);
+    Ok(())
+}
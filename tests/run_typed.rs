@@ -0,0 +1,43 @@
+pub mod common;
+use common::*;
+
+fn session(net: &Interpreter) -> Result<Session> {
+    let mut session = net.create_session(ScheduleConfig::new())?;
+    session.resize(&[("data", vec![1, 3, 64, 64])].into())?;
+    Ok(session)
+}
+
+#[test]
+fn test_run_typed_f32() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let mut session = session(&net)?;
+    let input = vec![0.5f32; 3 * 64 * 64];
+
+    let outputs = session.run_typed([("data", input.as_slice().into())], false)?;
+    assert!(outputs.values().all(|output| output.as_f32().is_some()));
+    // Nothing is quantized for a float model
+    let quantized = session.run_typed([("data", input.as_slice().into())], true)?;
+    assert_eq!(outputs, quantized);
+    assert_eq!(outputs, session.run_typed([], true)?);
+    let err = session
+        .run_typed([("data", TypedInput::F32(&input[1..]))], false)
+        .unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::SizeMismatch { .. }));
+    Ok(())
+}
+
+#[test]
+fn test_run_typed_i8() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let mut session = session(&net)?;
+    let input = vec![64i8; 3 * 64 * 64];
+
+    // i8 data is never converted, so it doesn't fit the float input
+    for auto_quantize in [false, true] {
+        let err = session
+            .run_typed([("data", input.as_slice().into())], auto_quantize)
+            .unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::HalideTypeMismatch { .. }));
+    }
+    Ok(())
+}
@@ -0,0 +1,73 @@
+pub mod common;
+use common::*;
+
+#[test]
+fn test_shared_runtime() -> Result<()> {
+    let runtime = Runtime::new([ScheduleConfig::new()])?;
+    let a = Interpreter::from_file("tests/assets/realesr.mnn")?;
+    let b = Interpreter::from_bytes(Model::new())?;
+    let session_a = a.create_session_with_runtime(ScheduleConfig::new(), &runtime)?;
+    let session_b = b.create_session_with_runtime(ScheduleConfig::new(), &runtime)?;
+    for (net, session) in [(&a, &session_a), (&b, &session_b)] {
+        net.inputs(session).iter().for_each(|x| {
+            let mut tensor = x.tensor::<f32>().expect("No tensor");
+            tensor.fill(1.0f32);
+        });
+        net.run_session(session)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_runtime_without_configs() {
+    assert!(Runtime::new([]).is_err());
+}
+
+#[test]
+fn test_runtime_mode() -> Result<()> {
+    let runtime = Runtime::new([ScheduleConfig::new()])?;
+    runtime.set_mode(SessionMode::ResizeDefer);
+    let net = Interpreter::from_bytes(Model::new())?;
+    let session = net.create_session_with_runtime(ScheduleConfig::new(), &runtime)?;
+    assert_eq!(net.resize_status(&session)?, ResizeStatus::NeedResize);
+    // The mode of the interpreter is left unchanged
+    let session = net.create_session(ScheduleConfig::new())?;
+    assert_eq!(net.resize_status(&session)?, ResizeStatus::None);
+    Ok(())
+}
+
+#[test]
+fn test_runtime_sessions_from_threads() -> Result<()> {
+    let runtime = Runtime::new([ScheduleConfig::new()])?;
+    std::thread::scope(|scope| {
+        let handles = (0..4)
+            .map(|_| {
+                scope.spawn(|| -> Result<()> {
+                    let net = Interpreter::from_bytes(Model::new())?;
+                    let session =
+                        net.create_session_with_runtime(ScheduleConfig::new(), &runtime)?;
+                    net.run_session(&session)?;
+                    Ok(())
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .try_for_each(|handle| handle.join().expect("Thread panicked"))
+    })
+}
+
+#[test]
+fn test_runtime_cache() -> Result<()> {
+    let runtime = Runtime::new([ScheduleConfig::new()])?;
+    assert!(runtime.update_cache().is_err());
+    let path = std::env::temp_dir().join(format!("mnn-rs-runtime-cache-{}", std::process::id()));
+    runtime.set_cache(&path)?;
+    let net = Interpreter::from_bytes(Model::new())?;
+    let session = net.create_session_with_runtime(ScheduleConfig::new(), &runtime)?;
+    net.run_session(&session)?;
+    // The cpu backend has no cache to write
+    runtime.update_cache()?;
+    assert!(!path.exists());
+    Ok(())
+}
@@ -6,12 +6,12 @@ fn test_segfault_case_1_() -> Result<(), Box<dyn std::error::Error>> {
     let backend = ForwardType::OpenCL;
     let realesr = std::path::Path::new("tests/assets/realesr.mnn");
 
-    let mut net = mnn::Interpreter::from_file(realesr)?;
+    let net = mnn::Interpreter::from_file(realesr)?;
     net.set_cache_file(realesr.with_extension("cache"), 128)?;
     let mut config = ScheduleConfig::new();
     config.set_type(backend);
-    let mut session = net.create_session(config)?;
-    net.update_cache_file(&mut session)?;
+    let session = net.create_session(config)?;
+    net.update_cache_file(&session)?;
 
     net.inputs(&session).iter().for_each(|x| {
         let mut tensor = x.tensor::<f32>().expect("No tensor");
@@ -32,7 +32,7 @@ fn test_segfault_case_1_() -> Result<(), Box<dyn std::error::Error>> {
 pub fn test_resizing() {
     use mnn::*;
     let model = std::fs::read("tests/assets/resizing.mnn").expect("No resizing model");
-    let mut net = Interpreter::from_bytes(&model).unwrap();
+    let net = Interpreter::from_bytes(&model).unwrap();
     let config = ScheduleConfig::default();
     let mut session = net.create_session(config).unwrap();
 
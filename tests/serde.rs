@@ -0,0 +1,38 @@
+#![cfg(feature = "serde")]
+pub mod common;
+use common::*;
+
+#[test]
+fn test_schedule_config_json_round_trip() -> Result<()> {
+    let config = ScheduleConfig::new()
+        .with_type(ForwardType::CPU)
+        .with_backup_type(ForwardType::CPU)
+        .with_num_threads(2)
+        .with_backend_auto(true)
+        .with_backend_config(
+            BackendConfig::new()
+                .with_memory_mode(MemoryMode::Low)
+                .with_power_mode(PowerMode::High)
+                .with_precision_mode(PrecisionMode::Low),
+        );
+    let json = serde_json::to_string(&config)?;
+    let restored: ScheduleConfig = serde_json::from_str(&json)?;
+    assert_eq!(serde_json::to_string(&restored)?, json);
+    assert_eq!(restored.get_num_threads(), 2);
+    assert!(restored.get_backend_auto());
+
+    let net = Interpreter::from_bytes(Model::new())?;
+    let session = net.create_session(restored)?;
+    net.run_session(&session)?;
+    Ok(())
+}
+
+#[test]
+fn test_schedule_config_json_defaults() -> Result<()> {
+    let config: ScheduleConfig = serde_json::from_str(r#"{ "num_threads": 1 }"#)?;
+    let defaults = ScheduleConfig::new();
+    assert_eq!(config.get_type(), defaults.get_type());
+    assert_eq!(config.get_num_threads(), 1);
+    assert!(serde_json::to_value(&config)?["backend_config"].is_null());
+    Ok(())
+}
@@ -0,0 +1,95 @@
+pub mod common;
+use common::*;
+
+fn thread_number(session: &Session) -> Option<i32> {
+    match session.info(SessionInfoCode::ThreadNumber)? {
+        SessionInfo::ThreadNumber(threads) => Some(threads),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_global_thread_number() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let config = || ScheduleConfig::new().with_num_threads(8);
+    assert_eq!(global_thread_number(), None);
+    let uncapped = net.create_session(config())?;
+
+    set_global_thread_number(2);
+    assert_eq!(global_thread_number(), Some(2));
+    let capped = net.create_session(config())?;
+    let multipath = net.create_multipath_session([config(), config()])?;
+    let runtime = Runtime::new([config()])?;
+    let shared = net.create_session_with_runtime(config(), &runtime)?;
+    set_global_thread_number(0);
+
+    if let Some(threads) = thread_number(&capped) {
+        assert!(threads <= 2, "Capped session uses {threads} threads");
+        assert!(threads <= thread_number(&uncapped).unwrap_or(8));
+    }
+    for session in [&multipath, &shared] {
+        if let Some(threads) = thread_number(session) {
+            assert!(threads <= 2, "Capped session uses {threads} threads");
+        }
+    }
+    assert_eq!(
+        capped.backends().unwrap_or_default(),
+        uncapped.backends().unwrap_or_default()
+    );
+    net.input::<f32>(&capped, "data")?.fill(1.0);
+    net.run_session(&capped)?;
+    Ok(())
+}
+
+#[cfg(feature = "openmp")]
+#[test]
+fn test_omp_threads() -> Result<()> {
+    set_omp_threads(2);
+    assert_eq!(omp_threads(), 2);
+    let net = Interpreter::from_bytes(Model::new())?;
+    let session = net.create_session(ScheduleConfig::new().with_num_threads(2))?;
+    net.input::<f32>(&session, "data")?.fill(1.0);
+    net.run_session(&session)?;
+    assert!(omp_threads() <= 2, "OpenMP uses {} threads", omp_threads());
+    if let Some(threads) = thread_number(&session) {
+        assert!(threads <= 2, "Session uses {threads} threads");
+    }
+    set_omp_threads(0);
+    assert_eq!(omp_threads(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_sessions_for_run_concurrently() -> Result<()> {
+    let net = Interpreter::from_bytes(Model::new())?;
+    let mut sessions = net.sessions_for(4, ScheduleConfig::new().with_num_threads(1))?;
+    assert_eq!(sessions.len(), 4);
+    for (index, session) in sessions.iter_mut().enumerate() {
+        session.resize(&[("data", vec![1, 3, 32, 32])].into())?;
+        net.input::<f32>(session, "data")?.fill(index as f32 / 4.0);
+    }
+    let run = |session: &Session| -> Result<Vec<f32>> {
+        net.run_session(session)?;
+        let outputs = session.collect_outputs::<f32>()?;
+        Ok(outputs.into_values().next().ok_or("No output")?)
+    };
+    // The same sessions run one after the other
+    let expected = sessions.iter().map(run).collect::<Result<Vec<_>>>()?;
+    assert_ne!(expected[0], expected[3]);
+
+    let outputs = std::thread::scope(|scope| {
+        // Sessions aren't Sync, each thread owns its session
+        let handles = sessions
+            .into_iter()
+            .map(|session| scope.spawn(move || run(&session)))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("Session thread panicked"))
+            .collect::<Vec<_>>()
+    });
+    for (output, expected) in outputs.into_iter().zip(&expected) {
+        assert_eq!(&output?, expected);
+    }
+    Ok(())
+}
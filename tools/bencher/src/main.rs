@@ -331,7 +331,7 @@ pub fn generate_main(cli: Generate) -> Result<()> {
             inputs: Default::default(),
             outputs: Default::default(),
         };
-        let mut net = mnn::Interpreter::from_file(&model).cc(BenchError)?;
+        let net = mnn::Interpreter::from_file(&model).cc(BenchError)?;
         let sc = ScheduleConfig::new()
             .with_type(cli.forward)
             .with_backend_config(
@@ -565,21 +565,21 @@ pub fn bench(
     let not_terminal = !std::io::stdout().is_terminal();
 
     tracing::info!("Benching {:?}", sc);
-    let mut net = mnn::Interpreter::from_file(&model).cc(BenchError)?;
+    let net = mnn::Interpreter::from_file(&model).cc(BenchError)?;
 
     bar.set_message("Creating session without cache");
     not_terminal.then(|| eprintln!("Creating session without cache"));
-    let (mut uncached, initial_load_time) = timeit(|| {
+    let (uncached, initial_load_time) = timeit(|| {
         tracing::trace!("Creating session without cache");
         net.create_session(sc.clone())
     })
     .cc(BenchError)?;
     let temp_file = temp_file_path()?;
     net.set_cache_file(&temp_file, 128).cc(BenchError)?;
-    net.update_cache_file(&mut uncached).cc(BenchError)?;
+    net.update_cache_file(&uncached).cc(BenchError)?;
     drop(uncached);
     drop(net);
-    let mut net = mnn::Interpreter::from_file(&model).cc(BenchError)?;
+    let net = mnn::Interpreter::from_file(&model).cc(BenchError)?;
     net.set_cache_file(&temp_file, 128).cc(BenchError)?;
     bar.set_message("Creating session with cache");
     not_terminal.then(|| eprintln!("Creating session with cache"));